@@ -3,9 +3,10 @@ use aptos_dkg::constants::{
     BEST_CASE_N, BEST_CASE_THRESHOLD, DST_PVSS_TESTING_APP, WORST_CASE_N, WORST_CASE_THRESHOLD,
 };
 use aptos_dkg::pvss;
+use aptos_dkg::pvss::scrape::LowDegreeTest;
 use aptos_dkg::pvss::traits::transcript::Transcript;
 use aptos_dkg::pvss::traits::SecretSharingConfig;
-use aptos_dkg::pvss::{test_utils, ThresholdConfig};
+use aptos_dkg::pvss::{test_utils, ThresholdConfig, WeightedConfig};
 use criterion::measurement::WallTime;
 use criterion::{
     criterion_group, criterion_main, measurement::Measurement, BenchmarkGroup, Criterion,
@@ -22,6 +23,70 @@ pub fn all_groups(c: &mut Criterion) {
         &ThresholdConfig::new(WORST_CASE_THRESHOLD, WORST_CASE_N),
         c,
     );
+
+    pvss_group::<pvss::dleq_scrape::Transcript>(
+        &ThresholdConfig::new(BEST_CASE_THRESHOLD, BEST_CASE_N),
+        c,
+    );
+    pvss_group::<pvss::dleq_scrape::Transcript>(
+        &ThresholdConfig::new(WORST_CASE_THRESHOLD, WORST_CASE_N),
+        c,
+    );
+
+    pvss_group::<pvss::scrape::CompactTranscript>(
+        &ThresholdConfig::new(BEST_CASE_THRESHOLD, BEST_CASE_N),
+        c,
+    );
+    pvss_group::<pvss::scrape::CompactTranscript>(
+        &ThresholdConfig::new(WORST_CASE_THRESHOLD, WORST_CASE_N),
+        c,
+    );
+
+    pvss_group::<pvss::scrape_g1::Transcript>(
+        &ThresholdConfig::new(BEST_CASE_THRESHOLD, BEST_CASE_N),
+        c,
+    );
+    pvss_group::<pvss::scrape_g1::Transcript>(
+        &ThresholdConfig::new(WORST_CASE_THRESHOLD, WORST_CASE_N),
+        c,
+    );
+
+    pvss_group::<pvss::das::Transcript>(&ThresholdConfig::new(BEST_CASE_THRESHOLD, BEST_CASE_N), c);
+    pvss_group::<pvss::das::Transcript>(
+        &ThresholdConfig::new(WORST_CASE_THRESHOLD, WORST_CASE_N),
+        c,
+    );
+
+    pvss_group::<pvss::Weighted<pvss::scrape::Transcript>>(
+        &get_weighted_config_for_benchmarking(BEST_CASE_N),
+        c,
+    );
+    pvss_group::<pvss::Weighted<pvss::scrape::Transcript>>(
+        &get_weighted_config_for_benchmarking(WORST_CASE_N),
+        c,
+    );
+
+    pvss_group::<pvss::Weighted<pvss::dleq_scrape::Transcript>>(
+        &get_weighted_config_for_benchmarking(BEST_CASE_N),
+        c,
+    );
+    pvss_group::<pvss::Weighted<pvss::dleq_scrape::Transcript>>(
+        &get_weighted_config_for_benchmarking(WORST_CASE_N),
+        c,
+    );
+
+    scrape_low_degree_test_group(&ThresholdConfig::new(WORST_CASE_THRESHOLD, WORST_CASE_N), c);
+    scrape_verify_batch_group(&ThresholdConfig::new(BEST_CASE_THRESHOLD, BEST_CASE_N), c);
+}
+
+/// Returns a weighted config with about `total_weight / 10` players, whose weights range from 1 to 19,
+/// and where about a third of the total weight is needed to reconstruct.
+fn get_weighted_config_for_benchmarking(total_weight: usize) -> WeightedConfig {
+    let n = total_weight / 10;
+    let weights = (0..n).map(|i| i % 19 + 1).collect::<Vec<usize>>();
+    let w = weights.iter().sum::<usize>() / 3 + 1;
+
+    WeightedConfig::new(w, n, weights)
 }
 
 pub fn pvss_group<T: Transcript>(sc: &T::SecretSharingConfig, c: &mut Criterion) {
@@ -37,6 +102,31 @@ pub fn pvss_group<T: Transcript>(sc: &T::SecretSharingConfig, c: &mut Criterion)
     group.finish();
 }
 
+/// Compares SCRAPE verification with the Lagrange-based low-degree test against verification with the
+/// SCRAPE dual-code low-degree test.
+pub fn scrape_low_degree_test_group(sc: &ThresholdConfig, c: &mut Criterion) {
+    let name = pvss::scrape::Transcript::scheme_name();
+    let mut group = c.benchmark_group(format!("pvss/{}", name));
+
+    scrape_verify_with_low_degree_test::<WallTime>(sc, LowDegreeTest::Lagrange, &mut group);
+    scrape_verify_with_low_degree_test::<WallTime>(sc, LowDegreeTest::DualCode, &mut group);
+
+    group.finish();
+}
+
+/// Compares verifying a batch of SCRAPE transcripts from different dealers one by one against
+/// verifying them via `verify_batch`.
+pub fn scrape_verify_batch_group(sc: &ThresholdConfig, c: &mut Criterion) {
+    let name = pvss::scrape::Transcript::scheme_name();
+    let mut group = c.benchmark_group(format!("pvss/{}", name));
+
+    for num_trxs in [8, 32] {
+        scrape_verify_batch::<WallTime>(sc, num_trxs, &mut group);
+    }
+
+    group.finish();
+}
+
 fn pvss_deal<T: Transcript, M: Measurement>(
     sc: &T::SecretSharingConfig,
     g: &mut BenchmarkGroup<M>,
@@ -44,6 +134,7 @@ fn pvss_deal<T: Transcript, M: Measurement>(
     g.throughput(Throughput::Elements(sc.get_total_num_shares() as u64));
 
     let (pp, _, eks, _, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, _) = test_utils::setup_signing::<T>(sc);
     let mut rng = thread_rng();
 
     g.bench_function(format!("deal/{}", sc), move |b| {
@@ -52,7 +143,18 @@ fn pvss_deal<T: Transcript, M: Measurement>(
                 let s = T::InputSecret::generate(&mut rng);
                 (s, rng)
             },
-            |(s, mut rng)| T::deal(&sc, &pp, &eks, s, &DST_PVSS_TESTING_APP[..], &mut rng),
+            |(s, mut rng)| {
+                T::deal(
+                    &sc,
+                    &pp,
+                    &ssks[0],
+                    &eks,
+                    s,
+                    &sc.get_player(0),
+                    &DST_PVSS_TESTING_APP[..],
+                    &mut rng,
+                )
+            },
         )
     });
 }
@@ -62,16 +164,32 @@ fn pvss_aggregate<T: Transcript, M: Measurement>(
     g: &mut BenchmarkGroup<M>,
 ) {
     g.throughput(Throughput::Elements(sc.get_total_num_shares() as u64));
+
+    let (pp, _, eks, _, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, _) = test_utils::setup_signing::<T>(sc);
     let mut rng = thread_rng();
 
+    // Aggregation fails for transcripts with a common dealer, so we deal two with different dealers.
+    let trxs = (0..2)
+        .map(|i| {
+            T::deal(
+                &sc,
+                &pp,
+                &ssks[i],
+                &eks,
+                T::InputSecret::generate(&mut rng),
+                &sc.get_player(i),
+                &DST_PVSS_TESTING_APP[..],
+                &mut rng,
+            )
+        })
+        .collect::<Vec<T>>();
+
     g.bench_function(format!("aggregate/{}", sc), move |b| {
         b.iter_with_setup(
-            || {
-                let trx = T::generate(&sc, &mut rng);
-                (trx.clone(), trx)
-            },
+            || (trxs[0].clone(), trxs[1].clone()),
             |(mut first, second)| {
-                first.aggregate_with(&sc, &second);
+                first.aggregate_with(&sc, &second).unwrap();
             },
         )
     });
@@ -84,21 +202,126 @@ fn pvss_verify<T: Transcript, M: Measurement>(
     g.throughput(Throughput::Elements(sc.get_total_num_shares() as u64));
 
     let (pp, _, eks, _, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, spks) = test_utils::setup_signing::<T>(sc);
     let mut rng = thread_rng();
 
     g.bench_function(format!("verify/{}", sc), move |b| {
         b.iter_with_setup(
             || {
                 let s = T::InputSecret::generate(&mut rng);
-                T::deal(&sc, &pp, &eks, s, &DST_PVSS_TESTING_APP[..], &mut rng)
+                T::deal(
+                    &sc,
+                    &pp,
+                    &ssks[0],
+                    &eks,
+                    s,
+                    &sc.get_player(0),
+                    &DST_PVSS_TESTING_APP[..],
+                    &mut rng,
+                )
+            },
+            |trx| {
+                assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+            },
+        )
+    });
+}
+
+fn scrape_verify_with_low_degree_test<M: Measurement>(
+    sc: &ThresholdConfig,
+    ldt: LowDegreeTest,
+    g: &mut BenchmarkGroup<M>,
+) {
+    type T = pvss::scrape::Transcript;
+
+    g.throughput(Throughput::Elements(sc.get_total_num_shares() as u64));
+
+    let (pp, _, eks, _, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, spks) = test_utils::setup_signing::<T>(sc);
+    let mut rng = thread_rng();
+
+    let ldt_name = match ldt {
+        LowDegreeTest::Lagrange => "lagrange",
+        LowDegreeTest::DualCode => "dual-code",
+    };
+
+    g.bench_function(format!("verify-{}/{}", ldt_name, sc), move |b| {
+        b.iter_with_setup(
+            || {
+                let s = <T as Transcript>::InputSecret::generate(&mut rng);
+                T::deal(
+                    &sc,
+                    &pp,
+                    &ssks[0],
+                    &eks,
+                    s,
+                    &sc.get_player(0),
+                    &DST_PVSS_TESTING_APP[..],
+                    &mut rng,
+                )
             },
             |trx| {
-                assert!(trx.verify(&sc, &pp, &eks, &DST_PVSS_TESTING_APP[..]));
+                assert!(trx.verify_with_low_degree_test(
+                    &sc,
+                    &pp,
+                    &spks,
+                    &eks,
+                    &DST_PVSS_TESTING_APP[..],
+                    ldt
+                ));
             },
         )
     });
 }
 
+fn scrape_verify_batch<M: Measurement>(
+    sc: &ThresholdConfig,
+    num_trxs: usize,
+    g: &mut BenchmarkGroup<M>,
+) {
+    type T = pvss::scrape::Transcript;
+
+    g.throughput(Throughput::Elements(
+        (num_trxs * sc.get_total_num_shares()) as u64,
+    ));
+
+    let (pp, _, eks, _, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, spks) = test_utils::setup_signing::<T>(sc);
+    let mut rng = thread_rng();
+
+    let trxs = (0..num_trxs)
+        .map(|i| {
+            T::deal(
+                &sc,
+                &pp,
+                &ssks[i],
+                &eks,
+                <T as Transcript>::InputSecret::generate(&mut rng),
+                &sc.get_player(i),
+                &DST_PVSS_TESTING_APP[..],
+                &mut rng,
+            )
+        })
+        .collect::<Vec<T>>();
+
+    g.bench_function(format!("verify-each-of-{}/{}", num_trxs, sc), |b| {
+        b.iter(|| {
+            for trx in &trxs {
+                assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+            }
+        })
+    });
+
+    g.bench_function(format!("verify-batch-of-{}/{}", num_trxs, sc), |b| {
+        b.iter(|| {
+            assert_eq!(
+                T::verify_batch(&trxs, &sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]),
+                Ok(())
+            );
+        })
+    });
+}
+
 fn pvss_decrypt_own_share<T: Transcript, M: Measurement>(
     sc: &T::SecretSharingConfig,
     g: &mut BenchmarkGroup<M>,
@@ -106,13 +329,23 @@ fn pvss_decrypt_own_share<T: Transcript, M: Measurement>(
     g.throughput(Throughput::Elements(sc.get_total_num_shares() as u64));
 
     let (pp, dks, eks, _, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, _) = test_utils::setup_signing::<T>(sc);
     let mut rng = thread_rng();
 
     g.bench_function(format!("decrypt-share/{}", sc), move |b| {
         b.iter_with_setup(
             || {
                 let s = T::InputSecret::generate(&mut rng);
-                T::deal(&sc, &pp, &eks, s, &DST_PVSS_TESTING_APP[..], &mut rng)
+                T::deal(
+                    &sc,
+                    &pp,
+                    &ssks[0],
+                    &eks,
+                    s,
+                    &sc.get_player(0),
+                    &DST_PVSS_TESTING_APP[..],
+                    &mut rng,
+                )
             },
             |trx| {
                 trx.decrypt_own_share(&sc, &sc.get_player(0), &dks[0]);
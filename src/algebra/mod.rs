@@ -2,3 +2,4 @@ pub mod evaluation_domain;
 pub mod fft;
 pub mod lagrange;
 pub mod polynomials;
+pub mod reed_solomon;
@@ -0,0 +1,257 @@
+//! Unique decoding of Reed-Solomon codewords *in the exponent*: i.e., of evaluations $g^{y_i}$ where
+//! $y_i = f(\omega^i)$ for a polynomial $f$ of degree $< t$, except for a few wrong $y_i$'s.
+//!
+//! Since only the discrete logs $y_i$ form a Reed-Solomon codeword, decoders like Berlekamp-Welch,
+//! which solve a linear system in the $y_i$'s, do not apply. Instead, we look for the smallest set
+//! of evaluations whose removal leaves the others on a polynomial of degree $< t$, checking every
+//! candidate set with a dual code test.
+
+use crate::algebra::evaluation_domain::BatchEvaluationDomain;
+use crate::algebra::lagrange::lagrange_coefficients_at_zero;
+use crate::utils::{hash_to_scalar, HasMultiExp};
+use blstrs::Scalar;
+use ff::{BatchInvert, Field};
+use std::ops::{AddAssign, Mul, MulAssign};
+
+pub const REED_SOLOMON_DECODING_DST: &[u8; 31] = b"APTOS_REED_SOLOMON_DECODING_DST";
+
+/// Given $m$ evaluations $(i, g^{y_i})$ of a polynomial $f$ of degree $< t$ at distinct
+/// $\omega^i$'s, grouped into `blocks`, returns $g^{f(0)}$ together with the indices in `blocks` of
+/// the blocks that contain wrong evaluations (i.e., $y_i \ne f(\omega^i)$). A block is either
+/// right or wrong as a whole: e.g., all the sub-shares of a player in a weighted config form one
+/// block, since a malicious player can make all of them wrong at once.
+///
+/// Returns `None` if $m < t$, if more than `max_errors` blocks are wrong, or if the wrong blocks
+/// have more than $\lfloor (m - t) / 2 \rfloor$ evaluations in total.
+///
+/// Tries all sets of $0, 1, 2, \ldots, \mathtt{max\_errors}$ wrong blocks in turn, so, for $b$
+/// blocks, it computes up to $\sum_{k \le \mathtt{max\_errors}} \binom{b}{k}$ size-$m$ multiexps:
+/// `max_errors` should be a small constant.
+pub fn decode_in_exponent<Gr: HasMultiExp>(
+    dom: &BatchEvaluationDomain,
+    t: usize,
+    blocks: &[Vec<(usize, Gr)>],
+    max_errors: usize,
+) -> Option<(Gr, Vec<usize>)> {
+    let evals = blocks.concat();
+    let m = evals.len();
+    if m < t {
+        return None;
+    }
+
+    // The $k$th block's evaluations are at positions $[offsets[k], offsets[k + 1])$ in `evals`.
+    let mut offsets = vec![0];
+    for block in blocks {
+        offsets.push(offsets[offsets.len() - 1] + block.len());
+    }
+
+    let beta = dual_code_challenge(&evals);
+    let b = blocks.len();
+
+    for num_errors in 0..=max_errors.min(b) {
+        let mut errors = (0..num_errors).collect::<Vec<usize>>();
+        loop {
+            let num_wrong_evals = errors.iter().map(|&k| blocks[k].len()).sum::<usize>();
+
+            // Beyond (m - t) / 2 wrong evaluations, the decoded polynomial would not be unique.
+            if num_wrong_evals <= (m - t) / 2 {
+                let rest = (0..b)
+                    .filter(|k| !errors.contains(k))
+                    .flat_map(|k| offsets[k]..offsets[k + 1])
+                    .collect::<Vec<usize>>();
+
+                if is_low_degree(dom, t, &evals, &rest, &beta) {
+                    let ids = rest[..t]
+                        .iter()
+                        .map(|&i| evals[i].0)
+                        .collect::<Vec<usize>>();
+                    let bases = rest[..t].iter().map(|&i| evals[i].1).collect::<Vec<Gr>>();
+                    let lagr = lagrange_coefficients_at_zero(dom, ids.as_slice());
+
+                    return Some((Gr::multi_exp(&bases, &lagr), errors));
+                }
+            }
+
+            if !next_combination(&mut errors, b) {
+                break;
+            }
+        }
+    }
+
+    None
+}
+
+/// Checks that the evaluations at the positions in `rest` lie on a polynomial of degree $< t$ by
+/// checking that they are orthogonal to a random dual code word:
+///
+///   $\prod_{i \in rest} (g^{y_i})^{v_i r(\omega^i)} = 1$
+///
+/// where $v_i = 1 / \prod_{j \in rest, j \ne i} (\omega^i - \omega^j)$ and
+/// $r(X) = \sum_{k < |rest| - t} (\beta X)^k$.
+fn is_low_degree<Gr: HasMultiExp>(
+    dom: &BatchEvaluationDomain,
+    t: usize,
+    evals: &[(usize, Gr)],
+    rest: &[usize],
+    beta: &Scalar,
+) -> bool {
+    debug_assert!(rest.len() >= t);
+    if rest.len() == t {
+        return true;
+    }
+
+    let omegas = dom.get_all_roots_of_unity();
+    let xs = rest
+        .iter()
+        .map(|&i| omegas[evals[i].0])
+        .collect::<Vec<Scalar>>();
+
+    let mut v = xs
+        .iter()
+        .enumerate()
+        .map(|(i, x_i)| {
+            xs.iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(Scalar::one(), |acc, (_, x_j)| acc.mul(x_i - x_j))
+        })
+        .collect::<Vec<Scalar>>();
+    v.batch_invert();
+
+    let scalars = xs
+        .iter()
+        .zip(v.iter())
+        .map(|(x, v)| {
+            let beta_x = beta.mul(x);
+            let (mut r, mut term) = (Scalar::zero(), Scalar::one());
+            for _ in 0..rest.len() - t {
+                r.add_assign(&term);
+                term.mul_assign(&beta_x);
+            }
+
+            r.mul(v)
+        })
+        .collect::<Vec<Scalar>>();
+    let bases = rest.iter().map(|&i| evals[i].1).collect::<Vec<Gr>>();
+
+    Gr::multi_exp(&bases, &scalars) == Gr::identity()
+}
+
+/// Advances `c` to the next size-$|c|$ subset of $[0, m)$ in lexicographic order. Returns false if
+/// `c` was the last one.
+fn next_combination(c: &mut [usize], m: usize) -> bool {
+    let k = c.len();
+    for i in (0..k).rev() {
+        if c[i] < m - k + i {
+            c[i] += 1;
+            for j in i + 1..k {
+                c[j] = c[j - 1] + 1;
+            }
+
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Derives the challenge $\beta$ for the dual code tests by hashing all the evaluations, so that
+/// wrong evaluations cannot be picked to pass them.
+fn dual_code_challenge<Gr: HasMultiExp>(evals: &[(usize, Gr)]) -> Scalar {
+    let mut buf = vec![];
+    for (i, eval) in evals {
+        buf.extend((*i as u64).to_le_bytes());
+        buf.extend(eval.to_compressed_bytes());
+    }
+
+    hash_to_scalar(buf.as_slice(), REED_SOLOMON_DECODING_DST)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::algebra::evaluation_domain::BatchEvaluationDomain;
+    use crate::algebra::polynomials::poly_eval;
+    use crate::algebra::reed_solomon::decode_in_exponent;
+    use crate::utils::random::{random_g1_point, random_scalars};
+    use blstrs::G1Projective;
+    use group::Group;
+    use rand::thread_rng;
+
+    /// Puts every evaluation in a block of its own.
+    fn singletons(evals: &[(usize, G1Projective)]) -> Vec<Vec<(usize, G1Projective)>> {
+        evals.iter().map(|eval| vec![*eval]).collect()
+    }
+
+    #[test]
+    fn decode_in_exponent_corrects_errors() {
+        let mut rng = thread_rng();
+        let (t, n) = (4, 11);
+        let dom = BatchEvaluationDomain::new(n);
+
+        let f = random_scalars(t, &mut rng);
+        let g = G1Projective::generator();
+        let mut evals = (0..n)
+            .map(|i| (i, g * poly_eval(&f, &dom.get_root_of_unity(i))))
+            .collect::<Vec<(usize, G1Projective)>>();
+        let secret = g * f[0];
+
+        assert_eq!(
+            decode_in_exponent(&dom, t, &singletons(&evals), 3),
+            Some((secret, vec![]))
+        );
+
+        // Can correct up to (n - t) / 2 = 3 errors, wherever they are
+        for &i in [9, 2, 5].iter() {
+            evals[i].1 += random_g1_point(&mut rng);
+        }
+        assert_eq!(
+            decode_in_exponent(&dom, t, &singletons(&evals), 3),
+            Some((secret, vec![2, 5, 9]))
+        );
+
+        // ...also when only some of the evaluations are given
+        assert_eq!(
+            decode_in_exponent(&dom, t, &singletons(&evals[..9]), 3),
+            Some((secret, vec![2, 5]))
+        );
+
+        // ...but not more than asked for
+        assert_eq!(decode_in_exponent(&dom, t, &singletons(&evals), 2), None);
+
+        // ...nor more than (n - t) / 2
+        evals[0].1 += random_g1_point(&mut rng);
+        assert_eq!(decode_in_exponent(&dom, t, &singletons(&evals), n), None);
+    }
+
+    #[test]
+    fn decode_in_exponent_corrects_wrong_blocks() {
+        let mut rng = thread_rng();
+        let (t, n) = (4, 16);
+        let dom = BatchEvaluationDomain::new(n);
+
+        let f = random_scalars(t, &mut rng);
+        let g = G1Projective::generator();
+        let evals = (0..n)
+            .map(|i| (i, g * poly_eval(&f, &dom.get_root_of_unity(i))))
+            .collect::<Vec<(usize, G1Projective)>>();
+        let secret = g * f[0];
+
+        // A heavy block of 6 evaluations, followed by 10 blocks of one evaluation each
+        let mut blocks = vec![evals[..6].to_vec()];
+        blocks.extend(singletons(&evals[6..]));
+
+        // A wrong heavy block counts as a single error, as long as it has at most (n - t) / 2 = 6
+        // evaluations
+        for eval in blocks[0].iter_mut().skip(2) {
+            eval.1 += random_g1_point(&mut rng);
+        }
+        assert_eq!(
+            decode_in_exponent(&dom, t, &blocks, 1),
+            Some((secret, vec![0]))
+        );
+
+        // ...but not together with any other wrong evaluation
+        blocks[7][0].1 += random_g1_point(&mut rng);
+        assert_eq!(decode_in_exponent(&dom, t, &blocks, 2), None);
+    }
+}
@@ -0,0 +1,40 @@
+//! # Distributed key generation (DKG) on top of PVSS
+//!
+//! Every player deals a PVSS transcript of a random input secret. Each player collects the
+//! transcripts it receives, verifies them and aggregates the valid ones, which deals the sum of their
+//! input secrets. Once transcripts from a quorum of distinct dealers were aggregated, the player
+//! finalizes: it outputs the dealt public key, the dealt public key shares of all players and its
+//! own dealt secret key share.
+//!
+//! The quorum must be large enough to include at least one honest dealer, so that the dealt secret
+//! key is not known to any of the (malicious) dealers. In a weighted config, the adversary is bounded
+//! by the combined weight (i.e., stake) of the players it corrupts rather than by their number, so
+//! the quorum is measured in weight and must exceed that bound. In an unweighted config, every
+//! player has weight 1, so the quorum is simply a number of dealers.
+//!
+//! ## Resharing to a new committee
+//!
+//! Resharing the dealt secret key to a new committee, while keeping the dealt public key, is NOT
+//! supported by the PVSS schemes in this crate, so a new committee must run a fresh DKG instead.
+//!
+//! Resharing would have every old player $i$ deal a transcript of its share $f(\omega^i)$ to the new
+//! committee, after which the new players would combine the transcripts of $t$ old players with the
+//! Lagrange coefficients $\lambda_i$, so that the combined transcript deals
+//! $\sum_i \lambda_i f(\omega^i) = f(0)$ and has the same dealt public key. However:
+//!
+//!  1. An old player never learns its share $f(\omega^i)$ as a scalar. Decryption only gives it
+//!     its `DealtSecretKeyShare` $\hat{h}^{f(\omega^i)}$, which is a group element.
+//!  2. `Transcript::deal` takes an `InputSecret`, i.e., the scalar $a$, because every encrypted share
+//!     is $ek_j^{g(\omega^j)}$ for the dealt polynomial $g$ with $g(0) = a$. The old player could
+//!     compute the new shares $\hat{h}^{g(\omega^j)} = \hat{h}^{f(\omega^i)} \cdot
+//!     \hat{h}^{\omega^j r(\omega^j)}$ for $g(X) = f(\omega^i) + X r(X)$ in the exponent. But
+//!     encrypting such a group element $S_j$ to $ek_j = \hat{h}^{dk_j^{-1}}$ means computing
+//!     $S_j^{dk_j^{-1}}$, which takes the new player's decryption key $dk_j$.
+//!
+//! Supporting resharing would take an encryption scheme whose shares decrypt to scalars (e.g.,
+//! class-group-based encryption), so that old players can re-deal their shares as `InputSecret`s.
+
+mod session;
+pub mod simulator;
+
+pub use session::{DkgConfig, DkgError, DkgOutput, DkgSession};
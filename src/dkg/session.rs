@@ -0,0 +1,273 @@
+use crate::pvss::traits::{AggregationError, SecretSharingConfig, Transcript};
+use crate::pvss::Player;
+use more_asserts::{assert_gt, assert_le, assert_lt};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+/// The reasons why a `DkgSession` can reject a transcript or fail to finalize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DkgError {
+    /// This dealer already contributed to a previously-accepted transcript.
+    DuplicateDealer(Player),
+    /// The transcript does not verify.
+    InvalidTranscript,
+    /// The distinct dealers who contributed so far have a combined weight of only `got`, but a
+    /// combined weight of `needed` is required to finalize.
+    NoQuorum { got: usize, needed: usize },
+}
+
+impl Display for DkgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DkgError::DuplicateDealer(p) => {
+                write!(f, "dealer {} already contributed", p.get_id())
+            }
+            DkgError::InvalidTranscript => write!(f, "transcript does not verify"),
+            DkgError::NoQuorum { got, needed } => {
+                write!(
+                    f,
+                    "got dealers of weight {}, but need weight {} to finalize",
+                    got, needed
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DkgError {}
+
+impl From<AggregationError> for DkgError {
+    fn from(e: AggregationError) -> Self {
+        match e {
+            AggregationError::DuplicateDealer(p) => DkgError::DuplicateDealer(p),
+            AggregationError::InvalidSizes => DkgError::InvalidTranscript,
+        }
+    }
+}
+
+/// The public setup shared by all the players in a DKG.
+pub struct DkgConfig<T: Transcript> {
+    sc: T::SecretSharingConfig,
+    pp: T::PvssPublicParameters,
+    /// The signing public key of every player, in its role as a dealer.
+    spks: Vec<T::SigningPubKey>,
+    /// The encryption key of every player.
+    eks: Vec<T::EncryptPubKey>,
+    /// The domain separation tag that every dealer signs its transcript under.
+    dst: &'static [u8],
+    /// The minimum combined weight of the distinct dealers that must be aggregated before
+    /// finalizing (see `SecretSharingConfig::get_player_weight`). In an unweighted config, this is
+    /// simply the number of dealers.
+    quorum: usize,
+}
+
+impl<T: Transcript> DkgConfig<T> {
+    /// Panics if there is not one signing public key and one encryption key per player, or if the
+    /// `quorum` is not in $[1, W]$, where $W$ is the total weight of all players (i.e., $W = n$ in an
+    /// unweighted config).
+    pub fn new(
+        sc: T::SecretSharingConfig,
+        pp: T::PvssPublicParameters,
+        spks: Vec<T::SigningPubKey>,
+        eks: Vec<T::EncryptPubKey>,
+        dst: &'static [u8],
+        quorum: usize,
+    ) -> Self {
+        let n = sc.get_total_num_players();
+        assert_eq!(spks.len(), n);
+        assert_eq!(eks.len(), n);
+        assert_gt!(quorum, 0);
+        assert_le!(quorum, sc.get_total_num_shares());
+
+        DkgConfig {
+            sc,
+            pp,
+            spks,
+            eks,
+            dst,
+            quorum,
+        }
+    }
+
+    pub fn get_secret_sharing_config(&self) -> &T::SecretSharingConfig {
+        &self.sc
+    }
+
+    pub fn get_public_parameters(&self) -> &T::PvssPublicParameters {
+        &self.pp
+    }
+
+    pub fn get_signing_public_keys(&self) -> &Vec<T::SigningPubKey> {
+        &self.spks
+    }
+
+    pub fn get_encryption_keys(&self) -> &Vec<T::EncryptPubKey> {
+        &self.eks
+    }
+
+    pub fn get_dst(&self) -> &'static [u8] {
+        self.dst
+    }
+
+    pub fn get_quorum(&self) -> usize {
+        self.quorum
+    }
+}
+
+/// The output of a DKG for one player.
+pub struct DkgOutput<T: Transcript> {
+    /// The aggregate of all the transcripts the player accepted.
+    transcript: T,
+    dealt_pub_key: T::DealtPubKey,
+    /// The dealt public key shares of all players: i.e., `pk_shares[i]` is player $i$'s.
+    pk_shares: Vec<T::DealtPubKeyShare>,
+    /// The player's own dealt secret key share.
+    sk_share: T::DealtSecretKeyShare,
+}
+
+impl<T: Transcript> DkgOutput<T> {
+    pub fn get_transcript(&self) -> &T {
+        &self.transcript
+    }
+
+    /// Returns the dealers whose transcripts were aggregated.
+    pub fn get_dealers(&self) -> Vec<Player> {
+        self.transcript.get_dealers()
+    }
+
+    pub fn get_dealt_public_key(&self) -> &T::DealtPubKey {
+        &self.dealt_pub_key
+    }
+
+    pub fn get_public_key_shares(&self) -> &Vec<T::DealtPubKeyShare> {
+        &self.pk_shares
+    }
+
+    pub fn get_secret_key_share(&self) -> &T::DealtSecretKeyShare {
+        &self.sk_share
+    }
+}
+
+/// One player's view of a DKG: takes incoming transcripts as messages, verifies them and aggregates
+/// the valid ones, until it can finalize.
+pub struct DkgSession<'a, T: Transcript> {
+    config: &'a DkgConfig<T>,
+    /// The player running this session.
+    me: Player,
+    /// The player's decryption key, for decrypting its share once finalized.
+    dk: T::DecryptPrivKey,
+    /// The dealers who contributed to the transcripts accepted so far.
+    contributors: BTreeSet<usize>,
+    /// The aggregate of the transcripts accepted so far, if any.
+    aggregate: Option<T>,
+}
+
+impl<'a, T: Transcript> DkgSession<'a, T> {
+    pub fn new(config: &'a DkgConfig<T>, me: Player, dk: T::DecryptPrivKey) -> Self {
+        assert_lt!(me.get_id(), config.sc.get_total_num_players());
+
+        DkgSession {
+            config,
+            me,
+            dk,
+            contributors: BTreeSet::new(),
+            aggregate: None,
+        }
+    }
+
+    /// Deals this player's transcript of the input secret `s`, to be sent to all players (including
+    /// to itself, via `receive_transcript`).
+    pub fn deal<R: rand_core::RngCore + rand_core::CryptoRng>(
+        &self,
+        ssk: &T::SigningSecretKey,
+        s: T::InputSecret,
+        rng: &mut R,
+    ) -> T {
+        T::deal(
+            &self.config.sc,
+            &self.config.pp,
+            ssk,
+            &self.config.eks,
+            s,
+            &self.me,
+            self.config.dst,
+            rng,
+        )
+    }
+
+    /// Verifies an incoming transcript and, if valid, aggregates it. The transcript may itself be
+    /// an aggregate, but none of its dealers may have contributed to a previously-accepted one.
+    /// Rejected transcripts leave the session unchanged.
+    pub fn receive_transcript(&mut self, trx: T) -> Result<(), DkgError> {
+        let dealers = trx.get_dealers();
+        if let Some(dealer) = dealers
+            .iter()
+            .find(|d| self.contributors.contains(&d.get_id()))
+        {
+            return Err(DkgError::DuplicateDealer(dealer.clone()));
+        }
+
+        let c = self.config;
+        if !trx.verify(&c.sc, &c.pp, &c.spks, &c.eks, c.dst) {
+            return Err(DkgError::InvalidTranscript);
+        }
+
+        match &mut self.aggregate {
+            Some(agg) => agg.aggregate_with(&c.sc, &trx)?,
+            None => self.aggregate = Some(trx),
+        }
+
+        self.contributors
+            .extend(dealers.iter().map(|dealer| dealer.get_id()));
+        Ok(())
+    }
+
+    /// Returns the dealers who contributed to the transcripts accepted so far, sorted by ID.
+    pub fn get_contributors(&self) -> Vec<Player> {
+        self.contributors
+            .iter()
+            .map(|&id| self.config.sc.get_player(id))
+            .collect()
+    }
+
+    /// Returns the combined weight of the dealers who contributed to the transcripts accepted so
+    /// far.
+    pub fn get_contributed_weight(&self) -> usize {
+        self.get_contributors()
+            .iter()
+            .map(|dealer| self.config.sc.get_player_weight(dealer))
+            .sum()
+    }
+
+    pub fn has_quorum(&self) -> bool {
+        self.get_contributed_weight() >= self.config.quorum
+    }
+
+    /// Finalizes the DKG by decrypting this player's share from the aggregate of the accepted
+    /// transcripts. Fails if they do not come from a quorum of dealers.
+    pub fn finalize(self) -> Result<DkgOutput<T>, DkgError> {
+        let sc = &self.config.sc;
+        let weight = self.get_contributed_weight();
+        let transcript = match self.aggregate {
+            Some(agg) if weight >= self.config.quorum => agg,
+            _ => {
+                return Err(DkgError::NoQuorum {
+                    got: weight,
+                    needed: self.config.quorum,
+                })
+            }
+        };
+
+        let (sk_share, _) = transcript.decrypt_own_share(sc, &self.me, &self.dk);
+        let pk_shares = (0..sc.get_total_num_players())
+            .map(|i| transcript.get_public_key_share(sc, &sc.get_player(i)))
+            .collect();
+
+        Ok(DkgOutput {
+            dealt_pub_key: transcript.get_dealt_public_key(),
+            transcript,
+            pk_shares,
+            sk_share,
+        })
+    }
+}
@@ -0,0 +1,290 @@
+//! A deterministic, in-process simulator for running a DKG between $n$ players over an in-memory
+//! network, with message delays, message drops, crashed players and Byzantine dealers.
+//!
+//! Every live player deals its transcript at time 0 and sends it to every other live player. Each
+//! message is either dropped or delivered after a random delay, in increasing order of delivery
+//! time. Once all messages are delivered (or, if a deadline is set, once the deadline is reached),
+//! every honest player tries to finalize. All the randomness used for generating keys, for scheduling
+//! and for picking input secrets is derived from the simulator's seed, so a run can be replayed
+//! exactly.
+
+use crate::dkg::{DkgConfig, DkgError, DkgOutput, DkgSession};
+use crate::pvss::traits::{SecretSharingConfig, Transcript};
+use crate::pvss::{test_utils, Player};
+use aptos_crypto::Uniform;
+use more_asserts::{assert_le, assert_lt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How a Byzantine dealer misbehaves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByzantineBehavior {
+    /// Sends a random-looking but invalid transcript to every player.
+    Malformed,
+    /// Deals two valid transcripts of different input secrets and sends one of them to the
+    /// even-numbered players and the other one to the odd-numbered players.
+    Equivocating,
+}
+
+/// A transcript in flight from one player to another.
+struct Message<T> {
+    from: usize,
+    to: usize,
+    /// The time at which the message is delivered, in abstract ticks.
+    deliver_at: u64,
+    trx: T,
+}
+
+/// Sets up a DKG between all players of a secret sharing config and simulates it over a faulty,
+/// in-memory network.
+pub struct Simulator<T: Transcript> {
+    config: DkgConfig<T>,
+    dks: Vec<T::DecryptPrivKey>,
+    ssks: Vec<T::SigningSecretKey>,
+    rng: StdRng,
+    /// Every message is delayed by a uniform number of ticks in $[min, max]$.
+    delay: (u64, u64),
+    /// The probability with which a message between two distinct players is dropped.
+    drop_probability: f64,
+    /// If set, messages delivered after this time are ignored and players finalize at this time.
+    deadline: Option<u64>,
+    /// The players who neither send nor receive any message.
+    crashed: BTreeSet<usize>,
+    byzantine: BTreeMap<usize, ByzantineBehavior>,
+}
+
+impl<T: Transcript> Simulator<T> {
+    /// Sets up a reliable network with no delays, no crashed players and no Byzantine dealers,
+    /// between all players of `sc`, which finalize once dealers of combined weight `quorum` are
+    /// aggregated.
+    pub fn new(sc: T::SecretSharingConfig, quorum: usize, dst: &'static [u8], seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (pp, dks, eks, _, _) = test_utils::setup_dealing_with_rng::<T, _>(&sc, &mut rng);
+        let (ssks, spks) = test_utils::setup_signing_with_rng::<T, _>(&sc, &mut rng);
+
+        Simulator {
+            config: DkgConfig::new(sc, pp, spks, eks, dst, quorum),
+            dks,
+            ssks,
+            rng,
+            delay: (0, 0),
+            drop_probability: 0.0,
+            deadline: None,
+            crashed: BTreeSet::new(),
+            byzantine: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_config(&self) -> &DkgConfig<T> {
+        &self.config
+    }
+
+    /// Delays every message by a uniform number of ticks in $[min, max]$.
+    pub fn set_delays(&mut self, min: u64, max: u64) {
+        assert_le!(min, max);
+        self.delay = (min, max);
+    }
+
+    /// Drops every message between two distinct players with probability `p`.
+    pub fn set_drop_probability(&mut self, p: f64) {
+        assert!((0.0..=1.0).contains(&p));
+        self.drop_probability = p;
+    }
+
+    /// Ignores messages delivered after time `deadline`, at which point all players finalize.
+    pub fn set_deadline(&mut self, deadline: u64) {
+        self.deadline = Some(deadline);
+    }
+
+    /// Crashes `player` from the start: it neither deals nor receives.
+    pub fn crash(&mut self, player: &Player) {
+        assert_lt!(player.get_id(), self.dks.len());
+        self.byzantine.remove(&player.get_id());
+        self.crashed.insert(player.get_id());
+    }
+
+    /// Makes `player` a Byzantine dealer, which does not take part in the DKG other than by sending
+    /// transcripts according to `behavior`.
+    pub fn make_byzantine(&mut self, player: &Player, behavior: ByzantineBehavior) {
+        assert_lt!(player.get_id(), self.dks.len());
+        self.crashed.remove(&player.get_id());
+        self.byzantine.insert(player.get_id(), behavior);
+    }
+
+    /// Returns the players who are neither crashed nor Byzantine, sorted by ID.
+    pub fn get_honest_players(&self) -> Vec<Player> {
+        let sc = self.config.get_secret_sharing_config();
+
+        (0..sc.get_total_num_players())
+            .filter(|i| !self.crashed.contains(i) && !self.byzantine.contains_key(i))
+            .map(|i| sc.get_player(i))
+            .collect()
+    }
+
+    /// Runs the DKG and reports on the outcome of every honest player.
+    pub fn run(mut self) -> SimulationReport<T> {
+        let n = self.dks.len();
+        let honest = self
+            .get_honest_players()
+            .iter()
+            .map(|p| p.get_id())
+            .collect::<BTreeSet<usize>>();
+
+        // Every non-crashed dealer deals, with each message picking its delay (or getting dropped)
+        // in order of (sender, recipient), so that the schedule only depends on the seed.
+        let mut messages = vec![];
+        let mut num_dropped = 0;
+        for from in (0..n).filter(|i| !self.crashed.contains(i)) {
+            let trxs = self.deal(from);
+
+            for to in (0..n).filter(|i| !self.crashed.contains(i)) {
+                if from != to && self.rng.gen_bool(self.drop_probability) {
+                    num_dropped += 1;
+                    continue;
+                }
+
+                let deliver_at = if from == to {
+                    0
+                } else {
+                    self.rng.gen_range(self.delay.0, self.delay.1 + 1)
+                };
+
+                messages.push(Message {
+                    from,
+                    to,
+                    deliver_at,
+                    trx: trxs[to % trxs.len()].clone(),
+                });
+            }
+        }
+        // Stable, so that messages delivered at the same time keep their send order.
+        messages.sort_by_key(|m| m.deliver_at);
+
+        let config = &self.config;
+        let sc = config.get_secret_sharing_config();
+        let mut sessions = self
+            .dks
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| honest.contains(i))
+            .map(|(i, dk)| (i, DkgSession::new(config, sc.get_player(i), dk)))
+            .collect::<BTreeMap<usize, DkgSession<T>>>();
+
+        let deadline = self.deadline.unwrap_or(u64::MAX);
+        let mut num_delivered = 0;
+        let mut rejected = BTreeMap::new();
+        for m in messages
+            .into_iter()
+            .take_while(|m| m.deliver_at <= deadline)
+        {
+            // Messages to Byzantine players are delivered, but ignored.
+            num_delivered += 1;
+            if let Some(session) = sessions.get_mut(&m.to) {
+                if let Err(e) = session.receive_transcript(m.trx) {
+                    rejected
+                        .entry(m.to)
+                        .or_insert_with(Vec::new)
+                        .push((sc.get_player(m.from), e));
+                }
+            }
+        }
+
+        let outputs = sessions
+            .into_iter()
+            .map(|(i, session)| (i, session.finalize()))
+            .collect();
+
+        SimulationReport {
+            outputs,
+            rejected,
+            num_delivered,
+            num_dropped,
+        }
+    }
+
+    /// Returns the transcripts sent by dealer `i`, where player $j$ is sent `trxs[j % trxs.len()]`.
+    fn deal(&mut self, i: usize) -> Vec<T> {
+        let c = &self.config;
+        let sc = c.get_secret_sharing_config();
+        let dealer = sc.get_player(i);
+        let deal = |rng: &mut StdRng| {
+            T::deal(
+                sc,
+                c.get_public_parameters(),
+                &self.ssks[i],
+                c.get_encryption_keys(),
+                T::InputSecret::generate(rng),
+                &dealer,
+                c.get_dst(),
+                rng,
+            )
+        };
+
+        match self.byzantine.get(&i) {
+            None => vec![deal(&mut self.rng)],
+            Some(ByzantineBehavior::Malformed) => vec![T::generate(sc, &mut self.rng)],
+            Some(ByzantineBehavior::Equivocating) => {
+                vec![deal(&mut self.rng), deal(&mut self.rng)]
+            }
+        }
+    }
+}
+
+/// The outcome of a simulated DKG.
+pub struct SimulationReport<T: Transcript> {
+    /// The result of finalizing, for every honest player.
+    outputs: BTreeMap<usize, Result<DkgOutput<T>, DkgError>>,
+    /// For every honest player, the transcripts it rejected, as (sender, reason) pairs, in the order
+    /// they were received.
+    rejected: BTreeMap<usize, Vec<(Player, DkgError)>>,
+    num_delivered: usize,
+    num_dropped: usize,
+}
+
+impl<T: Transcript> SimulationReport<T> {
+    /// Returns the result of finalizing for the honest player with ID `i`.
+    pub fn get_output(&self, i: usize) -> &Result<DkgOutput<T>, DkgError> {
+        &self.outputs[&i]
+    }
+
+    pub fn get_outputs(&self) -> &BTreeMap<usize, Result<DkgOutput<T>, DkgError>> {
+        &self.outputs
+    }
+
+    /// Returns the transcripts the honest player with ID `i` rejected, as (sender, reason) pairs.
+    pub fn get_rejected(&self, i: usize) -> &[(Player, DkgError)] {
+        self.rejected.get(&i).map_or(&[], |r| r.as_slice())
+    }
+
+    pub fn get_num_delivered(&self) -> usize {
+        self.num_delivered
+    }
+
+    pub fn get_num_dropped(&self) -> usize {
+        self.num_dropped
+    }
+
+    /// Returns the dealt public key that all honest players finalized to, or `None` if one of them
+    /// failed to finalize or if two of them finalized to different keys.
+    pub fn get_agreed_public_key(&self) -> Option<&T::DealtPubKey>
+    where
+        T::DealtPubKey: PartialEq,
+    {
+        let mut pks = self.outputs.values().map(|output| match output {
+            Ok(output) => Some(output.get_dealt_public_key()),
+            Err(_) => None,
+        });
+
+        let first = pks.next()??;
+        pks.all(|pk| pk == Some(first)).then_some(first)
+    }
+
+    /// Returns true if all honest players finalized to the same dealt public key.
+    pub fn honest_players_agree(&self) -> bool
+    where
+        T::DealtPubKey: PartialEq,
+    {
+        self.get_agreed_public_key().is_some()
+    }
+}
@@ -3,5 +3,6 @@ pub use constants::{DST_RAND_CORE_HELL, G1_PROJ_NUM_BYTES, G2_PROJ_NUM_BYTES, SC
 
 pub mod algebra;
 pub mod constants;
+pub mod dkg;
 pub mod pvss;
 pub mod utils;
@@ -0,0 +1,257 @@
+use crate::pvss::schnorr;
+use crate::pvss::scrape::sok::{sok_batch_verify, SoK};
+use crate::pvss::Player;
+use aptos_crypto::bls12381;
+use aptos_crypto::{SigningKey, Uniform};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use blstrs::{G1Projective, Scalar};
+use ff::Field;
+use group::Group;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::Mul;
+
+/// A dealer's contribution to a (possibly-aggregated) PVSS transcript: the dealer's identity, a
+/// commitment $g_1^a$ to the input secret $a$ it dealt, a Schnorr PoK of $a$ bound to the dealer's
+/// identity and to the DST, and the dealer's signature on all of the above.
+///
+/// Without the PoK, the last dealer could pick its transcript as a function of the others' and
+/// bias the aggregated dealt public key (e.g., by dealing $g_1^a$ minus their sum). The signature
+/// lets us tell which dealers an aggregated transcript was aggregated from.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Contribution {
+    /// The player who dealt this contribution
+    pub(crate) dealer: Player,
+    /// The commitment $g_1^a$ to the dealer's input secret $a$
+    pub(crate) comm: G1Projective,
+    /// A Schnorr PoK of $a$
+    pub(crate) pok: schnorr::PoK<G1Projective>,
+    /// The dealer's signature on a `SignedContribution`
+    pub(crate) sig: bls12381::Signature,
+}
+
+/// The message a dealer signs over: its identity, its commitment $g_1^a$ and the DST.
+#[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+struct SignedContribution {
+    dealer: Player,
+    comm: G1Projective,
+    dst: Vec<u8>,
+}
+
+impl Contribution {
+    /// Creates a contribution for the input secret `a`, committed as $g_1^a$, proven and signed
+    /// with `ssk` on behalf of `dealer`.
+    pub(crate) fn new<R: rand_core::RngCore + rand_core::CryptoRng>(
+        g1: &G1Projective,
+        a: &Scalar,
+        ssk: &bls12381::PrivateKey,
+        dealer: &Player,
+        dst: &[u8],
+        rng: &mut R,
+    ) -> Self {
+        let comm = g1.mul(a);
+        let pok = schnorr::pok_prove(a, g1, &comm, &pok_context(dealer, dst), rng);
+        let sig = ssk
+            .sign(&SignedContribution {
+                dealer: dealer.clone(),
+                comm,
+                dst: dst.to_vec(),
+            })
+            .expect("signing a PVSS contribution should not fail");
+
+        Contribution {
+            dealer: dealer.clone(),
+            comm,
+            pok,
+            sig,
+        }
+    }
+
+    pub fn get_dealer(&self) -> &Player {
+        &self.dealer
+    }
+}
+
+/// Appends the contributions and SoKs in `other_contribs` and `other_soks` to the ones in `contribs`
+/// and `soks`, returning the first dealer who contributed to both sides, if any. Leaves `contribs` and
+/// `soks` unchanged on error. Transcripts without SoKs pass empty ones.
+pub(crate) fn merge_contributors(
+    contribs: &mut Vec<Contribution>,
+    soks: &mut Vec<SoK>,
+    other_contribs: &[Contribution],
+    other_soks: &[SoK],
+) -> Result<(), Player> {
+    let dealers = contribs
+        .iter()
+        .map(|c| c.get_dealer().get_id())
+        .chain(soks.iter().map(|sok| sok.get_dealer().get_id()))
+        .collect::<HashSet<usize>>();
+
+    if let Some(dealer) = other_contribs
+        .iter()
+        .map(|c| c.get_dealer())
+        .chain(other_soks.iter().map(|sok| sok.get_dealer()))
+        .find(|dealer| dealers.contains(&dealer.get_id()))
+    {
+        return Err(dealer.clone());
+    }
+
+    contribs.extend(other_contribs.iter().cloned());
+    soks.extend(other_soks.iter().cloned());
+    Ok(())
+}
+
+/// Returns true if there is at least one contributor (via either a `Contribution` or an `SoK`), if no
+/// dealer contributed twice, if the contributors' commitments add up to `F_0` (i.e., to the dealt
+/// public key) and if all their PoKs, signatures and SoKs verify. The signing public key of dealer
+/// $i$ is `spks[i]`.
+#[allow(non_snake_case)]
+pub(crate) fn verify_contributors(
+    g1: &G1Projective,
+    F_0: &G1Projective,
+    contribs: &[Contribution],
+    soks: &[SoK],
+    spks: &Vec<bls12381::PublicKey>,
+    dst: &'static [u8],
+) -> bool {
+    contributors_add_up(F_0, contribs, soks, spks)
+        && verify_contributor_proofs(g1, contribs, soks, spks, dst)
+}
+
+/// Returns true if there is at least one contributor, if no dealer contributed twice, if all dealers
+/// have a signing public key in `spks` and if the contributors' commitments add up to `F_0`. Does
+/// not verify any PoKs, signatures or SoKs; see `verify_contributor_proofs`.
+#[allow(non_snake_case)]
+pub(crate) fn contributors_add_up(
+    F_0: &G1Projective,
+    contribs: &[Contribution],
+    soks: &[SoK],
+    spks: &Vec<bls12381::PublicKey>,
+) -> bool {
+    if !contributors_are_distinct(contribs, soks, spks) {
+        return false;
+    }
+
+    let sum: G1Projective = contribs
+        .iter()
+        .map(|c| c.comm)
+        .chain(soks.iter().map(|sok| sok.comm))
+        .sum();
+    sum == *F_0
+}
+
+/// Returns true if there is at least one contributor, if no dealer contributed twice and if all
+/// dealers have a signing public key in `spks`.
+pub(crate) fn contributors_are_distinct(
+    contribs: &[Contribution],
+    soks: &[SoK],
+    spks: &Vec<bls12381::PublicKey>,
+) -> bool {
+    if contribs.is_empty() && soks.is_empty() {
+        return false;
+    }
+
+    let mut dealers = HashSet::with_capacity(contribs.len() + soks.len());
+    contribs
+        .iter()
+        .map(|c| c.get_dealer())
+        .chain(soks.iter().map(|sok| sok.get_dealer()))
+        .all(|dealer| dealer.get_id() < spks.len() && dealers.insert(dealer.get_id()))
+}
+
+/// Returns true if all the PoKs, signatures and SoKs of the contributors verify. The caller must first
+/// check that all dealers have a signing public key in `spks` (e.g., via `contributors_are_distinct`).
+/// The contributors need not come from the same transcript, which lets us verify the contributors of
+/// many transcripts at once.
+pub(crate) fn verify_contributor_proofs(
+    g1: &G1Projective,
+    contribs: &[Contribution],
+    soks: &[SoK],
+    spks: &Vec<bls12381::PublicKey>,
+    dst: &'static [u8],
+) -> bool {
+    if !contribs.is_empty() && !verify_contribution_proofs(g1, contribs, spks, dst) {
+        return false;
+    }
+
+    soks.is_empty() || sok_batch_verify(g1, soks, spks, dst)
+}
+
+/// Returns true if all the PoKs and signatures in `contribs` verify. The signing public key of dealer
+/// $i$ is `spks[i]`, so the caller must first check that all dealers have one.
+fn verify_contribution_proofs(
+    g1: &G1Projective,
+    contribs: &[Contribution],
+    spks: &Vec<bls12381::PublicKey>,
+    dst: &[u8],
+) -> bool {
+    let pks = contribs
+        .iter()
+        .map(|c| c.comm)
+        .collect::<Vec<G1Projective>>();
+    let poks = contribs
+        .iter()
+        .map(|c| c.pok)
+        .collect::<Vec<schnorr::PoK<G1Projective>>>();
+    let ctxs = contribs
+        .iter()
+        .map(|c| pok_context(&c.dealer, dst))
+        .collect::<Vec<Vec<u8>>>();
+
+    if !schnorr::pok_batch_verify(g1, &pks, &poks, &ctxs) {
+        return false;
+    }
+
+    // Verify all the signatures at once, by aggregating them
+    let msgs = contribs
+        .iter()
+        .map(|c| SignedContribution {
+            dealer: c.dealer.clone(),
+            comm: c.comm,
+            dst: dst.to_vec(),
+        })
+        .collect::<Vec<SignedContribution>>();
+    let msgs_refs = msgs.iter().collect::<Vec<&SignedContribution>>();
+    let pks_refs = contribs
+        .iter()
+        .map(|c| &spks[c.dealer.get_id()])
+        .collect::<Vec<&bls12381::PublicKey>>();
+    let sigs = contribs
+        .iter()
+        .map(|c| c.sig.clone())
+        .collect::<Vec<bls12381::Signature>>();
+
+    match bls12381::Signature::aggregate(sigs) {
+        Ok(multisig) => multisig.verify_aggregate(&msgs_refs, &pks_refs).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Returns a random-looking (but invalid) contribution. Useful for generating random-looking
+/// transcripts in tests and benchmarks.
+pub(crate) fn random_contribution<R: rand_core::RngCore + rand_core::CryptoRng>(
+    dealer: &Player,
+    comm: &G1Projective,
+    rng: &mut R,
+) -> Contribution {
+    let ssk = bls12381::PrivateKey::generate(rng);
+    let sig = ssk
+        .sign(&SignedContribution {
+            dealer: dealer.clone(),
+            comm: *comm,
+            dst: vec![],
+        })
+        .expect("signing a PVSS contribution should not fail");
+
+    Contribution {
+        dealer: dealer.clone(),
+        comm: *comm,
+        pok: (comm.double(), Scalar::one()),
+        sig,
+    }
+}
+
+/// The context a dealer's PoK is bound to: the DST and the dealer's identity.
+fn pok_context(dealer: &Player, dst: &[u8]) -> Vec<u8> {
+    bcs::to_bytes(&(dst, dealer)).expect("unexpected error during PoK context serialization")
+}
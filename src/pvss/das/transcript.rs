@@ -1,17 +1,494 @@
-// use blstrs::G2Projective;
-// use serde::{Deserialize, Serialize};
-
-// #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-// #[allow(non_snake_case)]
-// pub struct Transcript {
-//     /// ElGamal encryption randomness $g_2^r \in G_2$
-//     hat_w: G2Projective,
-//     /// Commitments to the $n$ evaluations of $p(X)$: $g_2^{p(\omega^i)}$
-//     V: Vec<G2Projective>,
-//     /// ElGamal encryptions of the shares:
-//     ///  - $C_0 = g_1^r$
-//     ///  - $C_i = $h_1^{p(\omega^i)} ek^r, \forall i \in [n]$
-//     C: Vec<G2Projective>,
-// }
-//
-// // TODO(Performance): for verification, can we get any speed-ups when a lot of the PKs are the same?
+use crate::algebra::fft::fft;
+use crate::pvss::contribution::{
+    contributors_are_distinct, merge_contributors, random_contribution, verify_contributor_proofs,
+    Contribution,
+};
+use crate::pvss::das;
+use crate::pvss::decryption_proof::{self, VerifiableDecryption};
+use crate::pvss::dleq;
+use crate::pvss::encryption_dlog;
+use crate::pvss::player::Player;
+use crate::pvss::scrape::transcript::dual_code_low_degree_test;
+use crate::pvss::sk_in_g1::das::FiatShamirProtocol;
+use crate::pvss::threshold_config::ThresholdConfig;
+use crate::pvss::traits;
+use crate::pvss::traits::{AggregationError, SecretSharingConfig};
+use crate::utils::random::{random_g1_point, random_g2_point, random_scalar, random_scalars};
+use aptos_crypto::{bls12381, CryptoMaterialError, ValidCryptoMaterial};
+use blstrs::{Bls12, G1Affine, G1Projective, G2Prepared, G2Projective, Gt, Scalar};
+use ff::Field;
+use group::{Curve, Group};
+use pairing::{MillerLoopResult, MultiMillerLoop};
+use serde::{Deserialize, Serialize};
+use std::ops::{Mul, Neg};
+
+/// A DAS PVSS *transcript*, where the shares are ElGamal-encrypted in $\mathbb{G}_1$ and committed
+/// to in $\mathbb{G}_2$.
+///
+/// Compared to SCRAPE, the dealt secret key $h_1^a$ lives in $\mathbb{G}_1$ and, since all
+/// ciphertexts share the same ElGamal randomness $r$, verification only needs a constant number of
+/// pairings (plus multiexps), rather than $2n + 2$ of them.
+// TODO(Performance): for verification, can we get any speed-ups when a lot of the PKs are the same?
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[allow(non_snake_case)]
+pub struct Transcript {
+    /// ElGamal encryption randomness $\hat{w} = g_2^r \in G_2$
+    hat_w: G2Projective,
+    /// Commitments to the $n$ evaluations of $p(X)$: $g_2^{p(\omega^i)}$, followed by the commitment
+    /// $g_2^{p(0)}$ to the dealt secret (i.e., the dealt public key)
+    V: Vec<G2Projective>,
+    /// ElGamal encryptions of the shares: $C_i = h_1^{p(\omega^i)} ek_i^r, \forall i \in [n]$
+    C: Vec<G1Projective>,
+    /// ElGamal encryption randomness $C_0 = g_1^r \in G_1$
+    C_0: G1Projective,
+    /// The contributions of all dealers who were aggregated into this transcript, with their PoKs
+    /// and signatures
+    contributions: Vec<Contribution>,
+}
+
+impl ValidCryptoMaterial for Transcript {
+    fn to_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&self).expect("unexpected error during DAS PVSS transcript serialization")
+    }
+}
+
+impl TryFrom<&[u8]> for Transcript {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bcs::from_bytes::<Transcript>(bytes).map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl traits::Transcript for Transcript {
+    type SecretSharingConfig = ThresholdConfig;
+    type PvssPublicParameters = das::PublicParameters;
+    type SigningSecretKey = bls12381::PrivateKey;
+    type SigningPubKey = bls12381::PublicKey;
+    type DealtSecretKeyShare = das::DealtSecretKeyShare;
+    type DealtPubKeyShare = das::DealtPubKeyShare;
+    type DealtSecretKey = das::DealtSecretKey;
+    type DealtPubKey = das::DealtPubKey;
+    type InputSecret = das::InputSecret;
+    type EncryptPubKey = encryption_dlog::g1::EncryptPubKey;
+    type DecryptPrivKey = encryption_dlog::g1::DecryptPrivKey;
+
+    fn scheme_name() -> String {
+        "das_sk_in_g1".to_string()
+    }
+
+    #[allow(non_snake_case)]
+    fn deal<R: rand_core::RngCore + rand_core::CryptoRng>(
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
+        eks: &Vec<Self::EncryptPubKey>,
+        s: Self::InputSecret,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> Self {
+        assert_eq!(eks.len(), sc.n);
+
+        // A random, degree t-1 polynomial $p(X) = [a_0, \dots, a_{t-1}]$, with $a_0$ set to `s.a`
+        let mut p = random_scalars(sc.t, rng);
+        p[0] = *s.get_secret_a();
+
+        // Evaluate $p$ at all the $N$th roots of unity.
+        let mut p_evals = fft(&p, sc.get_evaluation_domain());
+        p_evals.truncate(sc.n);
+
+        // The ElGamal encryption randomness
+        let r = random_scalar(rng);
+
+        let g1 = pp.get_encryption_key_base();
+        let h1 = pp.get_secret_key_base();
+        let g2 = pp.get_commitment_base();
+
+        let V = p_evals
+            .iter()
+            .chain([p[0]].iter())
+            .map(|p_i| g2.mul(p_i))
+            .collect::<Vec<G2Projective>>();
+        let C = (0..sc.n)
+            .map(|i| h1.mul(p_evals[i]) + Into::<G1Projective>::into(&eks[i]).mul(r))
+            .collect::<Vec<G1Projective>>();
+
+        Transcript {
+            hat_w: g2.mul(r),
+            V,
+            C,
+            C_0: g1.mul(r),
+            contributions: vec![Contribution::new(g1, &p[0], ssk, dealer, dst, rng)],
+        }
+    }
+
+    #[allow(non_snake_case)]
+    fn verify(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        spks: &Vec<Self::SigningPubKey>,
+        eks: &Vec<Self::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> bool {
+        if eks.len() != sc.n {
+            return false;
+        }
+
+        if !traits::Transcript::has_valid_sizes(self, sc) {
+            return false;
+        }
+
+        // We only check the dealers' PoKs and signatures here: the transcript has no $F_0$ of its own
+        // that the dealers' commitments $g_1^{a_j}$ could be checked to add up to. Instead, we take
+        // their sum as $F_0$ and the multipairing below binds it to $V_n = g_2^{p(0)}$.
+        let g1 = pp.get_encryption_key_base();
+        if !contributors_are_distinct(&self.contributions, &[], spks)
+            || !verify_contributor_proofs(g1, &self.contributions, &[], spks, dst)
+        {
+            return false;
+        }
+        let F_0: G1Projective = self.contributions.iter().map(|c| c.comm).sum();
+
+        // Derive challenges deterministically via Fiat-Shamir; it's easier to debug for distributed systems
+        let (beta, r) = self.fiat_shamir(sc, pp, eks, dst);
+
+        // The SCRAPE dual-code low-degree test, on the commitments to p(0), p(\omega^0), \ldots, p(\omega^{n-1})
+        if !dual_code_low_degree_test(sc, &self.V[sc.n], &self.V[..sc.n].to_vec(), &beta) {
+            return false;
+        }
+
+        //
+        // We need to check the following equations hold:
+        //
+        //     e(C_i, g_2) = e(h_1, V_i) e(ek_i, \hat{w}), \forall i \in [0,n)
+        //     e(C_0, g_2) = e(g_1, \hat{w})
+        //     e(F_0, g_2) = e(g_1, V_n)
+        //
+        // We combine them using r_i = r^i, for a random r, which gives a size-4 multipairing:
+        //
+        //     e(\sum_{i \in [0,n)} r^i C_i + r^n C_0 + r^{n+1} F_0, g_2)
+        //     e(h_1^{-1}, \sum_{i \in [0,n)} r^i V_i)
+        //     e((\sum_{i \in [0,n)} r^i ek_i + r^n g_1)^{-1}, \hat{w})
+        //     e(g_1^{-1}, V_n^{r^{n+1}}) = 1
+        //
+        let mut r_i = Vec::with_capacity(sc.n + 2);
+        r_i.push(Scalar::one());
+        for _ in 0..sc.n + 1 {
+            r_i.push(r_i.last().unwrap().mul(r));
+        }
+
+        let lhs_g2 = G1Projective::multi_exp(
+            &self
+                .C
+                .iter()
+                .chain([self.C_0, F_0].iter())
+                .copied()
+                .collect::<Vec<G1Projective>>(),
+            &r_i,
+        );
+        let lhs_V = G2Projective::multi_exp(&self.V[..sc.n], &r_i[..sc.n]);
+        let lhs_hat_w = G1Projective::multi_exp(
+            &eks.iter()
+                .map(|ek| Into::<G1Projective>::into(ek))
+                .chain([*g1].into_iter())
+                .collect::<Vec<G1Projective>>(),
+            &r_i[..sc.n + 1],
+        );
+        let lhs_V_n = self.V[sc.n].mul(r_i[sc.n + 1]);
+
+        let lhs = [
+            lhs_g2.to_affine(),
+            pp.get_secret_key_base().neg().to_affine(),
+            lhs_hat_w.neg().to_affine(),
+            g1.neg().to_affine(),
+        ];
+        let rhs = [
+            G2Prepared::from(pp.get_commitment_base().to_affine()),
+            G2Prepared::from(lhs_V.to_affine()),
+            G2Prepared::from(self.hat_w.to_affine()),
+            G2Prepared::from(lhs_V_n.to_affine()),
+        ];
+
+        let res = <Bls12 as MultiMillerLoop>::multi_miller_loop(
+            lhs.iter()
+                .zip(rhs.iter())
+                .collect::<Vec<(&G1Affine, &G2Prepared)>>()
+                .as_slice(),
+        );
+
+        res.final_exponentiation() == Gt::identity()
+    }
+
+    /// Checks that the transcript has $n + 1$ evaluation commitments and $n$ ciphertexts.
+    fn has_valid_sizes(&self, sc: &ThresholdConfig) -> bool {
+        self.V.len() == sc.n + 1 && self.C.len() == sc.n
+    }
+
+    fn aggregate_with(
+        &mut self,
+        sc: &ThresholdConfig,
+        other: &Transcript,
+    ) -> Result<(), AggregationError> {
+        if !traits::Transcript::has_valid_sizes(self, sc)
+            || !traits::Transcript::has_valid_sizes(other, sc)
+        {
+            return Err(AggregationError::InvalidSizes);
+        }
+
+        merge_contributors(
+            &mut self.contributions,
+            &mut vec![],
+            &other.contributions,
+            &[],
+        )
+        .map_err(AggregationError::DuplicateDealer)?;
+
+        self.hat_w += other.hat_w;
+        self.C_0 += other.C_0;
+
+        for i in 0..sc.n {
+            self.C[i] += other.C[i];
+        }
+
+        for i in 0..sc.n + 1 {
+            self.V[i] += other.V[i];
+        }
+
+        Ok(())
+    }
+
+    fn get_dealers(&self) -> Vec<Player> {
+        self.contributions
+            .iter()
+            .map(|c| c.get_dealer().clone())
+            .collect()
+    }
+
+    fn get_dealt_public_key(&self) -> das::DealtPubKey {
+        das::DealtPubKey::new(*self.V.last().unwrap())
+    }
+
+    fn get_public_key_share(
+        &self,
+        _sc: &ThresholdConfig,
+        player_id: &Player,
+    ) -> Self::DealtPubKeyShare {
+        das::DealtPubKeyShare(Self::DealtPubKey::new(self.V[player_id.id]))
+    }
+
+    fn decrypt_own_share(
+        &self,
+        _sc: &ThresholdConfig,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+    ) -> (Self::DealtSecretKeyShare, Self::DealtPubKeyShare) {
+        // C_i / C_0^{dk^{-1}} = h_1^{p(\omega^i)} (because ek_i = g_1^{dk^{-1}})
+        let ephemeral_key = self.C_0.mul(dk.dk.invert().unwrap());
+        let secret_key_share = self.C[player_id.id] - ephemeral_key;
+        let verification_key_share = self.V[player_id.id]; // g_2^{p(\omega^i})
+
+        (
+            das::DealtSecretKeyShare(Self::DealtSecretKey::new(secret_key_share)),
+            das::DealtPubKeyShare(Self::DealtPubKey::new(verification_key_share)),
+        )
+    }
+
+    #[allow(non_snake_case)]
+    fn generate<R>(sc: &ThresholdConfig, rng: &mut R) -> Self
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        // TODO(rand_core_hell): See `scrape::Transcript::generate` for why we use doublings here.
+        let mut acc_g2 = random_g2_point(rng);
+        let V = (0..sc.n + 1)
+            .map(|_| {
+                acc_g2 = acc_g2.double();
+                acc_g2
+            })
+            .collect::<Vec<G2Projective>>();
+
+        let mut acc_g1 = random_g1_point(rng);
+        let C = (0..sc.n)
+            .map(|_| {
+                acc_g1 = acc_g1.double();
+                acc_g1
+            })
+            .collect::<Vec<G1Projective>>();
+
+        let F_0 = random_g1_point(rng);
+
+        Transcript {
+            hat_w: random_g2_point(rng),
+            V,
+            C,
+            C_0: random_g1_point(rng),
+            contributions: vec![random_contribution(&sc.get_player(0), &F_0, rng)],
+        }
+    }
+}
+
+impl VerifiableDecryption for Transcript {
+    type DecryptionProof = dleq::Proof;
+
+    /// Proves that $C_0^{dk^{-1}} = C_i / h_1^{p(\omega^i)}$ via a DLEQ proof with $ek_i = g_1^{dk^{-1}}$.
+    fn decrypt_own_share_with_proof<R>(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+        rng: &mut R,
+    ) -> (
+        Self::DealtSecretKeyShare,
+        Self::DealtPubKeyShare,
+        dleq::Proof,
+    )
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        let (sk_share, pk_share) = traits::Transcript::decrypt_own_share(self, sc, player_id, dk);
+
+        let dk_inv = dk.dk.invert().unwrap();
+        let g1 = pp.get_encryption_key_base();
+        let ephemeral_key = self.C[player_id.id] - sk_share.0.as_group_element();
+        let proof = dleq::dleq_prove(
+            &dk_inv,
+            g1,
+            &g1.mul(dk_inv),
+            &self.C_0,
+            &ephemeral_key,
+            &decryption_proof::proof_ctx(player_id),
+            rng,
+        );
+
+        (sk_share, pk_share, proof)
+    }
+
+    fn verify_decryption(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        player_id: &Player,
+        ek: &Self::EncryptPubKey,
+        sk_share: &Self::DealtSecretKeyShare,
+        proof: &dleq::Proof,
+    ) -> bool {
+        if player_id.id >= sc.n {
+            return false;
+        }
+
+        dleq::dleq_verify(
+            pp.get_encryption_key_base(),
+            &ek.ek,
+            &self.C_0,
+            &(self.C[player_id.id] - sk_share.0.as_group_element()),
+            proof,
+            &decryption_proof::proof_ctx(player_id),
+        )
+    }
+}
+
+impl Transcript {
+    /// Securely derives the Fiat-Shamir challenges for the dual-code low-degree test and for the
+    /// multipairing, via Merlin.
+    fn fiat_shamir(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &das::PublicParameters,
+        eks: &Vec<encryption_dlog::g1::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> (Scalar, Scalar) {
+        // TODO(Security): Audit this
+        let mut fs_t = merlin::Transcript::new(dst);
+        fs_t.pvss_domain_sep(sc);
+        fs_t.append_public_parameters(pp);
+        fs_t.append_encryption_keys(eks);
+
+        fs_t.append_transcript(self);
+        (
+            fs_t.challenge_dual_code_scalar(),
+            fs_t.challenge_multipairing_scalar(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::constants::DST_PVSS_TESTING_APP;
+    use crate::pvss::das::Transcript;
+    use crate::pvss::test_utils;
+    use crate::pvss::test_utils::get_threshold_config_and_rng;
+    use crate::pvss::threshold_config::ThresholdConfig;
+    use crate::pvss::traits::transcript::Transcript as UniformTranscript;
+    use crate::pvss::traits::SecretSharingConfig;
+    use aptos_crypto::{Uniform, ValidCryptoMaterial};
+    use blstrs::{G1Projective, G2Projective};
+    use group::Group;
+    use rand::thread_rng;
+
+    #[test]
+    fn transcript_serialization() {
+        let sc = ThresholdConfig::new(10, 20);
+        let mut rng = thread_rng();
+
+        let trx = Transcript::generate(&sc, &mut rng);
+
+        let serialized = trx.to_bytes();
+        let deserialized = Transcript::try_from(serialized.as_slice())
+            .expect("serialized DAS transcript should deserialize correctly");
+
+        assert_eq!(trx, deserialized);
+    }
+
+    #[test]
+    fn tampered_transcript_does_not_verify() {
+        let (sc, mut rng) = get_threshold_config_and_rng(3, 7);
+        let (pp, _, eks, _, _) = test_utils::setup_dealing::<Transcript>(&sc);
+        let (ssks, spks) = test_utils::setup_signing::<Transcript>(&sc);
+
+        let mut trx = Transcript::deal(
+            &sc,
+            &pp,
+            &ssks[0],
+            &eks,
+            <Transcript as UniformTranscript>::InputSecret::generate(&mut rng),
+            &sc.get_player(0),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+        let other = Transcript::deal(
+            &sc,
+            &pp,
+            &ssks[1],
+            &eks,
+            <Transcript as UniformTranscript>::InputSecret::generate(&mut rng),
+            &sc.get_player(1),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+        trx.aggregate_with(&sc, &other).unwrap();
+        assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // A bad ciphertext
+        let mut bad_trx = trx.clone();
+        bad_trx.C[2] += G1Projective::generator();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // A commitment off the polynomial
+        let mut bad_trx = trx.clone();
+        bad_trx.V[2] += G2Projective::generator();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // A dealt public key that does not match the dealers' commitments
+        let mut bad_trx = trx.clone();
+        bad_trx.V[sc.n] += G2Projective::generator();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // Inconsistent ElGamal randomness
+        let mut bad_trx = trx.clone();
+        bad_trx.hat_w += G2Projective::generator();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+    }
+}
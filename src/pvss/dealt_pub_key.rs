@@ -17,7 +17,7 @@ macro_rules! dealt_pub_key_impl {
         pub(crate) const DEALT_PK_NUM_BYTES: usize = $GT_PROJ_NUM_BYTES;
 
         /// The *dealt public key* associated with the the secret key that was dealt via the PVSS transcript.
-        #[derive(DeserializeKey, Clone, SerializeKey)]
+        #[derive(DeserializeKey, Clone, PartialEq, SerializeKey)]
         pub struct DealtPubKey {
             /// A group element $g_1^a \in G$, where $G$ is $G_1$, $G_2$ or $G_T$
             g_a: $GTProjective,
@@ -35,6 +35,10 @@ macro_rules! dealt_pub_key_impl {
             pub fn to_bytes(&self) -> [u8; DEALT_PK_NUM_BYTES] {
                 self.g_a.to_compressed()
             }
+
+            pub(crate) fn as_group_element(&self) -> &$GTProjective {
+                &self.g_a
+            }
         }
 
         impl ValidCryptoMaterial for DealtPubKey {
@@ -58,9 +62,5 @@ pub mod g1 {
 }
 
 pub mod g2 {
-    // dealt_pub_key_impl!(
-    //     G2_PROJ_NUM_BYTES,
-    //     g2_proj_from_bytes,
-    //     G2Projective
-    // );
+    dealt_pub_key_impl!(G2_PROJ_NUM_BYTES, g2_proj_from_bytes, G2Projective);
 }
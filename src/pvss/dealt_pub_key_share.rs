@@ -14,7 +14,7 @@ macro_rules! dealt_pub_key_share_impl {
         pub(crate) const DEALT_PK_SHARE_NUM_BYTES: usize = DEALT_PK_NUM_BYTES;
 
         /// A player's *share* of the *dealt public key* from above.
-        #[derive(DeserializeKey, Clone, SerializeKey)]
+        #[derive(DeserializeKey, Clone, PartialEq, SerializeKey)]
         pub struct DealtPubKeyShare(pub(crate) DealtPubKey);
 
         //
@@ -49,5 +49,5 @@ pub mod g1 {
 }
 
 pub mod g2 {
-    //dealt_pub_key_share_impl!(g2);
+    dealt_pub_key_share_impl!(g2);
 }
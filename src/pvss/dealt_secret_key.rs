@@ -6,17 +6,17 @@ macro_rules! dealt_secret_key_impl {
         $gt:ident
     ) => {
         use crate::algebra::lagrange::lagrange_coefficients_at_zero;
+        use crate::algebra::reed_solomon::decode_in_exponent;
         use crate::constants::$GT_PROJ_NUM_BYTES;
         use crate::pvss::dealt_secret_key_share::$gt::DealtSecretKeyShare;
         use crate::pvss::player::Player;
         use crate::pvss::threshold_config::ThresholdConfig;
         use crate::pvss::traits;
-        use crate::pvss::traits::SecretSharingConfig;
+        use crate::pvss::traits::{check_players, ReconstructionError, SecretSharingConfig};
         use crate::utils::serialization::$gt_proj_from_bytes;
         use aptos_crypto::CryptoMaterialError;
         use aptos_crypto_derive::{SilentDebug, SilentDisplay};
         use blstrs::$GTProjective;
-        use more_asserts::{assert_ge, assert_le};
 
         /// The size of a serialized *dealt secret key*.
         pub(crate) const DEALT_SK_NUM_BYTES: usize = $GT_PROJ_NUM_BYTES;
@@ -53,6 +53,10 @@ macro_rules! dealt_secret_key_impl {
             pub fn to_bytes(&self) -> [u8; DEALT_SK_NUM_BYTES] {
                 self.h_hat.to_compressed()
             }
+
+            pub(crate) fn as_group_element(&self) -> &$GTProjective {
+                &self.h_hat
+            }
         }
 
         impl TryFrom<&[u8]> for DealtSecretKey {
@@ -73,8 +77,20 @@ macro_rules! dealt_secret_key_impl {
             /// Reconstructs the `DealtSecretKey` given a sufficiently-large subset of shares from players.
             /// Mainly used for testing the PVSS transcript dealing and decryption.
             fn reconstruct(sc: &ThresholdConfig, shares: &Vec<(Player, Self::Share)>) -> Self {
-                assert_ge!(shares.len(), sc.get_threshold());
-                assert_le!(shares.len(), sc.get_total_num_players());
+                Self::try_reconstruct(sc, shares).expect("could not reconstruct dealt secret key")
+            }
+
+            fn try_reconstruct(
+                sc: &ThresholdConfig,
+                shares: &Vec<(Player, Self::Share)>,
+            ) -> Result<Self, ReconstructionError> {
+                check_players(sc.get_total_num_players(), shares.iter().map(|(p, _)| p))?;
+                if shares.len() < sc.get_threshold() {
+                    return Err(ReconstructionError::TooFewShares {
+                        got: shares.len(),
+                        needed: sc.get_threshold(),
+                    });
+                }
 
                 let ids = shares.iter().map(|(p, _)| p.id).collect::<Vec<usize>>();
                 let lagr =
@@ -86,15 +102,51 @@ macro_rules! dealt_secret_key_impl {
 
                 assert_eq!(lagr.len(), bases.len());
 
-                DealtSecretKey {
+                Ok(DealtSecretKey {
                     h_hat: $GTProjective::multi_exp(bases.as_slice(), lagr.as_slice()),
-                }
+                })
+            }
+        }
+
+        impl traits::RobustlyReconstructable for DealtSecretKey {
+            /// Corrects wrong blocks with up to $\lfloor (m - t) / 2 \rfloor$ wrong shares in total
+            /// out of $m$.
+            fn reconstruct_robustly_in_blocks(
+                sc: &ThresholdConfig,
+                blocks: &Vec<Vec<(Player, Self::Share)>>,
+                max_errors: usize,
+            ) -> Option<(Self, Vec<usize>)> {
+                check_players(
+                    sc.get_total_num_players(),
+                    blocks.iter().flatten().map(|(p, _)| p),
+                )
+                .ok()?;
+
+                let evals = blocks
+                    .iter()
+                    .map(|block| {
+                        block
+                            .iter()
+                            .map(|(p, share)| (p.id, share.0.h_hat))
+                            .collect::<Vec<(usize, $GTProjective)>>()
+                    })
+                    .collect::<Vec<Vec<(usize, $GTProjective)>>>();
+                let (h_hat, errors) = decode_in_exponent(
+                    sc.get_batch_evaluation_domain(),
+                    sc.get_threshold(),
+                    evals.as_slice(),
+                    max_errors,
+                )?;
+
+                Some((DealtSecretKey { h_hat }, errors))
             }
         }
     };
 }
 
-pub mod g1 {}
+pub mod g1 {
+    dealt_secret_key_impl!(G1_PROJ_NUM_BYTES, g1_proj_from_bytes, G1Projective, g1);
+}
 
 pub mod g2 {
     dealt_secret_key_impl!(G2_PROJ_NUM_BYTES, g2_proj_from_bytes, G2Projective, g2);
@@ -45,7 +45,9 @@ macro_rules! dealt_secret_key_share_impl {
     };
 }
 
-pub mod g1 {}
+pub mod g1 {
+    dealt_secret_key_share_impl!(g1);
+}
 
 pub mod g2 {
     dealt_secret_key_share_impl!(g2);
@@ -0,0 +1,163 @@
+//! Publicly verifiable decryption of dealt secret key shares, for handling complaints.
+//!
+//! A player who claims that its share in a (verified) transcript does not decrypt correctly publishes
+//! its decrypted share together with a proof of correct decryption. Anybody can then check the proof
+//! against the transcript and the player's encryption key, without the decryption key, and check the
+//! share against the player's dealt public key share (see `share_verification`):
+//!
+//!  - If the proof does not verify, the complaint is invalid and the player is to blame.
+//!  - If the proof verifies, but the share does not match the dealt public key share, the complaint
+//!    is valid.
+
+use crate::pvss::dleq;
+use crate::pvss::traits::Transcript;
+use crate::pvss::{dleq_scrape, scrape, scrape_g1, Player, ThresholdConfig};
+use crate::utils::HasMultiExp;
+use blstrs::Scalar;
+use ff::Field;
+use std::ops::Mul;
+
+pub const DECRYPTION_PROOF_DST: &[u8; 31] = b"APTOS_PVSS_DECRYPTION_PROOF_DST";
+
+/// A PVSS transcript whose players can prove that they correctly decrypted their dealt secret key
+/// share.
+pub trait VerifiableDecryption: Transcript {
+    type DecryptionProof: Clone;
+
+    /// Like `Transcript::decrypt_own_share`, but also returns a proof that the dealt secret key share
+    /// was correctly decrypted using the decryption key associated with the player's encryption key.
+    fn decrypt_own_share_with_proof<R>(
+        &self,
+        sc: &Self::SecretSharingConfig,
+        pp: &Self::PvssPublicParameters,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+        rng: &mut R,
+    ) -> (
+        Self::DealtSecretKeyShare,
+        Self::DealtPubKeyShare,
+        Self::DecryptionProof,
+    )
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng;
+
+    /// Verifies that `sk_share` is the correct decryption of player `player_id`'s encrypted share in
+    /// this transcript, under the player's encryption key `ek`.
+    fn verify_decryption(
+        &self,
+        sc: &Self::SecretSharingConfig,
+        pp: &Self::PvssPublicParameters,
+        player_id: &Player,
+        ek: &Self::EncryptPubKey,
+        sk_share: &Self::DealtSecretKeyShare,
+        proof: &Self::DecryptionProof,
+    ) -> bool;
+}
+
+/// Implements `VerifiableDecryption` for a PVSS transcript type whose encrypted shares
+/// $C_i = ek_i^{s_i}$ are in the `$($ctxts).+` field, where $ek_i = h^{dk_i^{-1}}$ for the encryption
+/// key base $h$ in the public parameters.
+macro_rules! verifiable_decryption_impl {
+    ($Transcript:ty, $($ctxts:ident).+) => {
+        impl VerifiableDecryption for $Transcript {
+            type DecryptionProof = dleq::Proof;
+
+            fn decrypt_own_share_with_proof<R>(
+                &self,
+                sc: &ThresholdConfig,
+                pp: &Self::PvssPublicParameters,
+                player_id: &Player,
+                dk: &Self::DecryptPrivKey,
+                rng: &mut R,
+            ) -> (
+                Self::DealtSecretKeyShare,
+                Self::DealtPubKeyShare,
+                dleq::Proof,
+            )
+            where
+                R: rand_core::RngCore + rand_core::CryptoRng,
+            {
+                let (sk_share, pk_share) = self.decrypt_own_share(sc, player_id, dk);
+
+                let h = pp.get_encryption_key_base();
+                let ek = h.mul(dk.dk.invert().unwrap()); // \ek_i = h^{\dk_i^{-1}}
+                let proof = prove_decryption(
+                    h,
+                    &ek,
+                    &self.$($ctxts).+[player_id.id],
+                    sk_share.0.as_group_element(),
+                    &dk.dk,
+                    player_id,
+                    rng,
+                );
+
+                (sk_share, pk_share, proof)
+            }
+
+            fn verify_decryption(
+                &self,
+                sc: &ThresholdConfig,
+                pp: &Self::PvssPublicParameters,
+                player_id: &Player,
+                ek: &Self::EncryptPubKey,
+                sk_share: &Self::DealtSecretKeyShare,
+                proof: &dleq::Proof,
+            ) -> bool {
+                if player_id.id >= sc.n {
+                    return false;
+                }
+
+                verify_decryption(
+                    pp.get_encryption_key_base(),
+                    &ek.ek,
+                    &self.$($ctxts).+[player_id.id],
+                    sk_share.0.as_group_element(),
+                    player_id,
+                    proof,
+                )
+            }
+        }
+    };
+}
+
+verifiable_decryption_impl!(scrape::Transcript, Y_hat);
+verifiable_decryption_impl!(scrape::CompactTranscript, Y_hat);
+verifiable_decryption_impl!(dleq_scrape::Transcript, trx.Y_hat);
+verifiable_decryption_impl!(scrape_g1::Transcript, Y);
+
+/// Proves that `share` $= C^{dk}$ is the decryption of a ciphertext $C = ek^s$ under an encryption
+/// key $ek = h^{dk^{-1}}$, via a DLEQ proof that $ek^{dk} = h$ and $C^{dk} = $ `share`.
+pub(crate) fn prove_decryption<Gr, R>(
+    h: &Gr,
+    ek: &Gr,
+    ctxt: &Gr,
+    share: &Gr,
+    dk: &Scalar,
+    player_id: &Player,
+    rng: &mut R,
+) -> dleq::Proof
+where
+    Gr: HasMultiExp,
+    R: rand_core::RngCore + rand_core::CryptoRng,
+{
+    dleq::dleq_prove(dk, ek, h, ctxt, share, &proof_ctx(player_id), rng)
+}
+
+/// Verifies a proof computed via `prove_decryption`.
+pub(crate) fn verify_decryption<Gr: HasMultiExp>(
+    h: &Gr,
+    ek: &Gr,
+    ctxt: &Gr,
+    share: &Gr,
+    player_id: &Player,
+    proof: &dleq::Proof,
+) -> bool {
+    dleq::dleq_verify(ek, h, ctxt, share, proof, &proof_ctx(player_id))
+}
+
+/// Binds a decryption proof to the player who computed it.
+pub(crate) fn proof_ctx(player_id: &Player) -> Vec<u8> {
+    let mut ctx = DECRYPTION_PROOF_DST.to_vec();
+    ctx.extend((player_id.get_id() as u64).to_le_bytes());
+    ctx
+}
@@ -0,0 +1,293 @@
+//! Non-interactive Chaum-Pedersen proofs of discrete log equality (DLEQ), bound to a context (e.g.,
+//! a DST and the prover's identity) via Fiat-Shamir. The two bases may live in different groups of
+//! the same prime order (e.g., $g \in \mathbb{G}_1$ and $h \in \mathbb{G}_2$).
+
+use crate::utils::{hash_to_scalar, random::random_scalar, HasMultiExp};
+use blstrs::Scalar;
+use ff::Field;
+use std::ops::Neg;
+
+pub const DLEQ_DOM_SEP: &[u8; 14] = b"APTOS_DLEQ_DST";
+pub const DLEQ_HASH_TO_SCALAR_DST: &[u8; 29] = b"APTOS_DLEQ_HASH_TO_SCALAR_DST";
+
+/// A DLEQ proof $(c, s)$ that $x$ is such that $X = g^x$ and $Y = h^x$, where $c$ is the Fiat-Shamir
+/// challenge for the commitments $g^k$ and $h^k$ for a random $k$, and $s = k - c \cdot x$.
+pub type Proof = (Scalar, Scalar);
+
+/// A DLEQ proof $(R_g, R_h, z)$ that $x$ is such that $X = g^x$ and $Y = h^x$, where $R_g = g^k$ and
+/// $R_h = h^k$ for a random $k$, and $z = k + c \cdot x$ for the Fiat-Shamir challenge $c$. Unlike a
+/// `Proof`, it carries the commitments, so many such proofs can be verified at once via
+/// `dleq_batch_verify`.
+pub type BatchableProof<G, H> = (G, H, Scalar);
+
+/// Computes a DLEQ proof of `x` such that `big_x` $= g^x$ and `big_y` $= h^x$, bound to the context
+/// `ctx`.
+pub fn dleq_prove<G, H, R>(
+    x: &Scalar,
+    g: &G,
+    big_x: &G,
+    h: &H,
+    big_y: &H,
+    ctx: &[u8],
+    rng: &mut R,
+) -> Proof
+where
+    G: HasMultiExp,
+    H: HasMultiExp,
+    R: rand_core::RngCore + rand_core::CryptoRng,
+{
+    debug_assert_eq!(g.mul(x), *big_x);
+    debug_assert_eq!(h.mul(x), *big_y);
+
+    let k = random_scalar(rng);
+    let c = dleq_challenge(g, big_x, h, big_y, &g.mul(k), &h.mul(k), ctx);
+    let s = k - c * x;
+
+    (c, s)
+}
+
+/// Verifies a DLEQ proof that `big_x` $= g^x$ and `big_y` $= h^x$ for the same $x$, bound to the
+/// context `ctx`, by recomputing the commitments $g^s X^c$ and $h^s Y^c$ and checking the challenge.
+pub fn dleq_verify<G: HasMultiExp, H: HasMultiExp>(
+    g: &G,
+    big_x: &G,
+    h: &H,
+    big_y: &H,
+    proof: &Proof,
+    ctx: &[u8],
+) -> bool {
+    let (c, s) = proof;
+    let r_g = G::multi_exp(&[*g, *big_x], &[*s, *c]);
+    let r_h = H::multi_exp(&[*h, *big_y], &[*s, *c]);
+
+    *c == dleq_challenge(g, big_x, h, big_y, &r_g, &r_h, ctx)
+}
+
+/// Computes a batchable DLEQ proof of `x` such that `big_x` $= g^x$ and `big_y` $= h^x$, bound to the
+/// context `ctx`.
+pub fn dleq_prove_batchable<G, H, R>(
+    x: &Scalar,
+    g: &G,
+    big_x: &G,
+    h: &H,
+    big_y: &H,
+    ctx: &[u8],
+    rng: &mut R,
+) -> BatchableProof<G, H>
+where
+    G: HasMultiExp,
+    H: HasMultiExp,
+    R: rand_core::RngCore + rand_core::CryptoRng,
+{
+    debug_assert_eq!(g.mul(x), *big_x);
+    debug_assert_eq!(h.mul(x), *big_y);
+
+    let k = random_scalar(rng);
+    let (r_g, r_h) = (g.mul(k), h.mul(k));
+    let c = dleq_challenge(g, big_x, h, big_y, &r_g, &r_h, ctx);
+    let z = k + c * x;
+
+    (r_g, r_h, z)
+}
+
+/// Verifies many batchable DLEQ proofs at once, where the $i$th proof in `proofs` is for
+/// `big_xs[i]` $= g^{x_i}$ and `big_ys[i]` $=$ `hs[i]`$^{x_i}$, and is bound to the context
+/// `ctxs[i]`.
+///
+/// We need to check $g^{z_i} = R_{g,i} X_i^{c_i}$ and $h_i^{z_i} = R_{h,i} Y_i^{c_i}$ for all $i$.
+/// Instead, we check $g^{\sum_i \gamma^i z_i} \prod_i R_{g,i}^{-\gamma^i} X_i^{-\gamma^i c_i} = 1$
+/// and $\prod_i h_i^{\gamma^i z_i} R_{h,i}^{-\gamma^i} Y_i^{-\gamma^i c_i} = 1$
+/// with one multiexp in each group, where $\gamma$ is derived via Fiat-Shamir from all the proofs.
+pub fn dleq_batch_verify<G: HasMultiExp, H: HasMultiExp>(
+    g: &G,
+    big_xs: &[G],
+    hs: &[H],
+    big_ys: &[H],
+    proofs: &[BatchableProof<G, H>],
+    ctxs: &[Vec<u8>],
+) -> bool {
+    let m = proofs.len();
+    if big_xs.len() != m || hs.len() != m || big_ys.len() != m || ctxs.len() != m {
+        return false;
+    }
+
+    let mut fs_t = merlin::Transcript::new(DLEQ_DOM_SEP);
+    fs_t.append_u64(b"num-proofs", m as u64);
+
+    let mut c = Vec::with_capacity(m);
+    for i in 0..m {
+        let (r_g, r_h, z) = &proofs[i];
+        c.push(dleq_challenge(
+            g, &big_xs[i], &hs[i], &big_ys[i], r_g, r_h, &ctxs[i],
+        ));
+        fs_t.append_message(b"z", z.to_bytes_le().as_slice());
+        fs_t.append_message(b"c", c[i].to_bytes_le().as_slice());
+    }
+
+    let mut buf = [0u8; 64];
+    fs_t.challenge_bytes(b"challenge_batch", &mut buf);
+    let gamma = hash_to_scalar(buf.as_slice(), DLEQ_HASH_TO_SCALAR_DST);
+
+    let mut g_bases = Vec::with_capacity(2 * m + 1);
+    let mut g_scalars = Vec::with_capacity(2 * m + 1);
+    let mut h_bases = Vec::with_capacity(3 * m);
+    let mut h_scalars = Vec::with_capacity(3 * m);
+    let mut sum_z = Scalar::zero();
+    let mut gamma_i = Scalar::one();
+
+    for i in 0..m {
+        let (r_g, r_h, z) = &proofs[i];
+        let gamma_i_c_i_neg = (gamma_i * c[i]).neg();
+        sum_z += gamma_i * z;
+
+        g_bases.push(*r_g);
+        g_scalars.push(gamma_i.neg());
+        g_bases.push(big_xs[i]);
+        g_scalars.push(gamma_i_c_i_neg);
+
+        h_bases.push(hs[i]);
+        h_scalars.push(gamma_i * z);
+        h_bases.push(*r_h);
+        h_scalars.push(gamma_i.neg());
+        h_bases.push(big_ys[i]);
+        h_scalars.push(gamma_i_c_i_neg);
+
+        gamma_i *= gamma;
+    }
+
+    g_bases.push(*g);
+    g_scalars.push(sum_z);
+
+    G::multi_exp(&g_bases, &g_scalars) == G::identity()
+        && H::multi_exp(&h_bases, &h_scalars) == H::identity()
+}
+
+/// Derives the Fiat-Shamir challenge $c$ for a DLEQ proof with commitments $R_g = g^k$ and
+/// $R_h = h^k$, bound to the context `ctx`.
+fn dleq_challenge<G: HasMultiExp, H: HasMultiExp>(
+    g: &G,
+    big_x: &G,
+    h: &H,
+    big_y: &H,
+    r_g: &G,
+    r_h: &H,
+    ctx: &[u8],
+) -> Scalar {
+    let mut fs_t = merlin::Transcript::new(DLEQ_DOM_SEP);
+    fs_t.append_message(b"ctx", ctx);
+    fs_t.append_message(b"g", g.to_compressed_bytes().as_slice());
+    fs_t.append_message(b"X", big_x.to_compressed_bytes().as_slice());
+    fs_t.append_message(b"h", h.to_compressed_bytes().as_slice());
+    fs_t.append_message(b"Y", big_y.to_compressed_bytes().as_slice());
+    fs_t.append_message(b"R_g", r_g.to_compressed_bytes().as_slice());
+    fs_t.append_message(b"R_h", r_h.to_compressed_bytes().as_slice());
+
+    let mut buf = [0u8; 64];
+    fs_t.challenge_bytes(b"challenge_c", &mut buf);
+
+    hash_to_scalar(buf.as_slice(), DLEQ_HASH_TO_SCALAR_DST)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pvss::dleq::{dleq_batch_verify, dleq_prove, dleq_prove_batchable, dleq_verify};
+    use crate::utils::random::{random_g1_point, random_g2_point, random_scalar};
+    use group::Group;
+    use rand::thread_rng;
+    use std::ops::Mul;
+
+    #[test]
+    fn dleq_prove_and_verify() {
+        let mut rng = thread_rng();
+        let g = random_g2_point(&mut rng);
+        let h = random_g2_point(&mut rng);
+        let x = random_scalar(&mut rng);
+        let (big_x, big_y) = (g.mul(x), h.mul(x));
+
+        let proof = dleq_prove(&x, &g, &big_x, &h, &big_y, b"ctx", &mut rng);
+        assert!(dleq_verify(&g, &big_x, &h, &big_y, &proof, b"ctx"));
+
+        // The proof is bound to its context
+        assert!(!dleq_verify(&g, &big_x, &h, &big_y, &proof, b"other ctx"));
+
+        // ...and does not verify for unequal discrete logs
+        assert!(!dleq_verify(
+            &g,
+            &big_x,
+            &h,
+            &big_y.double(),
+            &proof,
+            b"ctx"
+        ));
+    }
+
+    #[test]
+    fn dleq_prove_and_verify_across_groups() {
+        let mut rng = thread_rng();
+        let g = random_g1_point(&mut rng);
+        let h = random_g2_point(&mut rng);
+        let x = random_scalar(&mut rng);
+        let (big_x, big_y) = (g.mul(x), h.mul(x));
+
+        let proof = dleq_prove(&x, &g, &big_x, &h, &big_y, b"ctx", &mut rng);
+        assert!(dleq_verify(&g, &big_x, &h, &big_y, &proof, b"ctx"));
+        assert!(!dleq_verify(
+            &g,
+            &big_x.double(),
+            &h,
+            &big_y,
+            &proof,
+            b"ctx"
+        ));
+    }
+
+    #[test]
+    fn dleq_batch_verify_across_groups() {
+        let mut rng = thread_rng();
+        let g = random_g1_point(&mut rng);
+        let m = 5;
+
+        let hs = (0..m)
+            .map(|_| random_g2_point(&mut rng))
+            .collect::<Vec<_>>();
+        let xs = (0..m).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+        let big_xs = xs.iter().map(|x| g.mul(x)).collect::<Vec<_>>();
+        let big_ys = (0..m).map(|i| hs[i].mul(xs[i])).collect::<Vec<_>>();
+        let ctxs = (0..m)
+            .map(|i| format!("ctx {i}").into_bytes())
+            .collect::<Vec<_>>();
+
+        let mut proofs = (0..m)
+            .map(|i| {
+                dleq_prove_batchable(
+                    &xs[i], &g, &big_xs[i], &hs[i], &big_ys[i], &ctxs[i], &mut rng,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(dleq_batch_verify(&g, &big_xs, &hs, &big_ys, &proofs, &ctxs));
+
+        // A single bad proof makes the whole batch fail
+        let mut bad_big_ys = big_ys.clone();
+        bad_big_ys[3] = bad_big_ys[3].double();
+        assert!(!dleq_batch_verify(
+            &g,
+            &big_xs,
+            &hs,
+            &bad_big_ys,
+            &proofs,
+            &ctxs
+        ));
+
+        // Every proof is bound to its own context
+        let mut bad_ctxs = ctxs.clone();
+        bad_ctxs.swap(0, 1);
+        assert!(!dleq_batch_verify(
+            &g, &big_xs, &hs, &big_ys, &proofs, &bad_ctxs
+        ));
+
+        proofs.pop();
+        assert!(!dleq_batch_verify(
+            &g, &big_xs, &hs, &big_ys, &proofs, &ctxs
+        ));
+    }
+}
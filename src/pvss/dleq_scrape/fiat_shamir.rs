@@ -0,0 +1,74 @@
+use crate::pvss::encryption_dlog::g2::EncryptPubKey;
+use crate::pvss::scrape;
+use crate::pvss::threshold_config::ThresholdConfig;
+use crate::utils::fiat_shamir;
+use crate::utils::hash_to_scalar;
+use aptos_crypto::ValidCryptoMaterial;
+use blstrs::{G2Projective, Scalar};
+
+pub const PVSS_DOM_SEP: &[u8; 26] = b"APTOS_DLEQ_SCRAPE_PVSS_DST";
+pub const PVSS_HASH_TO_SCALAR_DST: &[u8; 41] = b"APTOS_DLEQ_SCRAPE_PVSS_HASH_TO_SCALAR_DST";
+
+#[allow(non_snake_case)]
+pub trait FiatShamirProtocol {
+    /// Append a domain separator for the PVSS protocol, consisting of a sharing configuration `sc`,
+    /// which locks in the $t$ out of $n$ threshold.
+    fn pvss_domain_sep(&mut self, sc: &ThresholdConfig);
+
+    /// Append the public parameters `pp`.
+    fn append_public_parameters(&mut self, pp: &scrape::PublicParameters);
+
+    /// Append the encryption keys `eks`.
+    fn append_encryption_keys(&mut self, eks: &Vec<EncryptPubKey>);
+
+    /// Appends the SCRAPE transcript whose encryptions are being proven correct.
+    fn append_scrape_transcript(&mut self, trx: &scrape::Transcript);
+
+    /// Derives the context that the $n$ DLEQ proofs are bound to, before appending the player IDs.
+    fn challenge_dleq_ctx(&mut self) -> Vec<u8>;
+
+    /// Compute the Fiat-Shamir challenge `\alpha` for doing the Lagrange-based consistency check
+    fn challenge_lagrange_scalar(&mut self) -> Scalar;
+}
+
+#[allow(non_snake_case)]
+// TODO(Security): Audit this
+impl FiatShamirProtocol for merlin::Transcript {
+    fn pvss_domain_sep(&mut self, sc: &ThresholdConfig) {
+        self.append_message(b"dom-sep", PVSS_DOM_SEP);
+        self.append_u64(b"t", sc.t as u64);
+        self.append_u64(b"n", sc.n as u64);
+    }
+
+    fn append_public_parameters(&mut self, pp: &scrape::PublicParameters) {
+        self.append_message(b"pp", pp.to_bytes().as_slice());
+    }
+
+    fn append_encryption_keys(&mut self, eks: &Vec<EncryptPubKey>) {
+        fiat_shamir::append_g2_vector(
+            self,
+            b"encryption-keys",
+            &eks.iter()
+                .map(|ek| Into::<G2Projective>::into(ek))
+                .collect::<Vec<G2Projective>>(),
+        )
+    }
+
+    fn append_scrape_transcript(&mut self, trx: &scrape::Transcript) {
+        self.append_message(b"scrape-transcript", trx.to_bytes().as_slice());
+    }
+
+    fn challenge_dleq_ctx(&mut self) -> Vec<u8> {
+        let mut buf = [0u8; 32];
+        self.challenge_bytes(b"challenge_dleq_ctx", &mut buf);
+
+        buf.to_vec()
+    }
+
+    fn challenge_lagrange_scalar(&mut self) -> Scalar {
+        let mut buf = [0u8; 64];
+        self.challenge_bytes(b"challenge_alpha", &mut buf);
+
+        hash_to_scalar(buf.as_slice(), PVSS_HASH_TO_SCALAR_DST)
+    }
+}
@@ -0,0 +1,4 @@
+mod fiat_shamir;
+pub(crate) mod transcript;
+
+pub use transcript::Transcript;
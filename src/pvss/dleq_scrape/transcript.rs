@@ -0,0 +1,338 @@
+use crate::pvss::dleq;
+use crate::pvss::dleq_scrape::fiat_shamir::FiatShamirProtocol;
+use crate::pvss::encryption_dlog;
+use crate::pvss::player::Player;
+use crate::pvss::scrape;
+use crate::pvss::threshold_config::ThresholdConfig;
+use crate::pvss::traits;
+use crate::pvss::traits::AggregationError;
+use crate::utils::random::{random_g1_point, random_g2_point, random_scalar};
+use aptos_crypto::{bls12381, CryptoMaterialError, ValidCryptoMaterial};
+use blstrs::{Bls12, G1Affine, G1Projective, G2Prepared, G2Projective, Gt};
+use group::{Curve, Group};
+use pairing::{MillerLoopResult, MultiMillerLoop};
+use serde::{Deserialize, Serialize};
+use std::ops::Neg;
+
+/// A DLEQ-SCRAPE PVSS *transcript*: a SCRAPE transcript where the correctness of each player's
+/// encrypted share is proven via a Chaum-Pedersen DLEQ proof, rather than checked via pairings.
+///
+/// Specifically, for each player $i$, the dealer proves that $\log_{g_1}(A_i) = \log_{ek_i}(\hat{Y}_i)$
+/// (i.e., that both equal $f(\omega^i)$) via `dleq::dleq_prove_batchable`. Every proof is bound to
+/// the SCRAPE transcript, the public parameters, the encryption keys and the ID of player $i$. Each
+/// proof carries its commitments $(g_1^k, ek_i^k)$, so the verifier checks all $n$ proofs at once
+/// via `dleq::dleq_batch_verify`.
+///
+/// NOTE: The DLEQ proofs of two transcripts cannot be aggregated, since each one is bound to its own
+/// SCRAPE transcript. Therefore, `aggregate_with` drops them and an aggregated transcript, i.e.,
+/// one with more than one (signed) contributor, is verified via the pairing-based encryption check of
+/// the vanilla SCRAPE PVSS. A transcript with a single contributor must always carry its $n$ proofs.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Transcript {
+    /// The underlying SCRAPE transcript
+    pub(crate) trx: scrape::Transcript,
+    /// The $n$ DLEQ proofs that $A_i = g_1^{f(\omega^i)}$ and $\hat{Y}_i = ek_i^{f(\omega^i)}$
+    proofs: Vec<dleq::BatchableProof<G1Projective, G2Projective>>,
+}
+
+impl ValidCryptoMaterial for Transcript {
+    fn to_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&self)
+            .expect("unexpected error during DLEQ-SCRAPE PVSS transcript serialization")
+    }
+}
+
+impl TryFrom<&[u8]> for Transcript {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bcs::from_bytes::<Transcript>(bytes).map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl traits::Transcript for Transcript {
+    type SecretSharingConfig = ThresholdConfig;
+    type PvssPublicParameters = scrape::PublicParameters;
+    type SigningSecretKey = bls12381::PrivateKey;
+    type SigningPubKey = bls12381::PublicKey;
+    type DealtSecretKeyShare = scrape::DealtSecretKeyShare;
+    type DealtPubKeyShare = scrape::DealtPubKeyShare;
+    type DealtSecretKey = scrape::DealtSecretKey;
+    type DealtPubKey = scrape::DealtPubKey;
+    type InputSecret = scrape::InputSecret;
+    type EncryptPubKey = encryption_dlog::g2::EncryptPubKey;
+    type DecryptPrivKey = encryption_dlog::g2::DecryptPrivKey;
+
+    fn scheme_name() -> String {
+        "dleq_scrape_sk_in_g2".to_string()
+    }
+
+    fn deal<R: rand_core::RngCore + rand_core::CryptoRng>(
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
+        eks: &Vec<Self::EncryptPubKey>,
+        s: Self::InputSecret,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> Self {
+        let (trx, f_evals) =
+            scrape::Transcript::deal_with_evaluations(sc, pp, ssk, eks, s, dealer, dst, rng);
+
+        let (_, ctx) = Self::fiat_shamir_dleq(&trx, sc, pp, eks, dst);
+
+        let g1 = pp.get_commitment_base();
+        let proofs = (0..sc.n)
+            .map(|i| {
+                dleq::dleq_prove_batchable(
+                    &f_evals[i],
+                    g1,
+                    &trx.A[i],
+                    &Into::<G2Projective>::into(&eks[i]),
+                    &trx.Y_hat[i],
+                    &Self::dleq_ctx(&ctx, i),
+                    rng,
+                )
+            })
+            .collect::<Vec<dleq::BatchableProof<G1Projective, G2Projective>>>();
+
+        Transcript { trx, proofs }
+    }
+
+    fn verify(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        spks: &Vec<Self::SigningPubKey>,
+        eks: &Vec<Self::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> bool {
+        if eks.len() != sc.n {
+            return false;
+        }
+
+        // Aggregated transcripts no longer carry DLEQ proofs, so we fall back to the pairing-based
+        // check of the vanilla SCRAPE PVSS.
+        if self.is_aggregated() {
+            return traits::Transcript::verify(&self.trx, sc, pp, spks, eks, dst);
+        }
+
+        if !traits::Transcript::has_valid_sizes(self, sc) || self.proofs.len() != sc.n {
+            return false;
+        }
+
+        if !self.trx.verify_contributions(pp, spks, dst) {
+            return false;
+        }
+
+        // Derive challenges deterministically via Fiat-Shamir; it's easier to debug for distributed systems
+        let (mut fs_t, ctx) = Self::fiat_shamir_dleq(&self.trx, sc, pp, eks, dst);
+        let alpha = fs_t.challenge_lagrange_scalar();
+
+        if !self.trx.low_degree_test(sc, &alpha) {
+            return false;
+        }
+
+        let ek_points = eks
+            .iter()
+            .map(Into::<G2Projective>::into)
+            .collect::<Vec<G2Projective>>();
+        let ctxs = (0..sc.n)
+            .map(|i| Self::dleq_ctx(&ctx, i))
+            .collect::<Vec<Vec<u8>>>();
+        if !dleq::dleq_batch_verify(
+            pp.get_commitment_base(),
+            &self.trx.A,
+            &ek_points,
+            &self.trx.Y_hat,
+            &self.proofs,
+            &ctxs,
+        ) {
+            return false;
+        }
+
+        //
+        // Last, we check that \hat{u}_2 commits to the same f(0) as F_0:
+        //
+        //      e(F_0, \hat{u}_1) = e(g_1, \hat{u}_2) <=> e(F_0, \hat{u}_1) e(g_1^{-1}, \hat{u}_2) = 1
+        //
+        let lhs = [
+            self.trx.F[0].to_affine(),
+            pp.get_commitment_base().neg().to_affine(),
+        ];
+        let rhs = [
+            G2Prepared::from(pp.get_public_key_base().to_affine()),
+            G2Prepared::from(self.trx.u2_hat.to_affine()),
+        ];
+
+        let res = <Bls12 as MultiMillerLoop>::multi_miller_loop(
+            lhs.iter()
+                .zip(rhs.iter())
+                .collect::<Vec<(&G1Affine, &G2Prepared)>>()
+                .as_slice(),
+        );
+
+        res.final_exponentiation() == Gt::identity()
+    }
+
+    /// Checks the sizes of the underlying SCRAPE transcript and that there are either $n$ DLEQ proofs
+    /// or, for an aggregated transcript, none.
+    fn has_valid_sizes(&self, sc: &ThresholdConfig) -> bool {
+        traits::Transcript::has_valid_sizes(&self.trx, sc)
+            && (self.proofs.is_empty() || self.proofs.len() == sc.n)
+    }
+
+    fn aggregate_with(
+        &mut self,
+        sc: &ThresholdConfig,
+        other: &Transcript,
+    ) -> Result<(), AggregationError> {
+        traits::Transcript::aggregate_with(&mut self.trx, sc, &other.trx)?;
+
+        self.proofs.clear();
+
+        Ok(())
+    }
+
+    fn get_dealers(&self) -> Vec<Player> {
+        traits::Transcript::get_dealers(&self.trx)
+    }
+
+    fn get_dealt_public_key(&self) -> scrape::DealtPubKey {
+        traits::Transcript::get_dealt_public_key(&self.trx)
+    }
+
+    fn get_public_key_share(
+        &self,
+        sc: &ThresholdConfig,
+        player_id: &Player,
+    ) -> Self::DealtPubKeyShare {
+        traits::Transcript::get_public_key_share(&self.trx, sc, player_id)
+    }
+
+    fn decrypt_own_share(
+        &self,
+        sc: &ThresholdConfig,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+    ) -> (Self::DealtSecretKeyShare, Self::DealtPubKeyShare) {
+        traits::Transcript::decrypt_own_share(&self.trx, sc, player_id, dk)
+    }
+
+    fn generate<R>(sc: &ThresholdConfig, rng: &mut R) -> Self
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        Transcript {
+            trx: scrape::Transcript::generate(sc, rng),
+            proofs: (0..sc.n)
+                .map(|_| {
+                    (
+                        random_g1_point(rng),
+                        random_g2_point(rng),
+                        random_scalar(rng),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Transcript {
+    /// Returns true if this transcript was obtained via `aggregate_with` and therefore no longer
+    /// carries DLEQ proofs. This is determined by the number of contributors, which are each checked
+    /// by `verify_contributions`, rather than by the (unauthenticated) proof vectors being empty.
+    fn is_aggregated(&self) -> bool {
+        traits::Transcript::get_dealers(&self.trx).len() > 1
+    }
+
+    /// Securely derives the context that the DLEQ proofs are bound to via Merlin. Also returns the
+    /// Merlin transcript, so that the verifier can continue deriving its own challenges from it.
+    fn fiat_shamir_dleq(
+        trx: &scrape::Transcript,
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> (merlin::Transcript, Vec<u8>) {
+        // TODO(Security): Audit this
+        let mut fs_t = merlin::Transcript::new(dst);
+        fs_t.pvss_domain_sep(sc);
+        fs_t.append_public_parameters(pp);
+        fs_t.append_encryption_keys(eks);
+        fs_t.append_scrape_transcript(trx);
+
+        let ctx = fs_t.challenge_dleq_ctx();
+
+        (fs_t, ctx)
+    }
+
+    /// Binds the DLEQ proof of player $i$ to the context `ctx` shared by all $n$ proofs.
+    fn dleq_ctx(ctx: &[u8], i: usize) -> Vec<u8> {
+        let mut ctx = ctx.to_vec();
+        ctx.extend((i as u64).to_le_bytes());
+        ctx
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::constants::DST_PVSS_TESTING_APP;
+    use crate::pvss::dleq_scrape::Transcript;
+    use crate::pvss::test_utils;
+    use crate::pvss::threshold_config::ThresholdConfig;
+    use crate::pvss::traits::transcript::Transcript as UniformTranscript;
+    use crate::pvss::traits::SecretSharingConfig;
+    use aptos_crypto::ValidCryptoMaterial;
+    use blstrs::Scalar;
+    use ff::Field;
+    use rand::thread_rng;
+
+    #[test]
+    fn transcript_serialization() {
+        let sc = ThresholdConfig::new(10, 20);
+        let mut rng = thread_rng();
+
+        let trx = Transcript::generate(&sc, &mut rng);
+
+        let serialized = trx.to_bytes();
+        let deserialized = Transcript::try_from(serialized.as_slice())
+            .expect("serialized DLEQ-SCRAPE transcript should deserialize correctly");
+
+        assert_eq!(trx, deserialized);
+    }
+
+    #[test]
+    fn tampered_dleq_proof_does_not_verify() {
+        let sc = ThresholdConfig::new(3, 7);
+        let (pp, _, eks, s, _) = test_utils::setup_dealing::<Transcript>(&sc);
+        let (ssks, spks) = test_utils::setup_signing::<Transcript>(&sc);
+        let mut rng = thread_rng();
+
+        let mut trx = Transcript::deal(
+            &sc,
+            &pp,
+            &ssks[0],
+            &eks,
+            s,
+            &sc.get_player(0),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+        assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        let mut tampered = trx.clone();
+        tampered.proofs[2].2 += Scalar::one();
+        assert!(!tampered.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // A valid proof for one player does not verify for another
+        let mut tampered = trx.clone();
+        tampered.proofs.swap(1, 2);
+        assert!(!tampered.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // Stripping the proofs does not make a single-dealer transcript pass as an aggregated one
+        trx.proofs.clear();
+        assert!(!trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+    }
+}
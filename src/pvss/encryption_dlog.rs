@@ -1,3 +1,48 @@
+//! Encryption of dealt secret key shares under encryption keys $h^{dk^{-1}}$, which decrypt to
+//! $h^{a_i}$, together with proofs of possession (PoPs) of their decryption keys.
+
+use std::fmt::{Display, Formatter};
+
+/// Domain-separator for the Schnorr PoKs used as PoPs of decryption keys.
+pub const ENCRYPTION_KEY_POP_DST: &[u8; 33] = b"APTOS_PVSS_ENCRYPTION_KEY_POP_DST";
+
+/// The context a PoP is bound to: the DST and the caller-supplied context `ctx` (e.g., the account
+/// address of the key's owner), so that a PoP cannot be replayed by someone else for the same key.
+fn pop_context(ctx: &[u8]) -> Vec<u8> {
+    bcs::to_bytes(&(&ENCRYPTION_KEY_POP_DST[..], ctx))
+        .expect("unexpected error during PoP context serialization")
+}
+
+/// The reasons why `EncryptPubKey::batch_verify_possession` can reject a batch of PoPs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PopVerificationError {
+    /// There is not one context and one PoP per encryption key.
+    MismatchedLengths {
+        eks: usize,
+        ctxs: usize,
+        pops: usize,
+    },
+    /// The PoPs at these indices do not verify.
+    InvalidPops(Vec<usize>),
+}
+
+impl Display for PopVerificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PopVerificationError::MismatchedLengths { eks, ctxs, pops } => write!(
+                f,
+                "got {} encryption keys, but {} contexts and {} PoPs",
+                eks, ctxs, pops
+            ),
+            PopVerificationError::InvalidPops(culprits) => {
+                write!(f, "the PoPs at indices {:?} do not verify", culprits)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PopVerificationError {}
+
 macro_rules! encryption_dlog_impl {
     (
         $GT_PROJ_NUM_BYTES:ident,
@@ -5,6 +50,8 @@ macro_rules! encryption_dlog_impl {
         $GTProjective:ident
     ) => {
         use crate::constants::{$GT_PROJ_NUM_BYTES, SCALAR_NUM_BYTES};
+        use crate::pvss::encryption_dlog::{pop_context, PopVerificationError};
+        use crate::pvss::schnorr;
         use crate::pvss::traits;
         use crate::utils::random::random_scalar;
         use crate::utils::serialization::{scalar_from_bytes_le, $gt_proj_from_bytes};
@@ -14,6 +61,7 @@ macro_rules! encryption_dlog_impl {
         use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
         use blstrs::{$GTProjective, Scalar};
         use ff::Field;
+        use serde::{Deserialize, Serialize};
         use std::hash::{Hash, Hasher};
         use std::ops::Mul;
 
@@ -55,6 +103,15 @@ macro_rules! encryption_dlog_impl {
         #[cfg(feature = "assert-private-keys-not-cloneable")]
         static_assertions::assert_not_impl_any!(DecryptPrivKey: Clone);
 
+        /// A proof of possession (PoP) of the decryption key $dk$ of an encryption key
+        /// $ek = h^{dk^{-1}}$: i.e., a Schnorr PoK of $dk^{-1}$. Encryption keys should only be
+        /// accepted together with a valid PoP, which prevents rogue-key attacks where a player
+        /// picks its encryption key as a function of other players' keys.
+        #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+        pub struct ProofOfPossession {
+            pok: schnorr::PoK<$GTProjective>,
+        }
+
         //
         // PublicParameters
         //
@@ -101,6 +158,27 @@ macro_rules! encryption_dlog_impl {
             pub fn to_bytes(&self) -> [u8; DECRYPT_KEY_NUM_BYTES] {
                 self.dk.to_bytes_le()
             }
+
+            /// Proves possession of this decryption key, for its encryption key w.r.t. `pp`, bound to
+            /// the context `ctx` of its owner (e.g., its account address).
+            pub fn prove_possession<R>(
+                &self,
+                pp: &PublicParameters,
+                ctx: &[u8],
+                rng: &mut R,
+            ) -> ProofOfPossession
+            where
+                R: rand_core::RngCore + rand_core::CryptoRng,
+            {
+                let dk_inv = self.dk.invert().expect(
+                    "decryption keys are nonzero, as `generate` and `try_from` reject zero",
+                );
+                let ek = pp.h.mul(dk_inv);
+
+                ProofOfPossession {
+                    pok: schnorr::pok_prove(&dk_inv, &pp.h, &ek, &pop_context(ctx), rng),
+                }
+            }
         }
 
         impl Length for DecryptPrivKey {
@@ -122,8 +200,14 @@ macro_rules! encryption_dlog_impl {
         impl TryFrom<&[u8]> for DecryptPrivKey {
             type Error = CryptoMaterialError;
 
+            /// Rejects zero, which has no inverse and so no encryption key.
             fn try_from(bytes: &[u8]) -> std::result::Result<DecryptPrivKey, Self::Error> {
-                scalar_from_bytes_le(bytes).map(|dk| DecryptPrivKey { dk })
+                let dk = scalar_from_bytes_le(bytes)?;
+                if dk == Scalar::zero() {
+                    return Err(CryptoMaterialError::ValidationError);
+                }
+
+                Ok(DecryptPrivKey { dk })
             }
         }
 
@@ -132,9 +216,14 @@ macro_rules! encryption_dlog_impl {
             where
                 R: rand_core::RngCore + rand::Rng + rand_core::CryptoRng + rand::CryptoRng,
             {
-                DecryptPrivKey {
-                    dk: random_scalar(rng),
+                // Zero has no inverse and so no encryption key. (This only happens with probability
+                // $1/p$, where $p$ is the order of the scalar field.)
+                let mut dk = random_scalar(rng);
+                while dk == Scalar::zero() {
+                    dk = random_scalar(rng);
                 }
+
+                DecryptPrivKey { dk }
             }
         }
 
@@ -147,13 +236,66 @@ macro_rules! encryption_dlog_impl {
             pub fn to_bytes(&self) -> [u8; ENCRYPT_KEY_NUM_BYTES] {
                 self.ek.to_compressed()
             }
+
+            /// Verifies a PoP of the decryption key of this encryption key, bound to the context `ctx`
+            /// of its owner.
+            pub fn verify_possession(
+                &self,
+                pp: &PublicParameters,
+                ctx: &[u8],
+                pop: &ProofOfPossession,
+            ) -> bool {
+                schnorr::pok_verify(&pp.h, &self.ek, &pop.pok, &pop_context(ctx))
+            }
+
+            /// Verifies many PoPs at once (e.g., when registering the encryption keys of a whole
+            /// validator set), where `pops[i]` is for `eks[i]` and bound to `ctxs[i]`, via
+            /// `schnorr::pok_batch_verify`. If the batch does not verify, falls back to verifying
+            /// every PoP individually to find the invalid ones. Fails if there is not one context
+            /// and one PoP per encryption key.
+            pub fn batch_verify_possession(
+                pp: &PublicParameters,
+                eks: &[EncryptPubKey],
+                ctxs: &[Vec<u8>],
+                pops: &[ProofOfPossession],
+            ) -> Result<(), PopVerificationError> {
+                if ctxs.len() != eks.len() || pops.len() != eks.len() {
+                    return Err(PopVerificationError::MismatchedLengths {
+                        eks: eks.len(),
+                        ctxs: ctxs.len(),
+                        pops: pops.len(),
+                    });
+                }
+
+                let pks = eks.iter().map(|ek| ek.ek).collect::<Vec<$GTProjective>>();
+                let poks = pops.iter().map(|pop| pop.pok).collect::<Vec<_>>();
+                let pop_ctxs = ctxs
+                    .iter()
+                    .map(|ctx| pop_context(ctx))
+                    .collect::<Vec<Vec<u8>>>();
+                if schnorr::pok_batch_verify(&pp.h, &pks, &poks, &pop_ctxs) {
+                    return Ok(());
+                }
+
+                let culprits = (0..eks.len())
+                    .filter(|&i| !eks[i].verify_possession(pp, &ctxs[i], &pops[i]))
+                    .collect::<Vec<usize>>();
+
+                if culprits.is_empty() {
+                    Ok(())
+                } else {
+                    Err(PopVerificationError::InvalidPops(culprits))
+                }
+            }
         }
 
         impl traits::Convert<EncryptPubKey, PublicParameters> for DecryptPrivKey {
             /// Given a decryption key $dk$, computes its associated encryption key $h^{dk^{-1}}$
             fn to(&self, pp: &PublicParameters) -> EncryptPubKey {
                 EncryptPubKey {
-                    ek: pp.h.mul(self.dk.invert().unwrap()),
+                    ek: pp.h.mul(self.dk.invert().expect(
+                        "decryption keys are nonzero, as `generate` and `try_from` reject zero",
+                    )),
                 }
             }
         }
@@ -195,11 +337,7 @@ macro_rules! encryption_dlog_impl {
 }
 
 pub mod g1 {
-    // encryption_dlog_impl!(
-    //     G1_PROJ_NUM_BYTES,
-    //     g1_proj_from_bytes,
-    //     G1Projective
-    // );
+    encryption_dlog_impl!(G1_PROJ_NUM_BYTES, g1_proj_from_bytes, G1Projective);
 }
 
 pub mod g2 {
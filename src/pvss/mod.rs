@@ -1,16 +1,32 @@
+mod contribution;
 pub mod das;
 pub(crate) mod dealt_pub_key;
 pub(crate) mod dealt_pub_key_share;
 pub(crate) mod dealt_secret_key;
 pub(crate) mod dealt_secret_key_share;
-pub(crate) mod encryption_dlog;
+pub mod decryption_proof;
+pub mod dleq;
+pub mod dleq_scrape;
+pub mod encryption_dlog;
+pub mod optimistic;
 mod player;
+pub mod refresh;
+pub mod schnorr;
 pub mod scrape;
+pub mod scrape_g1;
+pub mod share_verification;
+mod sk_in_g1;
 pub mod test_utils;
 mod threshold_config;
+pub mod threshold_vrf;
 pub mod traits;
 mod weighted;
+pub mod weighted_analysis;
 
+pub use contribution::Contribution;
+pub use optimistic::OptimisticAggregator;
 pub use player::Player;
 pub use threshold_config::ThresholdConfig;
-pub use weighted::WeightedConfig;
+pub use weighted::{
+    RampConfig, StakeRoundingReport, WeightTarget, Weighted, WeightedConfig, Wrapped,
+};
@@ -0,0 +1,180 @@
+//! Optimistic aggregation of PVSS transcripts: aggregate first, verify only the aggregate and only
+//! pay for verifying individual transcripts when the aggregate is invalid.
+
+use crate::pvss::traits::{AggregationError, Transcript};
+
+/// Aggregates unverified transcripts as they come in, while keeping the individual inputs around so
+/// that, if the aggregate turns out to be invalid, the invalid inputs can be found by bisection.
+///
+/// When all inputs are valid, which we expect to be the common case, `finish` only verifies the
+/// aggregate. When $k$ of the $m$ inputs are invalid, it does $O(k \log{m})$ verifications.
+///
+/// Since the dealers of an unverified transcript are not authenticated, an input that shares a
+/// dealer with an earlier input is not rejected: a forged transcript claiming to be from an honest
+/// dealer would otherwise keep out the honest dealer's own transcript. Instead, such an input is
+/// deferred and `finish` verifies it on its own, once the valid aggregate is known.
+#[derive(Clone, Debug)]
+pub struct OptimisticAggregator<T: Transcript> {
+    /// The transcripts added so far, in the order they were added.
+    inputs: Vec<T>,
+    /// The aggregate of all transcripts in `inputs` that are not deferred, if any.
+    aggregate: Option<T>,
+    /// The indices in `inputs` of the transcripts that share a dealer with the aggregate.
+    deferred: Vec<usize>,
+}
+
+impl<T: Transcript> Default for OptimisticAggregator<T> {
+    fn default() -> Self {
+        OptimisticAggregator {
+            inputs: vec![],
+            aggregate: None,
+            deferred: vec![],
+        }
+    }
+}
+
+impl<T: Transcript> OptimisticAggregator<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aggregates `trx` without verifying it or, if one of its dealers already contributed to the
+    /// aggregate, defers it. Fails, without adding `trx`, if it does not have valid sizes, so that
+    /// malformed transcripts cannot make aggregation panic.
+    pub fn add(&mut self, sc: &T::SecretSharingConfig, trx: T) -> Result<(), AggregationError> {
+        if !trx.has_valid_sizes(sc) {
+            return Err(AggregationError::InvalidSizes);
+        }
+
+        match &mut self.aggregate {
+            Some(agg) if shares_dealer(agg, &trx) => self.deferred.push(self.inputs.len()),
+            Some(agg) => agg.aggregate_with(sc, &trx)?,
+            None => self.aggregate = Some(trx.clone()),
+        }
+
+        self.inputs.push(trx);
+        Ok(())
+    }
+
+    /// Returns the transcripts added so far, in the order they were added.
+    pub fn get_inputs(&self) -> &Vec<T> {
+        &self.inputs
+    }
+
+    /// Verifies the aggregate and, if it is invalid, bisects the inputs to find the invalid ones. Then,
+    /// verifies every deferred input and aggregates the valid ones whose dealers are not in the valid
+    /// aggregate yet. A valid deferred input whose dealer is (e.g., a resent transcript) is dropped.
+    ///
+    /// Returns the aggregate of all the valid inputs (or `None`, if there are none) together with the
+    /// indices of the invalid inputs (i.e., the culprits), in increasing order. The returned aggregate
+    /// is valid, since aggregating valid transcripts from distinct dealers yields a valid transcript.
+    pub fn finish(
+        self,
+        sc: &T::SecretSharingConfig,
+        pp: &T::PvssPublicParameters,
+        spks: &Vec<T::SigningPubKey>,
+        eks: &Vec<T::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> (Option<T>, Vec<usize>) {
+        let verify = |trx: &T| trx.verify(sc, pp, spks, eks, dst);
+
+        let (mut valid, mut culprits) = match self.aggregate {
+            None => (None, vec![]),
+            Some(agg) if verify(&agg) => (Some(agg), vec![]),
+            Some(_) => {
+                let mut valid = None;
+                let mut culprits = vec![];
+
+                let aggregated = (0..self.inputs.len())
+                    .filter(|i| !self.deferred.contains(i))
+                    .collect::<Vec<usize>>();
+                bisect(
+                    sc,
+                    &self.inputs,
+                    &aggregated,
+                    &verify,
+                    &mut valid,
+                    &mut culprits,
+                );
+
+                (valid, culprits)
+            }
+        };
+
+        for &i in &self.deferred {
+            let trx = &self.inputs[i];
+
+            if !verify(trx) {
+                culprits.push(i);
+                continue;
+            }
+
+            match &mut valid {
+                Some(v) if shares_dealer(v, trx) => {}
+                // Cannot fail, since `trx` is valid and its dealers are not in `v`.
+                Some(v) => v
+                    .aggregate_with(sc, trx)
+                    .expect("valid deferred input should aggregate with the valid aggregate"),
+                None => valid = Some(trx.clone()),
+            }
+        }
+        culprits.sort_unstable();
+
+        (valid, culprits)
+    }
+}
+
+/// Returns true if a dealer contributed to both `agg` and `trx`.
+fn shares_dealer<T: Transcript>(agg: &T, trx: &T) -> bool {
+    let dealers = agg.get_dealers();
+
+    trx.get_dealers().iter().any(|d| dealers.contains(d))
+}
+
+/// Called on a set of inputs, given by their indices `idxs`, whose aggregate is known to be invalid.
+/// Splits them in two halves and verifies the aggregate of each half: a valid half is aggregated into
+/// `valid`, while an invalid half is recursed into, until we are left with a single invalid input,
+/// which is added to `culprits`.
+fn bisect<T: Transcript, F: Fn(&T) -> bool>(
+    sc: &T::SecretSharingConfig,
+    inputs: &Vec<T>,
+    idxs: &[usize],
+    verify: &F,
+    valid: &mut Option<T>,
+    culprits: &mut Vec<usize>,
+) {
+    if idxs.len() == 1 {
+        culprits.push(idxs[0]);
+        return;
+    }
+
+    let (left, right) = idxs.split_at(idxs.len() / 2);
+
+    for half in [left, right] {
+        let agg = aggregate(sc, inputs, half);
+
+        if verify(&agg) {
+            match valid {
+                // Cannot fail, since all inputs have distinct dealers.
+                Some(v) => v
+                    .aggregate_with(sc, &agg)
+                    .expect("inputs to optimistic aggregation should have distinct dealers"),
+                None => *valid = Some(agg),
+            }
+        } else {
+            bisect(sc, inputs, half, verify, valid, culprits);
+        }
+    }
+}
+
+/// Aggregates the (non-empty) set of inputs given by their indices `idxs`.
+fn aggregate<T: Transcript>(sc: &T::SecretSharingConfig, inputs: &Vec<T>, idxs: &[usize]) -> T {
+    let mut agg = inputs[idxs[0]].clone();
+
+    for &i in &idxs[1..] {
+        agg.aggregate_with(sc, &inputs[i])
+            .expect("inputs to optimistic aggregation should have distinct dealers");
+    }
+
+    agg
+}
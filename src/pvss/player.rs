@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// An identifier from 0 to n-1 for the n players involved in the PVSS protocol.
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub struct Player {
     /// A number from 0 to n-1.
     pub(crate) id: usize,
@@ -0,0 +1,143 @@
+//! Proactive refresh of the dealt secret key shares of the same set of players: every dealer deals
+//! a transcript of the zero secret and each player adds its shares of zero, decrypted from the
+//! (aggregated) refresh transcript, to its existing shares. This re-randomizes all the shares while
+//! keeping the dealt secret key and public key the same, so shares leaked before a refresh cannot be
+//! combined with shares leaked after it.
+//!
+//! A refresh transcript needs no separate proof that it deals zero: its dealt public key
+//! $F_0 = g^{f(0)}$ is public and `Transcript::verify` already checks that the encrypted shares are
+//! evaluations of the polynomial $f(X)$ committed to by $F_0$. So, a valid transcript whose $F_0$ is
+//! the identity deals shares of $f(0) = 0$. The PoKs of the dealers are PoKs of zero.
+
+use crate::pvss::traits::Transcript;
+use crate::pvss::{das, dleq_scrape, scrape, scrape_g1, Player};
+use group::Group;
+
+/// A PVSS transcript that can deal the zero secret, to refresh the shares dealt by earlier
+/// transcripts.
+pub trait Refreshable: Transcript {
+    /// Like `Transcript::deal`, but deals the zero secret.
+    #[allow(clippy::too_many_arguments)]
+    fn deal_refresh<R: rand_core::RngCore + rand_core::CryptoRng>(
+        sc: &Self::SecretSharingConfig,
+        pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
+        eks: &Vec<Self::EncryptPubKey>,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> Self;
+
+    /// Returns true if this transcript's dealt public key is the identity. Does NOT verify the
+    /// transcript.
+    fn is_refresh(&self) -> bool;
+
+    /// Verifies that this (possibly-aggregated) transcript is valid and that it deals the zero
+    /// secret.
+    fn verify_refresh(
+        &self,
+        sc: &Self::SecretSharingConfig,
+        pp: &Self::PvssPublicParameters,
+        spks: &Vec<Self::SigningPubKey>,
+        eks: &Vec<Self::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> bool {
+        self.is_refresh() && self.verify(sc, pp, spks, eks, dst)
+    }
+
+    /// Given a verified refresh transcript, returns the refreshed version of the dealt public key
+    /// share `pk_share` of player `player_id`. Lets every player keep track of all the other players'
+    /// dealt public key shares.
+    fn refresh_public_key_share(
+        &self,
+        sc: &Self::SecretSharingConfig,
+        player_id: &Player,
+        pk_share: &Self::DealtPubKeyShare,
+    ) -> Self::DealtPubKeyShare;
+
+    /// Given a verified refresh transcript, decrypts the shares of zero of player `player_id` and
+    /// adds them to its existing dealt secret key share and dealt public key share.
+    fn apply_refresh(
+        &self,
+        sc: &Self::SecretSharingConfig,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+        sk_share: &Self::DealtSecretKeyShare,
+        pk_share: &Self::DealtPubKeyShare,
+    ) -> (Self::DealtSecretKeyShare, Self::DealtPubKeyShare);
+}
+
+/// Implements `Refreshable` for an unweighted PVSS transcript type whose dealt keys and input secret
+/// are the ones in the `$scheme` module.
+macro_rules! refreshable_impl {
+    ($Transcript:ty, $scheme:ident) => {
+        impl Refreshable for $Transcript {
+            fn deal_refresh<R: rand_core::RngCore + rand_core::CryptoRng>(
+                sc: &Self::SecretSharingConfig,
+                pp: &Self::PvssPublicParameters,
+                ssk: &Self::SigningSecretKey,
+                eks: &Vec<Self::EncryptPubKey>,
+                dealer: &Player,
+                dst: &'static [u8],
+                rng: &mut R,
+            ) -> Self {
+                <Self as Transcript>::deal(
+                    sc,
+                    pp,
+                    ssk,
+                    eks,
+                    $scheme::InputSecret::zero(),
+                    dealer,
+                    dst,
+                    rng,
+                )
+            }
+
+            fn is_refresh(&self) -> bool {
+                self.get_dealt_public_key()
+                    .as_group_element()
+                    .is_identity()
+                    .into()
+            }
+
+            fn refresh_public_key_share(
+                &self,
+                sc: &Self::SecretSharingConfig,
+                player_id: &Player,
+                pk_share: &Self::DealtPubKeyShare,
+            ) -> Self::DealtPubKeyShare {
+                let delta = self.get_public_key_share(sc, player_id);
+
+                $scheme::DealtPubKeyShare($scheme::DealtPubKey::new(
+                    pk_share.0.as_group_element() + delta.0.as_group_element(),
+                ))
+            }
+
+            fn apply_refresh(
+                &self,
+                sc: &Self::SecretSharingConfig,
+                player_id: &Player,
+                dk: &Self::DecryptPrivKey,
+                sk_share: &Self::DealtSecretKeyShare,
+                pk_share: &Self::DealtPubKeyShare,
+            ) -> (Self::DealtSecretKeyShare, Self::DealtPubKeyShare) {
+                let (sk_delta, pk_delta) = self.decrypt_own_share(sc, player_id, dk);
+
+                (
+                    $scheme::DealtSecretKeyShare($scheme::DealtSecretKey::new(
+                        sk_share.0.as_group_element() + sk_delta.0.as_group_element(),
+                    )),
+                    $scheme::DealtPubKeyShare($scheme::DealtPubKey::new(
+                        pk_share.0.as_group_element() + pk_delta.0.as_group_element(),
+                    )),
+                )
+            }
+        }
+    };
+}
+
+refreshable_impl!(scrape::Transcript, scrape);
+refreshable_impl!(scrape::CompactTranscript, scrape);
+refreshable_impl!(dleq_scrape::Transcript, scrape);
+refreshable_impl!(scrape_g1::Transcript, scrape_g1);
+refreshable_impl!(das::Transcript, das);
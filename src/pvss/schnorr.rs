@@ -0,0 +1,172 @@
+//! Non-interactive Schnorr proofs of knowledge (PoKs) of a discrete logarithm, bound to a context
+//! (e.g., a DST and the prover's identity) via Fiat-Shamir.
+
+use crate::utils::{hash_to_scalar, random::random_scalar, HasMultiExp};
+use blstrs::Scalar;
+use ff::Field;
+use std::ops::Neg;
+
+pub const SCHNORR_POK_DOM_SEP: &[u8; 21] = b"APTOS_SCHNORR_POK_DST";
+pub const SCHNORR_POK_HASH_TO_SCALAR_DST: &[u8; 36] = b"APTOS_SCHNORR_POK_HASH_TO_SCALAR_DST";
+
+/// A Schnorr PoK $(R, s)$ of $a$ such that $X = g^a$, where $R = g^k$ for a random $k$ and
+/// $s = k + c \cdot a$ for a Fiat-Shamir challenge $c$.
+pub type PoK<Gr> = (Gr, Scalar);
+
+/// Computes a Schnorr PoK of `a` such that `pk` $= g^a$, bound to the context `ctx`.
+pub fn pok_prove<Gr, R>(a: &Scalar, g: &Gr, pk: &Gr, ctx: &[u8], rng: &mut R) -> PoK<Gr>
+where
+    Gr: HasMultiExp,
+    R: rand_core::RngCore + rand_core::CryptoRng,
+{
+    debug_assert_eq!(g.mul(a), *pk);
+
+    let k = random_scalar(rng);
+    let r = g.mul(k);
+    let c = schnorr_challenge(g, pk, &r, ctx);
+    let s = k + c * a;
+
+    (r, s)
+}
+
+/// Verifies a Schnorr PoK for `pk` $= g^a$, bound to the context `ctx`.
+pub fn pok_verify<Gr: HasMultiExp>(g: &Gr, pk: &Gr, pok: &PoK<Gr>, ctx: &[u8]) -> bool {
+    let (r, s) = pok;
+    let c = schnorr_challenge(g, pk, r, ctx);
+
+    g.mul(s) == *r + pk.mul(c)
+}
+
+/// Verifies many Schnorr PoKs at once, where the $i$th PoK in `poks` is for `pks[i]` and is bound
+/// to the context `ctxs[i]`.
+///
+/// We need to check $g^{s_i} = R_i X_i^{c_i}$ for all $i$. Instead, we check
+/// $g^{\sum_i \gamma^i s_i} \prod_i R_i^{-\gamma^i} X_i^{-\gamma^i c_i} = 1$ with a single multiexp,
+/// where $\gamma$ is derived via Fiat-Shamir from all the PoKs.
+pub fn pok_batch_verify<Gr: HasMultiExp>(
+    g: &Gr,
+    pks: &[Gr],
+    poks: &[PoK<Gr>],
+    ctxs: &[Vec<u8>],
+) -> bool {
+    let m = poks.len();
+    if pks.len() != m || ctxs.len() != m {
+        return false;
+    }
+
+    let mut fs_t = merlin::Transcript::new(SCHNORR_POK_DOM_SEP);
+    fs_t.append_u64(b"num-poks", m as u64);
+
+    let mut c = Vec::with_capacity(m);
+    for i in 0..m {
+        c.push(schnorr_challenge(g, &pks[i], &poks[i].0, &ctxs[i]));
+        fs_t.append_message(b"s", poks[i].1.to_bytes_le().as_slice());
+        fs_t.append_message(b"c", c[i].to_bytes_le().as_slice());
+    }
+
+    let mut buf = [0u8; 64];
+    fs_t.challenge_bytes(b"challenge_batch", &mut buf);
+    let gamma = hash_to_scalar(buf.as_slice(), SCHNORR_POK_HASH_TO_SCALAR_DST);
+
+    let mut bases = Vec::with_capacity(2 * m + 1);
+    let mut scalars = Vec::with_capacity(2 * m + 1);
+    let mut sum_s = Scalar::zero();
+    let mut gamma_i = Scalar::one();
+
+    for i in 0..m {
+        let (r, s) = &poks[i];
+        sum_s += gamma_i * s;
+
+        bases.push(*r);
+        scalars.push(gamma_i.neg());
+        bases.push(pks[i]);
+        scalars.push((gamma_i * c[i]).neg());
+
+        gamma_i *= gamma;
+    }
+
+    bases.push(*g);
+    scalars.push(sum_s);
+
+    Gr::multi_exp(&bases, &scalars) == Gr::identity()
+}
+
+/// Derives the Fiat-Shamir challenge $c$ for a Schnorr PoK $(R, s)$ of the discrete log of `pk`,
+/// bound to the context `ctx`.
+fn schnorr_challenge<Gr: HasMultiExp>(g: &Gr, pk: &Gr, r: &Gr, ctx: &[u8]) -> Scalar {
+    let mut fs_t = merlin::Transcript::new(SCHNORR_POK_DOM_SEP);
+    fs_t.append_message(b"ctx", ctx);
+    fs_t.append_message(b"g", g.to_compressed_bytes().as_slice());
+    fs_t.append_message(b"pk", pk.to_compressed_bytes().as_slice());
+    fs_t.append_message(b"R", r.to_compressed_bytes().as_slice());
+
+    let mut buf = [0u8; 64];
+    fs_t.challenge_bytes(b"challenge_c", &mut buf);
+
+    hash_to_scalar(buf.as_slice(), SCHNORR_POK_HASH_TO_SCALAR_DST)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pvss::schnorr::{pok_batch_verify, pok_prove, pok_verify};
+    use crate::utils::random::{random_g1_point, random_g2_point, random_scalar};
+    use blstrs::{G1Projective, G2Projective};
+    use group::Group;
+    use rand::thread_rng;
+    use std::ops::Mul;
+
+    #[test]
+    fn pok_prove_and_verify() {
+        let mut rng = thread_rng();
+        let g = random_g1_point(&mut rng);
+        let a = random_scalar(&mut rng);
+        let pk = g.mul(a);
+
+        let pok = pok_prove(&a, &g, &pk, b"ctx", &mut rng);
+        assert!(pok_verify(&g, &pk, &pok, b"ctx"));
+
+        // The PoK is bound to its context
+        assert!(!pok_verify(&g, &pk, &pok, b"other ctx"));
+
+        // ...and to its public key
+        assert!(!pok_verify(&g, &pk.double(), &pok, b"ctx"));
+    }
+
+    #[test]
+    fn pok_batch_verify_in_g2() {
+        let mut rng = thread_rng();
+        let g = random_g2_point(&mut rng);
+
+        let mut pks = vec![];
+        let mut poks = vec![];
+        let mut ctxs = vec![];
+        for i in 0..10u64 {
+            let a = random_scalar(&mut rng);
+            let pk: G2Projective = g.mul(a);
+            let ctx = i.to_le_bytes().to_vec();
+
+            poks.push(pok_prove(&a, &g, &pk, &ctx, &mut rng));
+            pks.push(pk);
+            ctxs.push(ctx);
+        }
+
+        assert!(pok_batch_verify(&g, &pks, &poks, &ctxs));
+
+        // Swapping two contexts should make batch verification fail
+        ctxs.swap(0, 1);
+        assert!(!pok_batch_verify(&g, &pks, &poks, &ctxs));
+        ctxs.swap(0, 1);
+
+        // Tampering with a PoK should make batch verification fail
+        poks[3].0 += g;
+        assert!(!pok_batch_verify(&g, &pks, &poks, &ctxs));
+
+        // An empty batch trivially verifies
+        assert!(pok_batch_verify::<G1Projective>(
+            &random_g1_point(&mut rng),
+            &[],
+            &[],
+            &[]
+        ));
+    }
+}
@@ -0,0 +1,353 @@
+use crate::pvss::contribution::{merge_contributors, random_contribution, verify_contributors};
+use crate::pvss::encryption_dlog;
+use crate::pvss::player::Player;
+use crate::pvss::scrape;
+use crate::pvss::scrape::fiat_shamir::FiatShamirProtocol;
+use crate::pvss::scrape::sok::SoK;
+use crate::pvss::scrape::transcript::{dual_code_low_degree_test, encryptions_check};
+use crate::pvss::threshold_config::ThresholdConfig;
+use crate::pvss::traits;
+use crate::pvss::traits::{AggregationError, SecretSharingConfig};
+use crate::pvss::Contribution;
+use crate::utils::random::{random_g1_point, random_g2_point};
+use aptos_crypto::{bls12381, CryptoMaterialError, ValidCryptoMaterial};
+use blstrs::{G1Projective, G2Projective, Scalar};
+use group::Group;
+use serde::{Deserialize, Serialize};
+use std::ops::Mul;
+
+/// A compact SCRAPE PVSS *transcript*, which drops the commitments $F_1, \ldots, F_{t-1}$ to the
+/// non-constant coefficients of $f(X)$ from a `scrape::Transcript`, saving $(t-1)$ $\mathbb{G}_1$
+/// elements.
+///
+/// This is possible because the SCRAPE dual-code low-degree test only needs $F_0$ and the $A_i$'s
+/// (see `LowDegreeTest::DualCode`), which is the test used to verify these transcripts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[allow(non_snake_case)]
+pub struct CompactTranscript {
+    /// Commitment to $f(0)$: $\hat{u}_2 = \hat{u}_1^{a_0}$
+    pub(crate) u2_hat: G2Projective,
+    /// Commitment to $f(0)$, which is the dealt public key: $F_0 = g_1^{a_0}$
+    pub(crate) F_0: G1Projective,
+    /// Commitments to the $n$ evaluations of $f(X)$: $g_1^{f(\omega^i)}$
+    pub(crate) A: Vec<G1Projective>,
+    /// $n$ encryptions, one for each player's share of $f(X)$: $ek^{f(\omega^i)}, \forall i\in[0,n)$
+    pub(crate) Y_hat: Vec<G2Projective>,
+    /// The contributions of all dealers who were aggregated into this transcript, with their PoKs
+    /// and signatures
+    pub(crate) contributions: Vec<Contribution>,
+    /// The SoKs of all dealers who dealt via `scrape::Transcript::deal_with_sok`
+    pub(crate) soks: Vec<SoK>,
+}
+
+impl From<scrape::Transcript> for CompactTranscript {
+    fn from(trx: scrape::Transcript) -> Self {
+        CompactTranscript {
+            u2_hat: trx.u2_hat,
+            F_0: trx.F[0],
+            A: trx.A,
+            Y_hat: trx.Y_hat,
+            contributions: trx.contributions,
+            soks: trx.soks,
+        }
+    }
+}
+
+impl ValidCryptoMaterial for CompactTranscript {
+    fn to_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&self)
+            .expect("unexpected error during compact SCRAPE PVSS transcript serialization")
+    }
+}
+
+impl TryFrom<&[u8]> for CompactTranscript {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bcs::from_bytes::<CompactTranscript>(bytes)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl traits::Transcript for CompactTranscript {
+    type SecretSharingConfig = ThresholdConfig;
+    type PvssPublicParameters = scrape::PublicParameters;
+    type SigningSecretKey = bls12381::PrivateKey;
+    type SigningPubKey = bls12381::PublicKey;
+    type DealtSecretKeyShare = scrape::DealtSecretKeyShare;
+    type DealtPubKeyShare = scrape::DealtPubKeyShare;
+    type DealtSecretKey = scrape::DealtSecretKey;
+    type DealtPubKey = scrape::DealtPubKey;
+    type InputSecret = scrape::InputSecret;
+    type EncryptPubKey = encryption_dlog::g2::EncryptPubKey;
+    type DecryptPrivKey = encryption_dlog::g2::DecryptPrivKey;
+
+    fn scheme_name() -> String {
+        "compact_scrape_sk_in_g2".to_string()
+    }
+
+    fn deal<R: rand_core::RngCore + rand_core::CryptoRng>(
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
+        eks: &Vec<Self::EncryptPubKey>,
+        s: Self::InputSecret,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> Self {
+        // TODO(Performance): We needlessly compute F_1, \ldots, F_{t-1} here.
+        CompactTranscript::from(scrape::Transcript::deal(
+            sc, pp, ssk, eks, s, dealer, dst, rng,
+        ))
+    }
+
+    fn verify(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        spks: &Vec<Self::SigningPubKey>,
+        eks: &Vec<Self::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> bool {
+        if eks.len() != sc.n {
+            return false;
+        }
+
+        if !traits::Transcript::has_valid_sizes(self, sc) {
+            return false;
+        }
+
+        if !verify_contributors(
+            pp.get_commitment_base(),
+            &self.F_0,
+            &self.contributions,
+            &self.soks,
+            spks,
+            dst,
+        ) {
+            return false;
+        }
+
+        // Derive challenges deterministically via Fiat-Shamir; it's easier to debug for distributed systems
+        let (beta, r) = self.fiat_shamir(sc, pp, eks, dst);
+
+        if !dual_code_low_degree_test(sc, &self.F_0, &self.A, &beta) {
+            return false;
+        }
+
+        encryptions_check(
+            sc,
+            pp,
+            eks,
+            &self.u2_hat,
+            &self.F_0,
+            &self.A,
+            &self.Y_hat,
+            &r,
+        )
+    }
+
+    fn has_valid_sizes(&self, sc: &ThresholdConfig) -> bool {
+        self.A.len() == sc.n && self.Y_hat.len() == sc.n
+    }
+
+    fn aggregate_with(
+        &mut self,
+        sc: &ThresholdConfig,
+        other: &CompactTranscript,
+    ) -> Result<(), AggregationError> {
+        if !traits::Transcript::has_valid_sizes(self, sc)
+            || !traits::Transcript::has_valid_sizes(other, sc)
+        {
+            return Err(AggregationError::InvalidSizes);
+        }
+
+        merge_contributors(
+            &mut self.contributions,
+            &mut self.soks,
+            &other.contributions,
+            &other.soks,
+        )
+        .map_err(AggregationError::DuplicateDealer)?;
+
+        self.u2_hat += other.u2_hat;
+        self.F_0 += other.F_0;
+
+        for i in 0..sc.n {
+            self.A[i] += other.A[i];
+            self.Y_hat[i] += other.Y_hat[i];
+        }
+
+        Ok(())
+    }
+
+    fn get_dealers(&self) -> Vec<Player> {
+        self.contributions
+            .iter()
+            .map(|c| c.get_dealer())
+            .chain(self.soks.iter().map(|sok| sok.get_dealer()))
+            .cloned()
+            .collect()
+    }
+
+    fn get_dealt_public_key(&self) -> scrape::DealtPubKey {
+        scrape::DealtPubKey::new(self.F_0)
+    }
+
+    fn get_public_key_share(
+        &self,
+        _sc: &ThresholdConfig,
+        player_id: &Player,
+    ) -> Self::DealtPubKeyShare {
+        scrape::DealtPubKeyShare(Self::DealtPubKey::new(self.A[player_id.id]))
+    }
+
+    fn decrypt_own_share(
+        &self,
+        _sc: &ThresholdConfig,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+    ) -> (Self::DealtSecretKeyShare, Self::DealtPubKeyShare) {
+        let ctxt = self.Y_hat[player_id.id]; // \hat{Y}_i = \ek_i^{f(\omega^i)}
+        let secret_key_share = ctxt.mul(dk.dk); // Y_i^{\dk_i} = \hat{h}_1^{f(\omega^i)} (because \ek_i = \hat{h}_1^{\dk_i^{-1}})
+        let verification_key_share = self.A[player_id.id]; // g_1^{f(\omega^i})
+
+        (
+            scrape::DealtSecretKeyShare(Self::DealtSecretKey::new(secret_key_share)),
+            scrape::DealtPubKeyShare(Self::DealtPubKey::new(verification_key_share)),
+        )
+    }
+
+    #[allow(non_snake_case)]
+    fn generate<R>(sc: &ThresholdConfig, rng: &mut R) -> Self
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        // TODO(rand_core_hell): See `scrape::Transcript::generate` for why we use doublings here.
+        let g2 = random_g2_point(rng);
+
+        let mut acc_g2 = g2;
+        let g2_vec = (0..sc.n)
+            .map(|_| {
+                acc_g2 = acc_g2.double();
+                acc_g2
+            })
+            .collect::<Vec<G2Projective>>();
+
+        let mut acc_g1 = random_g1_point(rng);
+        let g1_vec = (0..sc.n)
+            .map(|_| {
+                acc_g1 = acc_g1.double();
+                acc_g1
+            })
+            .collect::<Vec<G1Projective>>();
+
+        let F_0 = random_g1_point(rng);
+
+        CompactTranscript {
+            u2_hat: g2,
+            F_0,
+            A: g1_vec,
+            Y_hat: g2_vec,
+            contributions: vec![random_contribution(&sc.get_player(0), &F_0, rng)],
+            soks: vec![],
+        }
+    }
+}
+
+impl CompactTranscript {
+    /// Securely derives the Fiat-Shamir challenges for the dual-code low-degree test and for the
+    /// multipairing, via Merlin.
+    fn fiat_shamir(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> (Scalar, Scalar) {
+        // TODO(Security): Audit this
+        let mut fs_t = merlin::Transcript::new(dst);
+        fs_t.pvss_domain_sep(sc);
+        fs_t.append_public_parameters(pp);
+        fs_t.append_encryption_keys(eks);
+
+        fs_t.append_compact_transcript(&self);
+        (
+            fs_t.challenge_dual_code_scalar(),
+            fs_t.challenge_multipairing_scalar(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::constants::DST_PVSS_TESTING_APP;
+    use crate::pvss::scrape::{CompactTranscript, DealtPubKey};
+    use crate::pvss::test_utils;
+    use crate::pvss::test_utils::get_threshold_config_and_rng;
+    use crate::pvss::threshold_config::ThresholdConfig;
+    use crate::pvss::traits::transcript::Transcript as UniformTranscript;
+    use crate::pvss::traits::SecretSharingConfig;
+    use aptos_crypto::{Uniform, ValidCryptoMaterial};
+    use rand::thread_rng;
+    use std::ops::Mul;
+
+    #[test]
+    fn transcript_serialization() {
+        let sc = ThresholdConfig::new(10, 20);
+        let mut rng = thread_rng();
+
+        let trx = CompactTranscript::generate(&sc, &mut rng);
+
+        let serialized = trx.to_bytes();
+        let deserialized = CompactTranscript::try_from(serialized.as_slice())
+            .expect("serialized compact SCRAPE transcript should deserialize correctly");
+
+        assert_eq!(trx, deserialized);
+    }
+
+    #[test]
+    fn aggregated_transcript_verifies() {
+        let (sc, mut rng) = get_threshold_config_and_rng(5, 12);
+        let (pp, _, eks, _, _) = test_utils::setup_dealing::<CompactTranscript>(&sc);
+        let (ssks, spks) = test_utils::setup_signing::<CompactTranscript>(&sc);
+
+        let s1 = <CompactTranscript as UniformTranscript>::InputSecret::generate(&mut rng);
+        let s2 = <CompactTranscript as UniformTranscript>::InputSecret::generate(&mut rng);
+        let expected_pk = DealtPubKey::new(
+            pp.get_commitment_base()
+                .mul(s1.get_secret_a() + s2.get_secret_a()),
+        );
+
+        let mut trx = CompactTranscript::deal(
+            &sc,
+            &pp,
+            &ssks[0],
+            &eks,
+            s1,
+            &sc.get_player(0),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+        let other = CompactTranscript::deal(
+            &sc,
+            &pp,
+            &ssks[1],
+            &eks,
+            s2,
+            &sc.get_player(1),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+
+        trx.aggregate_with(&sc, &other).unwrap();
+
+        assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+        assert_eq!(trx.contributions.len(), 2);
+        assert_eq!(
+            trx.get_dealt_public_key().to_bytes(),
+            expected_pk.to_bytes()
+        );
+    }
+}
@@ -1,14 +1,18 @@
 use crate::pvss::encryption_dlog::g2::EncryptPubKey;
+use crate::pvss::player::Player;
 use crate::pvss::scrape;
 use crate::pvss::scrape::public_parameters::PublicParameters;
+use crate::pvss::scrape::sok::SoK;
 use crate::pvss::threshold_config::ThresholdConfig;
 use crate::utils::fiat_shamir;
 use crate::utils::hash_to_scalar;
 use aptos_crypto::ValidCryptoMaterial;
-use blstrs::{G2Projective, Scalar};
+use blstrs::{G1Projective, G2Projective, Scalar};
+use group::Group;
 
 pub const PVSS_DOM_SEP: &[u8; 21] = b"APTOS_SCRAPE_PVSS_DST";
 pub const PVSS_HASH_TO_SCALAR_DST: &[u8; 36] = b"APTOS_SCRAPE_PVSS_HASH_TO_SCALAR_DST";
+pub const SOK_DOM_SEP: &[u8; 20] = b"APTOS_SCRAPE_SOK_DST";
 
 #[allow(non_snake_case)]
 pub trait FiatShamirProtocol {
@@ -25,12 +29,50 @@ pub trait FiatShamirProtocol {
     /// Appends the transcript
     fn append_transcript(&mut self, trx: &scrape::Transcript);
 
+    /// Appends the compact transcript
+    fn append_compact_transcript(&mut self, trx: &scrape::CompactTranscript);
+
     /// Compute the Fiat-Shamir challenge `\alpha` for doing the Lagrange-based consistency check
     fn challenge_lagrange_scalar(&mut self) -> Scalar;
 
     /// Compute the Fiat-Shamir challenge `r` for combining pairings in the multipairing using
     /// coefficients $1, r, r^2, r^3, \ldots$
     fn challenge_multipairing_scalar(&mut self) -> Scalar;
+
+    /// Compute the Fiat-Shamir challenge `\beta` for the SCRAPE dual-code low-degree test, whose
+    /// dual polynomial has coefficients $1, \beta, \beta^2, \ldots, \beta^{n-t}$
+    fn challenge_dual_code_scalar(&mut self) -> Scalar;
+
+    /// Appends all the transcripts being batch-verified.
+    fn append_transcripts(&mut self, trxs: &[scrape::Transcript]);
+
+    /// Compute the Fiat-Shamir challenge $\rho$ for batch-verifying transcripts by combining the
+    /// checks of the $k$th transcript using the coefficient $\rho^k$
+    fn challenge_batch_scalar(&mut self) -> Scalar;
+
+    /// Append a domain separator for a dealer's SoK, which locks in the `dealer`.
+    fn sok_domain_sep(&mut self, dealer: &Player);
+
+    /// Appends the SoK statement: the bases $g_1$ and $h$, the dealer's commitment `comm` $= g_1^a$
+    /// and its signing public key `pk` $= h^{sk}$.
+    fn append_sok_statement(&mut self, g1: &G1Projective, comm: &G1Projective, pk: &G1Projective);
+
+    /// Appends the SoK's Schnorr commitments `R` $= g_1^{k_a}$ and `R_pk` $= h^{k_{sk}}$.
+    fn append_sok_commitments(&mut self, R: &G1Projective, R_pk: &G1Projective);
+
+    /// Appends all the SoKs being batch-verified.
+    fn append_soks(&mut self, soks: &[SoK]);
+
+    /// Appends the signing public keys `pks` that the SoKs being batch-verified are checked against,
+    /// where `pks[i]` is the one of the $i$th SoK's dealer.
+    fn append_sok_signing_pubkeys(&mut self, pks: &Vec<G1Projective>);
+
+    /// Compute the Fiat-Shamir challenge $c$ shared by both statements of an SoK
+    fn challenge_sok_scalar(&mut self) -> Scalar;
+
+    /// Compute the Fiat-Shamir challenge $\gamma$ for batch-verifying SoKs using coefficients
+    /// $1, \gamma, \gamma^2, \ldots$
+    fn challenge_sok_batch_scalar(&mut self) -> Scalar;
 }
 
 #[allow(non_snake_case)]
@@ -60,6 +102,10 @@ impl FiatShamirProtocol for merlin::Transcript {
         self.append_message(b"transcript", trx.to_bytes().as_slice());
     }
 
+    fn append_compact_transcript(&mut self, trx: &scrape::CompactTranscript) {
+        self.append_message(b"compact-transcript", trx.to_bytes().as_slice());
+    }
+
     fn challenge_lagrange_scalar(&mut self) -> Scalar {
         let mut buf = [0u8; 64];
         self.challenge_bytes(b"challenge_alpha", &mut buf);
@@ -73,4 +119,70 @@ impl FiatShamirProtocol for merlin::Transcript {
 
         hash_to_scalar(buf.as_slice(), PVSS_HASH_TO_SCALAR_DST)
     }
+
+    fn challenge_dual_code_scalar(&mut self) -> Scalar {
+        let mut buf = [0u8; 64];
+        self.challenge_bytes(b"challenge_dual_code", &mut buf);
+
+        hash_to_scalar(buf.as_slice(), PVSS_HASH_TO_SCALAR_DST)
+    }
+
+    fn append_transcripts(&mut self, trxs: &[scrape::Transcript]) {
+        self.append_u64(b"num-transcripts", trxs.len() as u64);
+        for trx in trxs {
+            self.append_transcript(trx);
+        }
+    }
+
+    fn challenge_batch_scalar(&mut self) -> Scalar {
+        let mut buf = [0u8; 64];
+        self.challenge_bytes(b"challenge_batch", &mut buf);
+
+        hash_to_scalar(buf.as_slice(), PVSS_HASH_TO_SCALAR_DST)
+    }
+
+    fn sok_domain_sep(&mut self, dealer: &Player) {
+        self.append_message(b"dom-sep", SOK_DOM_SEP);
+        self.append_u64(b"dealer", dealer.get_id() as u64);
+    }
+
+    fn append_sok_statement(&mut self, g1: &G1Projective, comm: &G1Projective, pk: &G1Projective) {
+        fiat_shamir::append_g1_point(self, b"g1", g1);
+        fiat_shamir::append_g1_point(self, b"h", &G1Projective::generator());
+        fiat_shamir::append_g1_point(self, b"comm", comm);
+        fiat_shamir::append_g1_point(self, b"pk", pk);
+    }
+
+    fn append_sok_commitments(&mut self, R: &G1Projective, R_pk: &G1Projective) {
+        fiat_shamir::append_g1_point(self, b"R", R);
+        fiat_shamir::append_g1_point(self, b"R_pk", R_pk);
+    }
+
+    fn append_soks(&mut self, soks: &[SoK]) {
+        self.append_message(b"dom-sep", SOK_DOM_SEP);
+        self.append_message(
+            b"soks",
+            bcs::to_bytes(soks)
+                .expect("unexpected error during SoK serialization")
+                .as_slice(),
+        );
+    }
+
+    fn append_sok_signing_pubkeys(&mut self, pks: &Vec<G1Projective>) {
+        fiat_shamir::append_g1_vector(self, b"signing-pubkeys", pks);
+    }
+
+    fn challenge_sok_scalar(&mut self) -> Scalar {
+        let mut buf = [0u8; 64];
+        self.challenge_bytes(b"challenge_sok", &mut buf);
+
+        hash_to_scalar(buf.as_slice(), PVSS_HASH_TO_SCALAR_DST)
+    }
+
+    fn challenge_sok_batch_scalar(&mut self) -> Scalar {
+        let mut buf = [0u8; 64];
+        self.challenge_bytes(b"challenge_sok_batch", &mut buf);
+
+        hash_to_scalar(buf.as_slice(), PVSS_HASH_TO_SCALAR_DST)
+    }
 }
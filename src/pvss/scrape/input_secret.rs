@@ -6,6 +6,7 @@ use crate::utils::random::random_scalar;
 use aptos_crypto::traits::Uniform;
 use aptos_crypto_derive::{SilentDebug, SilentDisplay};
 use blstrs::Scalar;
+use ff::Field;
 use rand_core::{CryptoRng, RngCore};
 use std::ops::Mul;
 
@@ -29,6 +30,12 @@ static_assertions::assert_not_impl_any!(InputSecret: Clone);
 //
 
 impl InputSecret {
+    /// The zero secret, which is dealt to refresh the shares of a previously-dealt secret (see
+    /// `refresh::Refreshable`).
+    pub(crate) fn zero() -> Self {
+        InputSecret { a: Scalar::zero() }
+    }
+
     pub fn get_secret_a(&self) -> &Scalar {
         &self.a
     }
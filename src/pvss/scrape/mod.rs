@@ -1,12 +1,16 @@
+pub(crate) mod compact_transcript;
 mod fiat_shamir;
 mod input_secret;
 mod public_parameters;
+pub(crate) mod sok;
 pub(crate) mod transcript;
 
-use crate::pvss::dealt_pub_key::g1::DealtPubKey;
-use crate::pvss::dealt_pub_key_share::g1::DealtPubKeyShare;
-use crate::pvss::dealt_secret_key::g2::DealtSecretKey;
-use crate::pvss::dealt_secret_key_share::g2::DealtSecretKeyShare;
-use input_secret::InputSecret;
-use public_parameters::PublicParameters;
-pub use transcript::Transcript;
+pub(crate) use crate::pvss::dealt_pub_key::g1::DealtPubKey;
+pub(crate) use crate::pvss::dealt_pub_key_share::g1::DealtPubKeyShare;
+pub(crate) use crate::pvss::dealt_secret_key::g2::DealtSecretKey;
+pub(crate) use crate::pvss::dealt_secret_key_share::g2::DealtSecretKeyShare;
+pub use compact_transcript::CompactTranscript;
+pub(crate) use input_secret::InputSecret;
+pub(crate) use public_parameters::PublicParameters;
+pub use sok::SoK;
+pub use transcript::{LowDegreeTest, Transcript};
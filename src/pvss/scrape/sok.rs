@@ -0,0 +1,291 @@
+//! A dealer's *signature of knowledge (SoK)* over its contribution to a SCRAPE transcript, à la
+//! [GJM+21]. It replaces the separate Schnorr PoK and BLS signature in a `Contribution` with a single
+//! proof that both authenticates the dealer and proves knowledge of the dealt secret.
+//!
+//! Specifically, the SoK is a Schnorr proof of knowledge of $(a, sk)$ such that $C = g_1^a$ and
+//! $pk = h^{sk}$, where $C$ is the dealer's commitment to its input secret $a$, $pk$ is its BLS
+//! signing public key and $h$ is the generator of $\mathbb{G}_1$. Both statements share a single
+//! Fiat-Shamir challenge, which is bound to the dealer's identity and to the DST.
+//!
+//! NOTE: This reuses the dealer's BLS signing key $sk$ as the witness of a Schnorr proof. This is
+//! safe in the sense of *joint security* [HP01]: given oracles for both BLS signatures and SoKs
+//! under the same key, an adversary can forge neither. Following [HP01], it suffices to show that
+//! each scheme's oracle can be simulated from the public key alone, via a random oracle that the
+//! other scheme does not use:
+//!
+//!  1. An SoK can be simulated from $(C, pk)$ by picking $c, s, s'$ at random, setting
+//!     $R = g_1^s C^{-c}$ and $R' = h^{s'} pk^{-c}$, and programming the SoK's Fiat-Shamir oracle to
+//!     output $c$. This oracle is Merlin's (i.e., Keccak-based STROBE) under the PVSS DST and the
+//!     SoK's own labels, which is independent of the hash-to-curve oracle of BLS signatures (i.e.,
+//!     SHA-256-based `expand_message_xmd` under the BLS DST). So, the co-CDH reduction for BLS
+//!     signatures [BDN18] goes through unchanged for an adversary who also gets SoKs.
+//!  2. A BLS signature on $m$ can be simulated from $(pk, g_2^{sk})$ by programming the BLS
+//!     hash-to-curve oracle to $H(m) = g_2^r$ for a random $r$ and returning $(g_2^{sk})^r$, which
+//!     does not touch the SoK's oracle. So, by the forking lemma [PS00], an adversary who forges an
+//!     SoK for an honest dealer's $pk$, even given BLS signatures, can be rewound to extract $sk$
+//!     from $(h^{sk}, g_2^{sk})$, which is hard under the co-discrete-log assumption that co-CDH
+//!     already implies.
+//!
+//! Dealers who do not want their signing key used this way can keep dealing via `Contribution`s.
+//!
+//! [HP01] Securely Combining Public-Key Cryptosystems; by Stuart Haber and Benny Pinkas; in ACM
+//! CCS'01; 2001
+//!
+//! [BDN18] Compact Multi-Signatures for Smaller Blockchains; by Dan Boneh, Manu Drijvers and Gregory
+//! Neven; in ASIACRYPT'18; 2018
+//!
+//! [PS00] Security Arguments for Digital Signatures and Blind Signatures; by David Pointcheval and
+//! Jacques Stern; in Journal of Cryptology; 2000
+//!
+//! [GJM+21] Aggregatable Distributed Key Generation; by Kobi Gurkan, Philipp Jovanovic, Mary Maller,
+//! Sarah Meiklejohn, Gilad Stern and Alin Tomescu; in EUROCRYPT'21; 2021
+
+use crate::pvss::player::Player;
+use crate::pvss::scrape::fiat_shamir::FiatShamirProtocol;
+use crate::utils::random::random_scalars;
+use aptos_crypto::{bls12381, CryptoMaterialError};
+use blstrs::{G1Projective, Scalar};
+use ff::Field;
+use group::Group;
+use serde::{Deserialize, Serialize};
+use std::ops::{Mul, Neg};
+
+/// A dealer's SoK of $(a, sk)$ such that $C = g_1^a$ and $pk = h^{sk}$. See the module docs.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[allow(non_snake_case)]
+pub struct SoK {
+    /// The player who dealt this contribution
+    pub(crate) dealer: Player,
+    /// The commitment $C = g_1^a$ to the dealer's input secret $a$
+    pub(crate) comm: G1Projective,
+    /// The Schnorr commitment $R = g_1^{k_a}$ for $a$
+    pub(crate) R: G1Projective,
+    /// The Schnorr commitment $R' = h^{k_{sk}}$ for $sk$
+    pub(crate) R_pk: G1Projective,
+    /// The response $s = k_a + c \cdot a$
+    pub(crate) s: Scalar,
+    /// The response $s' = k_{sk} + c \cdot sk$
+    pub(crate) s_sk: Scalar,
+}
+
+impl SoK {
+    /// Creates an SoK on behalf of `dealer` for the input secret `a`, committed as $g_1^a$, and for the
+    /// BLS signing key `ssk`. Returns an error if `ssk` does not encode a valid scalar.
+    #[allow(non_snake_case)]
+    pub(crate) fn new<R: rand_core::RngCore + rand_core::CryptoRng>(
+        g1: &G1Projective,
+        a: &Scalar,
+        ssk: &bls12381::PrivateKey,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> Result<Self, CryptoMaterialError> {
+        let sk = Option::<Scalar>::from(Scalar::from_bytes_be(&ssk.to_bytes()))
+            .ok_or(CryptoMaterialError::DeserializationError)?;
+        let h = G1Projective::generator();
+
+        let comm = g1.mul(a);
+        let pk = h.mul(sk);
+
+        let k = random_scalars(2, rng);
+        let R = g1.mul(k[0]);
+        let R_pk = h.mul(k[1]);
+
+        let c = sok_challenge(g1, dealer, &comm, &pk, &R, &R_pk, dst);
+
+        Ok(SoK {
+            dealer: dealer.clone(),
+            comm,
+            R,
+            R_pk,
+            s: k[0] + c * a,
+            s_sk: k[1] + c * sk,
+        })
+    }
+
+    pub fn get_dealer(&self) -> &Player {
+        &self.dealer
+    }
+}
+
+/// Verifies many SoKs at once, where the signing public key of dealer $i$ is `spks[i]`.
+///
+/// We need to check $g_1^{s_i} = R_i C_i^{c_i}$ and $h^{s'_i} = R'_i pk_i^{c_i}$ for all $i$.
+/// Instead, we combine all $2m$ equations using the powers of a Fiat-Shamir challenge $\gamma$ and
+/// check them with a single size-$(4m + 2)$ multiexp.
+pub(crate) fn sok_batch_verify(
+    g1: &G1Projective,
+    soks: &[SoK],
+    spks: &Vec<bls12381::PublicKey>,
+    dst: &'static [u8],
+) -> bool {
+    let m = soks.len();
+    let h = G1Projective::generator();
+
+    let mut pks = Vec::with_capacity(m);
+    for sok in soks {
+        if sok.dealer.get_id() >= spks.len() {
+            return false;
+        }
+
+        match Option::<G1Projective>::from(G1Projective::from_compressed(
+            &spks[sok.dealer.get_id()].to_bytes(),
+        )) {
+            Some(pk) => pks.push(pk),
+            None => return false,
+        }
+    }
+
+    // Bind \gamma to the statements being proven, including the public keys the SoKs are checked
+    // against, and not just to the SoKs themselves.
+    let mut fs_t = merlin::Transcript::new(dst);
+    fs_t.append_soks(soks);
+    fs_t.append_sok_signing_pubkeys(&pks);
+    let gamma = fs_t.challenge_sok_batch_scalar();
+
+    let mut bases = Vec::with_capacity(4 * m + 2);
+    let mut scalars = Vec::with_capacity(4 * m + 2);
+    let mut sum_s = Scalar::zero();
+    let mut sum_s_sk = Scalar::zero();
+    let mut gamma_i = Scalar::one();
+
+    for (sok, pk) in soks.iter().zip(pks.iter()) {
+        let c = sok_challenge(g1, &sok.dealer, &sok.comm, pk, &sok.R, &sok.R_pk, dst);
+
+        // g_1^{s_i} = R_i C_i^{c_i}, weighed by \gamma^{2i}
+        sum_s += gamma_i * sok.s;
+        bases.push(sok.R);
+        scalars.push(gamma_i.neg());
+        bases.push(sok.comm);
+        scalars.push((gamma_i * c).neg());
+        gamma_i *= gamma;
+
+        // h^{s'_i} = R'_i pk_i^{c_i}, weighed by \gamma^{2i+1}
+        sum_s_sk += gamma_i * sok.s_sk;
+        bases.push(sok.R_pk);
+        scalars.push(gamma_i.neg());
+        bases.push(*pk);
+        scalars.push((gamma_i * c).neg());
+        gamma_i *= gamma;
+    }
+
+    bases.push(*g1);
+    scalars.push(sum_s);
+    bases.push(h);
+    scalars.push(sum_s_sk);
+
+    G1Projective::multi_exp(&bases, &scalars) == G1Projective::identity()
+}
+
+/// Derives the Fiat-Shamir challenge $c$ shared by both statements of an SoK.
+#[allow(non_snake_case)]
+fn sok_challenge(
+    g1: &G1Projective,
+    dealer: &Player,
+    comm: &G1Projective,
+    pk: &G1Projective,
+    R: &G1Projective,
+    R_pk: &G1Projective,
+    dst: &'static [u8],
+) -> Scalar {
+    let mut fs_t = merlin::Transcript::new(dst);
+    fs_t.sok_domain_sep(dealer);
+    fs_t.append_sok_statement(g1, comm, pk);
+    fs_t.append_sok_commitments(R, R_pk);
+
+    fs_t.challenge_sok_scalar()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::constants::DST_PVSS_TESTING_APP;
+    use crate::pvss::player::Player;
+    use crate::pvss::scrape::sok::{sok_batch_verify, SoK};
+    use crate::utils::random::{random_g1_point, random_scalar};
+    use aptos_crypto::{bls12381, Uniform};
+    use blstrs::Scalar;
+    use ff::Field;
+    use rand::thread_rng;
+
+    #[test]
+    fn sok_batch_verify_test() {
+        let mut rng = thread_rng();
+        let g1 = random_g1_point(&mut rng);
+
+        let ssks = (0..5)
+            .map(|_| bls12381::PrivateKey::generate(&mut rng))
+            .collect::<Vec<bls12381::PrivateKey>>();
+        let spks = ssks
+            .iter()
+            .map(bls12381::PublicKey::from)
+            .collect::<Vec<bls12381::PublicKey>>();
+
+        let mut soks = (0..5)
+            .map(|i| {
+                SoK::new(
+                    &g1,
+                    &random_scalar(&mut rng),
+                    &ssks[i],
+                    &Player { id: i },
+                    &DST_PVSS_TESTING_APP[..],
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<SoK>>();
+
+        assert!(sok_batch_verify(
+            &g1,
+            &soks,
+            &spks,
+            &DST_PVSS_TESTING_APP[..]
+        ));
+
+        // An SoK made with someone else's signing key does not verify
+        let mut bad_soks = soks.clone();
+        bad_soks[2] = SoK::new(
+            &g1,
+            &random_scalar(&mut rng),
+            &ssks[3],
+            &Player { id: 2 },
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        )
+        .unwrap();
+        assert!(!sok_batch_verify(
+            &g1,
+            &bad_soks,
+            &spks,
+            &DST_PVSS_TESTING_APP[..]
+        ));
+
+        // An SoK is bound to its dealer
+        let mut bad_soks = soks.clone();
+        bad_soks.swap(0, 1);
+        bad_soks[0].dealer = Player { id: 0 };
+        bad_soks[1].dealer = Player { id: 1 };
+        assert!(!sok_batch_verify(
+            &g1,
+            &bad_soks,
+            &spks,
+            &DST_PVSS_TESTING_APP[..]
+        ));
+
+        // Tampering with either response makes batch verification fail
+        soks[4].s_sk += Scalar::one();
+        assert!(!sok_batch_verify(
+            &g1,
+            &soks,
+            &spks,
+            &DST_PVSS_TESTING_APP[..]
+        ));
+        soks[4].s_sk -= Scalar::one();
+        soks[4].s += Scalar::one();
+        assert!(!sok_batch_verify(
+            &g1,
+            &soks,
+            &spks,
+            &DST_PVSS_TESTING_APP[..]
+        ));
+    }
+}
@@ -3,18 +3,25 @@ use crate::algebra::fft::{fft, fft_assign};
 use crate::algebra::lagrange::{
     all_lagrange_denominators, all_n_lagrange_coefficients, lagrange_coefficients,
 };
+use crate::pvss::contribution::{
+    contributors_add_up, merge_contributors, random_contribution, verify_contributor_proofs,
+    verify_contributors, Contribution,
+};
 use crate::pvss::encryption_dlog;
 use crate::pvss::player::Player;
 use crate::pvss::scrape;
 use crate::pvss::scrape::fiat_shamir::FiatShamirProtocol;
+use crate::pvss::scrape::sok::SoK;
 use crate::pvss::threshold_config::ThresholdConfig;
 use crate::pvss::traits;
-use crate::utils::is_power_of_two;
+use crate::pvss::traits::{AggregationError, SecretSharingConfig};
 use crate::utils::random::{random_g1_point, random_g2_point, random_scalars};
-use aptos_crypto::{CryptoMaterialError, ValidCryptoMaterial};
+use crate::utils::{is_power_of_two, HasMultiExp};
+use aptos_crypto::{bls12381, CryptoMaterialError, ValidCryptoMaterial};
 use blstrs::{Bls12, G1Affine, G1Projective, G2Prepared, G2Projective, Gt, Scalar};
 use ff::Field;
 use group::{Curve, Group};
+use more_asserts::assert_le;
 use pairing::{MillerLoopResult, MultiMillerLoop};
 use serde::{Deserialize, Serialize};
 use std::ops::{Mul, Neg};
@@ -51,6 +58,200 @@ pub fn get_dual_code_word<R: rand_core::RngCore + rand_core::CryptoRng>(
     vf
 }
 
+/// Returns the dual code word $(v_0 f(0), v_1 f(\omega^0), \ldots, v_n f(\omega^{n-1}))$ for the
+/// SCRAPE low-degree test on the *extended* code word $(p(0), p(\omega^0), \ldots, p(\omega^{n-1}))$
+/// of a polynomial $p(X)$ of degree at most $t-1$. The dual polynomial $f(X)$ is given by its
+/// coefficients in `f` and must have degree at most $n - t$.
+///
+/// Here, $v_j = 1 / \prod_{k \ne j} (x_j - x_k)$ over the $n+1$ evaluation points $x_0 = 0$ and
+/// $x_{i+1} = \omega^i$.
+pub fn get_extended_dual_code_word(
+    f: &Vec<Scalar>,
+    batch_dom: &BatchEvaluationDomain,
+    n: usize,
+) -> Vec<Scalar> {
+    assert_le!(f.len(), n);
+
+    // Compute f(\omega^i) for all i's
+    let dom = batch_dom.get_subdomain(n);
+    let mut f_evals = fft(f, &dom);
+    f_evals.truncate(n);
+
+    let mut vf = Vec::with_capacity(n + 1);
+
+    if is_power_of_two(n) {
+        // Here, A(X) = X^n - 1 and so v_0 = 1 / A(0) = -1 and v_{i+1} = 1 / (\omega^i A'(\omega^i)) = 1/n.
+        // We scale all the v_j's by n, which yields an equally-good dual code word.
+        vf.push(f[0].mul(Scalar::from(n as u64)).neg());
+        vf.extend(f_evals);
+    } else {
+        // Compute v_0 = 1 / \prod_{k \in [0, n-1]} (0 - \omega^k)
+        let mut v_0 = Scalar::one();
+        let mut omega_k = Scalar::one();
+        for _ in 0..n {
+            v_0 *= omega_k.neg();
+            omega_k *= dom.omega;
+        }
+        vf.push(v_0.invert().unwrap().mul(f[0]));
+
+        // Compute v_{i+1} = 1 / (\omega^i \prod_{k \ne i, k \in [0, n-1]} (\omega^i - \omega^k)), for all i's
+        let denoms = all_lagrange_denominators(&batch_dom, n);
+        let mut omega_inverse_i = Scalar::one();
+        for i in 0..n {
+            vf.push(denoms[i] * omega_inverse_i * f_evals[i]);
+            omega_inverse_i *= dom.omega_inverse;
+        }
+    }
+
+    debug_assert_eq!(vf.len(), n + 1);
+    vf
+}
+
+/// The SCRAPE dual-code low-degree test [CD17]: checks that $F_0, A_0, \ldots, A_{n-1}$ commit to
+/// the evaluations at $0, \omega^0, \ldots, \omega^{n-1}$ of a polynomial of degree at most $t-1$.
+///
+/// The dual polynomial has coefficients $1, \beta, \beta^2, \ldots, \beta^{n-t}$, for a Fiat-Shamir
+/// challenge $\beta$, so the check is a single size-$(n+1)$ multiexp that does not need the
+/// commitments $F_1, \ldots, F_{t-1}$ to the other coefficients.
+///
+/// Works in either $\mathbb{G}_1$ or $\mathbb{G}_2$, depending on where the commitments live.
+#[allow(non_snake_case)]
+pub(crate) fn dual_code_low_degree_test<Gr: HasMultiExp>(
+    sc: &ThresholdConfig,
+    F_0: &Gr,
+    A: &Vec<Gr>,
+    beta: &Scalar,
+) -> bool {
+    debug_assert_eq!(A.len(), sc.n);
+
+    // \beta^0, \beta^1, \ldots, \beta^{n-t}
+    let mut f = Vec::with_capacity(sc.n - sc.t + 1);
+    f.push(Scalar::one());
+    for _ in 0..sc.n - sc.t {
+        f.push(f.last().unwrap() * beta);
+    }
+
+    let vf = get_extended_dual_code_word(&f, sc.get_batch_evaluation_domain(), sc.n);
+
+    //
+    // Need to do a multiexp to verify the extended code word is orthogonal to the dual code word:
+    //
+    //      F_0^{v_0 f(0)} \prod_{i \in [n]} A_i^{v_{i+1} f(\omega^i)} = 1
+    //
+    let bases = [F_0.clone()]
+        .into_iter()
+        .chain(A.iter().map(|p| p.clone()))
+        .collect::<Vec<Gr>>();
+
+    debug_assert_eq!(bases.len(), vf.len());
+
+    let res = Gr::multi_exp(&bases, &vf);
+
+    res == Gr::identity()
+}
+
+/// Checks that (1) each $\hat{Y}_i$ encrypts the share committed in $A_i$ under $ek_i$ and (2) that
+/// $\hat{u}_2$ commits to the same secret as $F_0$, via a single multipairing whose terms are combined
+/// using the powers of the Fiat-Shamir challenge `r`.
+#[allow(non_snake_case)]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn encryptions_check(
+    sc: &ThresholdConfig,
+    pp: &scrape::PublicParameters,
+    eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+    u2_hat: &G2Projective,
+    F_0: &G1Projective,
+    A: &Vec<G1Projective>,
+    Y_hat: &Vec<G2Projective>,
+    r: &Scalar,
+) -> bool {
+    //
+    // Correctness of encryptions check
+    // (This could be done via DLEQ proofs too.)
+    //
+
+    // We need to check the following equations hold:
+    //
+    //     e(g_1, \hat{Y}_i) = e(A_i, ek_i), \forall i \in [0,n) <=>
+    //     e(g_1^{-1}, \hat{Y}_i) e(A_i, ek_i) = 1, \forall i \in [0,n) <=>
+    //
+    //     \prod_{i\in[0,n)} e(g_1^{-r_i}, \hat{Y}_i) e(A_i^{r_i}, ek_i) = 1
+    //     TODO(Performance): rewrite as
+    //     e(g_1, \prod_{i\in[0,n)} \hat{Y}_i^{-r_i}) \prod_{i\in[0,n)} e(A_i^{r_i}, ek_i) = 1
+
+    // We can also add the last pairing equation into the product above by appending a term:
+    //
+    //     e(F_0^{r_n}, \hat{u}_1) e(g_1^{-r_n}, \hat{u}_2)
+    //
+    // We let r_i = r^i, for a random r.
+
+    // TODO(Performance): Do affine representations help?
+    let g1_inverse = pp.get_commitment_base().neg();
+    let mut r_i = Vec::with_capacity(sc.n + 1);
+    r_i.push(Scalar::one());
+
+    // `lhs` is a vector of the left inputs to the pairing:
+    // - g_1^{-r_i}, \forall i \in [0,n)
+    // - A_i^{r_i}, \forall i\in [0,n)
+    // - F_0^{r_n}
+    // - g_1^{-r_n}
+
+    // First, compute r_i = r^i, for all i \in [0, n]
+    for _ in 0..sc.n {
+        r_i.push(r_i.last().unwrap().mul(r));
+    }
+
+    let lhs = (0..sc.n)
+        .map(|i| g1_inverse.mul(r_i[i]).to_affine())
+        .chain((0..sc.n).map(|i| A[i].mul(r_i[i]).to_affine()))
+        .chain([F_0.mul(r_i[sc.n]).to_affine()].into_iter())
+        .chain([g1_inverse.mul(r_i[sc.n]).to_affine()].into_iter());
+
+    // `rhs` is a vector of the left inputs to the pairing:
+    // - \hat{Y}_i, \forall i\in [0,n)
+    // - ek_i, \forall i\in [0,n)
+    // - \hat{u}_1
+    // - \hat{u}_2
+
+    let rhs = Y_hat
+        .iter()
+        .map(|p| G2Prepared::from(p.to_affine()))
+        .chain(
+            eks.iter()
+                .map(|ek| G2Prepared::from(Into::<G2Projective>::into(ek).to_affine())),
+        )
+        .chain([G2Prepared::from(pp.get_public_key_base().to_affine())].into_iter())
+        .chain([G2Prepared::from(u2_hat.to_affine())].into_iter());
+
+    let pairs = lhs.zip(rhs).collect::<Vec<(G1Affine, G2Prepared)>>();
+
+    let res = <Bls12 as MultiMillerLoop>::multi_miller_loop(
+        pairs
+            .iter()
+            .map(|(g1, g2)| (g1, g2))
+            .collect::<Vec<(&G1Affine, &G2Prepared)>>()
+            .as_slice(),
+    );
+    let one = res.final_exponentiation();
+
+    if one != Gt::identity() {
+        return false;
+    }
+
+    true
+}
+
+/// The low-degree test used to check that the evaluation commitments in a SCRAPE transcript commit
+/// to a polynomial of degree at most $t-1$.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LowDegreeTest {
+    /// Interpolates $f(\alpha)$ "in the exponent" from the $A_i$'s and compares it with $f(\alpha)$
+    /// computed from the $t$ coefficient commitments $F_j$'s.
+    Lagrange,
+    /// The SCRAPE dual-code test, which only needs $F_0$ and the $A_i$'s. See `dual_code_low_degree_test`.
+    DualCode,
+}
+
 /// A SCRAPE PVSS *transcript*.
 ///
 /// We use the normal serde `Serialize` and `Deserialize` macros because `aptos_crypto`'s `SerializeKey`
@@ -61,14 +262,20 @@ pub fn get_dual_code_word<R: rand_core::RngCore + rand_core::CryptoRng>(
 #[allow(non_snake_case)]
 pub struct Transcript {
     /// Commitment to $f(0)$: $\hat{u}_2 = \hat{u}_1^{a_0}$
-    u2_hat: G2Projective,
+    pub(crate) u2_hat: G2Projective,
     /// Commitments to the $t$ coefficients of $f(X)$: $g_1^{a_i}$
-    /// TODO: the SCRAPE low-degree test can remove this from the transcript (except for F[0], which we still want since it has the PK)
-    F: Vec<G1Projective>,
+    /// (The SCRAPE dual-code low-degree test only needs $F_0$; see `CompactTranscript`, which drops the rest.)
+    pub(crate) F: Vec<G1Projective>,
     /// Commitments to the $n$ evaluations of $f(X)$: $g_1^{f(\omega^i)}$
-    A: Vec<G1Projective>,
+    pub(crate) A: Vec<G1Projective>,
     /// $n$ encryptions, one for each player's share of $f(X)$: $ek^{f(\omega^i)}, \forall i\in[0,n)$
-    Y_hat: Vec<G2Projective>,
+    pub(crate) Y_hat: Vec<G2Projective>,
+    /// The contributions of all dealers who were aggregated into this transcript, with their PoKs
+    /// and signatures
+    pub(crate) contributions: Vec<Contribution>,
+    /// The SoKs of all dealers who opted into dealing via `Transcript::deal_with_sok` and were
+    /// aggregated into this transcript. These dealers have no entry in `contributions`.
+    pub(crate) soks: Vec<SoK>,
 }
 
 impl ValidCryptoMaterial for Transcript {
@@ -88,6 +295,8 @@ impl TryFrom<&[u8]> for Transcript {
 impl traits::Transcript for Transcript {
     type SecretSharingConfig = ThresholdConfig;
     type PvssPublicParameters = scrape::PublicParameters;
+    type SigningSecretKey = bls12381::PrivateKey;
+    type SigningPubKey = bls12381::PublicKey;
     type DealtSecretKeyShare = scrape::DealtSecretKeyShare;
     type DealtPubKeyShare = scrape::DealtPubKeyShare;
     type DealtSecretKey = scrape::DealtSecretKey;
@@ -103,187 +312,55 @@ impl traits::Transcript for Transcript {
     fn deal<R: rand_core::RngCore + rand_core::CryptoRng>(
         sc: &ThresholdConfig,
         pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
         eks: &Vec<Self::EncryptPubKey>,
         s: Self::InputSecret,
-        _dst: &'static [u8], // TODO: probably not applicable in pairing-based scrape, since no Fiat-Shamir
+        dealer: &Player,
+        dst: &'static [u8],
         rng: &mut R,
     ) -> Self {
-        assert_eq!(eks.len(), sc.n);
-
-        // A random, degree t-1 polynomial $f(X) = [a_0, \dots, a_{t-1}]$, with $a_0$ set to `s.a`
-        let mut f = random_scalars(sc.t, rng);
-        f[0] = *s.get_secret_a();
-
-        // Evaluate $f$ at all the $N$th roots of unity.
-        let mut f_evals = fft(&f, sc.get_evaluation_domain());
-        f_evals.truncate(sc.n);
-
-        let g1 = pp.get_commitment_base();
-        let u1_hat = pp.get_public_key_base();
-
-        Transcript {
-            u2_hat: u1_hat.mul(f[0]),
-            F: (0..sc.t).map(|i| g1.mul(f[i])).collect(),
-            A: (0..sc.n).map(|i| g1.mul(f_evals[i])).collect(),
-            Y_hat: (0..sc.n)
-                .map(|i| Into::<G2Projective>::into(&eks[i]).mul(f_evals[i]))
-                .collect(),
-        }
+        Self::deal_with_evaluations(sc, pp, ssk, eks, s, dealer, dst, rng).0
     }
 
-    /// TODO(Performance): This can be sped-up; we are not actually doing the SCRAPE dual-code check here. See notes on [GJM+21] and in [CD17]
+    /// Verifies the transcript using the Lagrange-based low-degree test, which also checks the
+    /// commitments $F_1, \ldots, F_{t-1}$ to the non-constant coefficients. See
+    /// `verify_with_low_degree_test` for the faster SCRAPE dual-code test.
     fn verify(
         &self,
         sc: &ThresholdConfig,
         pp: &Self::PvssPublicParameters,
+        spks: &Vec<Self::SigningPubKey>,
         eks: &Vec<Self::EncryptPubKey>,
         dst: &'static [u8],
     ) -> bool {
-        if eks.len() != sc.n {
-            return false;
-        }
-
-        for &len in [&self.A.len(), &self.Y_hat.len()] {
-            if len != sc.n {
-                return false;
-            }
-        }
-
-        if self.F.len() != sc.t {
-            return false;
-        }
-
-        // Derive challenges deterministically via Fiat-Shamir; it's easier to debug for distributed systems
-        let (alpha, r) = self.fiat_shamir(sc, pp, eks, dst);
-
-        let lagr = if is_power_of_two(sc.n) {
-            // NOTE: There's barely any wasted computation here: we have \alpha^{t-1} and
-            // `all_n_lagrange_coefficients` will recompute it as part of computing \alpha^n
-            // but it will do it very fast via doublings since n = 2^k.
-            all_n_lagrange_coefficients(sc.get_batch_evaluation_domain(), &alpha)
-        } else {
-            let all_points = (0..sc.n).collect::<Vec<usize>>();
-            lagrange_coefficients(
-                sc.get_batch_evaluation_domain(),
-                all_points.as_slice(),
-                &alpha,
-            )
-        };
-
-        // \alpha^0, \alpha^1, \ldots, \alpha^{t-1}
-        let mut alphas = Vec::with_capacity(sc.t);
-        alphas.push(Scalar::one());
-        for _ in 1..sc.t {
-            alphas.push(alphas.last().unwrap() * alpha);
-        }
-        debug_assert_eq!(alphas.len(), sc.t);
-
-        //
-        // Need to do a multiexp to verify consistency of coefficient commitments with evaluation
-        // commitments:
-        //
-        //      \prod_{i \in [n]} A_i^{lagr[i]} = \prod_{j\in [0,t)} F_j^{\alpha^j}
-        //
-        // We reorganize it as:
-        //
-        //      \prod_{i \in [n]} A_i^{lagr[i]} \prod_{j\in [0,t)} F_j^{-\alpha^j}
-        //
-        let bases = self
-            .A
-            .iter()
-            .map(|p| p.clone())
-            .chain(self.F.iter().map(|p| p.clone()))
-            .collect::<Vec<G1Projective>>();
-        let scalars = lagr
-            .into_iter()
-            .chain(alphas.iter().map(|a| a.neg()))
-            .collect::<Vec<Scalar>>();
-
-        debug_assert_eq!(bases.len(), scalars.len());
-
-        let res = G1Projective::multi_exp(&bases, &scalars);
-        if res != G1Projective::identity() {
-            return false;
-        }
-
-        //
-        // Correctness of encryptions check
-        // (This could be done via DLEQ proofs too.)
-        //
-
-        // We need to check the following equations hold:
-        //
-        //     e(g_1, \hat{Y}_i) = e(A_i, ek_i), \forall i \in [0,n) <=>
-        //     e(g_1^{-1}, \hat{Y}_i) e(A_i, ek_i) = 1, \forall i \in [0,n) <=>
-        //
-        //     \prod_{i\in[0,n)} e(g_1^{-r_i}, \hat{Y}_i) e(A_i^{r_i}, ek_i) = 1
-        //     TODO(Performance): rewrite as
-        //     e(g_1, \prod_{i\in[0,n)} \hat{Y}_i^{-r_i}) \prod_{i\in[0,n)} e(A_i^{r_i}, ek_i) = 1
-
-        // We can also add the last pairing equation into the product above by appending a term:
-        //
-        //     e(F_0^{r_n}, \hat{u}_1) e(g_1^{-r_n}, \hat{u}_2)
-        //
-        // We let r_i = r^i, for a random r.
-
-        // TODO(Performance): Do affine representations help?
-        let g1_inverse = pp.get_commitment_base().neg();
-        let mut r_i = Vec::with_capacity(sc.n + 1);
-        r_i.push(Scalar::one());
-
-        // `lhs` is a vector of the left inputs to the pairing:
-        // - g_1^{-r_i}, \forall i \in [0,n)
-        // - A_i^{r_i}, \forall i\in [0,n)
-        // - F_0^{r_n}
-        // - g_1^{-r_n}
-
-        // First, compute r_i = r^i, for all i \in [0, n]
-        for _ in 0..sc.n {
-            r_i.push(r_i.last().unwrap().mul(&r));
-        }
-
-        let lhs = (0..sc.n)
-            .map(|i| g1_inverse.mul(r_i[i]).to_affine())
-            .chain((0..sc.n).map(|i| self.A[i].mul(r_i[i]).to_affine()))
-            .chain([self.F[0].mul(r_i[sc.n]).to_affine()].into_iter())
-            .chain([g1_inverse.mul(r_i[sc.n]).to_affine()].into_iter());
-
-        // `rhs` is a vector of the left inputs to the pairing:
-        // - \hat{Y}_i, \forall i\in [0,n)
-        // - ek_i, \forall i\in [0,n)
-        // - \hat{u}_1
-        // - \hat{u}_2
-
-        let rhs = self
-            .Y_hat
-            .iter()
-            .map(|p| G2Prepared::from(p.to_affine()))
-            .chain(
-                eks.iter()
-                    .map(|ek| G2Prepared::from(Into::<G2Projective>::into(ek).to_affine())),
-            )
-            .chain([G2Prepared::from(pp.get_public_key_base().to_affine())].into_iter())
-            .chain([G2Prepared::from(self.u2_hat.to_affine())].into_iter());
-
-        let pairs = lhs.zip(rhs).collect::<Vec<(G1Affine, G2Prepared)>>();
+        self.verify_with_low_degree_test(sc, pp, spks, eks, dst, LowDegreeTest::Lagrange)
+    }
 
-        let res = <Bls12 as MultiMillerLoop>::multi_miller_loop(
-            pairs
-                .iter()
-                .map(|(g1, g2)| (g1, g2))
-                .collect::<Vec<(&G1Affine, &G2Prepared)>>()
-                .as_slice(),
-        );
-        let one = res.final_exponentiation();
+    /// Checks that the transcript has $n$ evaluation commitments and encryptions and $t$
+    /// coefficient commitments.
+    fn has_valid_sizes(&self, sc: &ThresholdConfig) -> bool {
+        self.A.len() == sc.n && self.Y_hat.len() == sc.n && self.F.len() == sc.t
+    }
 
-        if one != Gt::identity() {
-            return false;
+    fn aggregate_with(
+        &mut self,
+        sc: &ThresholdConfig,
+        other: &Transcript,
+    ) -> Result<(), AggregationError> {
+        if !traits::Transcript::has_valid_sizes(self, sc)
+            || !traits::Transcript::has_valid_sizes(other, sc)
+        {
+            return Err(AggregationError::InvalidSizes);
         }
 
-        return true;
-    }
+        merge_contributors(
+            &mut self.contributions,
+            &mut self.soks,
+            &other.contributions,
+            &other.soks,
+        )
+        .map_err(AggregationError::DuplicateDealer)?;
 
-    fn aggregate_with(&mut self, sc: &ThresholdConfig, other: &Transcript) {
         self.u2_hat += other.u2_hat;
 
         for i in 0..sc.n {
@@ -296,6 +373,17 @@ impl traits::Transcript for Transcript {
         for i in 0..sc.t {
             self.F[i] += other.F[i];
         }
+
+        Ok(())
+    }
+
+    fn get_dealers(&self) -> Vec<Player> {
+        self.contributions
+            .iter()
+            .map(|c| c.get_dealer())
+            .chain(self.soks.iter().map(|sok| sok.get_dealer()))
+            .cloned()
+            .collect()
     }
 
     fn get_dealt_public_key(&self) -> scrape::DealtPubKey {
@@ -304,6 +392,14 @@ impl traits::Transcript for Transcript {
         scrape::DealtPubKey::new(self.F[0])
     }
 
+    fn get_public_key_share(
+        &self,
+        _sc: &ThresholdConfig,
+        player_id: &Player,
+    ) -> Self::DealtPubKeyShare {
+        scrape::DealtPubKeyShare(Self::DealtPubKey::new(self.A[player_id.id]))
+    }
+
     fn decrypt_own_share(
         &self,
         _sc: &ThresholdConfig,
@@ -320,6 +416,7 @@ impl traits::Transcript for Transcript {
         )
     }
 
+    #[allow(non_snake_case)]
     fn generate<R>(sc: &ThresholdConfig, rng: &mut R) -> Self
     where
         R: rand_core::RngCore + rand_core::CryptoRng,
@@ -351,16 +448,434 @@ impl traits::Transcript for Transcript {
         let r1a = random_g1_point(rng);
         let r1b = random_g1_point(rng);
 
+        let F = g1_vec
+            .iter()
+            .take(sc.t)
+            .map(|p| p + r1a)
+            .collect::<Vec<G1Projective>>();
+        let contributions = vec![random_contribution(&sc.get_player(0), &F[0], rng)];
+
         Transcript {
             u2_hat: g2,
-            F: g1_vec.iter().take(sc.t).map(|p| p + r1a).collect(),
+            F,
             A: g1_vec.iter().map(|p| p + r1b).collect(),
             Y_hat: g2_vec.iter().map(|p| p + r2).collect(),
+            contributions,
+            soks: vec![],
         }
     }
 }
 
 impl Transcript {
+    /// Like `traits::Transcript::deal`, but also returns the $n$ evaluations $f(\omega^i)$ of the
+    /// dealt polynomial $f(X)$, which other PVSS schemes built on top of SCRAPE (e.g., DLEQ-SCRAPE)
+    /// need in order to prove statements about the encrypted shares.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn deal_with_evaluations<R: rand_core::RngCore + rand_core::CryptoRng>(
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        ssk: &bls12381::PrivateKey,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        s: scrape::InputSecret,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> (Self, Vec<Scalar>) {
+        let (mut trx, a, f_evals) = Self::deal_unauthenticated(sc, pp, eks, s, rng);
+
+        trx.contributions.push(Contribution::new(
+            pp.get_commitment_base(),
+            &a,
+            ssk,
+            dealer,
+            dst,
+            rng,
+        ));
+
+        (trx, f_evals)
+    }
+
+    /// Like `traits::Transcript::deal`, but authenticates the dealer and proves knowledge of the dealt
+    /// secret via a single SoK (see `SoK`), rather than via a Schnorr PoK and a separate signature.
+    /// The resulting transcript can be aggregated with other transcripts, whether dealt this way or not.
+    ///
+    /// Returns an error if `ssk` cannot be used as the witness of the SoK.
+    #[allow(clippy::too_many_arguments)]
+    pub fn deal_with_sok<R: rand_core::RngCore + rand_core::CryptoRng>(
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        ssk: &bls12381::PrivateKey,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        s: scrape::InputSecret,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> Result<Self, CryptoMaterialError> {
+        let (mut trx, a, _) = Self::deal_unauthenticated(sc, pp, eks, s, rng);
+
+        trx.soks.push(SoK::new(
+            pp.get_commitment_base(),
+            &a,
+            ssk,
+            dealer,
+            dst,
+            rng,
+        )?);
+
+        Ok(trx)
+    }
+
+    /// Deals `s` without authenticating the dealer, returning the transcript (with no contributions
+    /// and no SoKs), the dealt secret $a_0$ and the $n$ evaluations $f(\omega^i)$.
+    fn deal_unauthenticated<R: rand_core::RngCore + rand_core::CryptoRng>(
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        s: scrape::InputSecret,
+        rng: &mut R,
+    ) -> (Self, Scalar, Vec<Scalar>) {
+        assert_eq!(eks.len(), sc.n);
+
+        // A random, degree t-1 polynomial $f(X) = [a_0, \dots, a_{t-1}]$, with $a_0$ set to `s.a`
+        let mut f = random_scalars(sc.t, rng);
+        f[0] = *s.get_secret_a();
+
+        // Evaluate $f$ at all the $N$th roots of unity.
+        let mut f_evals = fft(&f, sc.get_evaluation_domain());
+        f_evals.truncate(sc.n);
+
+        let g1 = pp.get_commitment_base();
+        let u1_hat = pp.get_public_key_base();
+
+        let trx = Transcript {
+            u2_hat: u1_hat.mul(f[0]),
+            F: (0..sc.t).map(|i| g1.mul(f[i])).collect(),
+            A: (0..sc.n).map(|i| g1.mul(f_evals[i])).collect(),
+            Y_hat: (0..sc.n)
+                .map(|i| Into::<G2Projective>::into(&eks[i]).mul(f_evals[i]))
+                .collect(),
+            contributions: vec![],
+            soks: vec![],
+        };
+
+        (trx, f[0], f_evals)
+    }
+
+    /// Like `traits::Transcript::verify`, but lets the caller pick the low-degree test used to check
+    /// the evaluation commitments $A_i$. Note that `LowDegreeTest::DualCode` does not check the
+    /// commitments $F_1, \ldots, F_{t-1}$, which are not used anywhere else.
+    pub fn verify_with_low_degree_test(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        spks: &Vec<bls12381::PublicKey>,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        dst: &'static [u8],
+        ldt: LowDegreeTest,
+    ) -> bool {
+        if eks.len() != sc.n {
+            return false;
+        }
+
+        if !traits::Transcript::has_valid_sizes(self, sc) {
+            return false;
+        }
+
+        if !self.verify_contributions(pp, spks, dst) {
+            return false;
+        }
+
+        // Derive challenges deterministically via Fiat-Shamir; it's easier to debug for distributed systems
+        let (alpha, beta, r) = self.fiat_shamir(sc, pp, eks, dst);
+
+        let is_low_degree = match ldt {
+            LowDegreeTest::Lagrange => self.low_degree_test(sc, &alpha),
+            LowDegreeTest::DualCode => dual_code_low_degree_test(sc, &self.F[0], &self.A, &beta),
+        };
+
+        if !is_low_degree {
+            return false;
+        }
+
+        encryptions_check(
+            sc,
+            pp,
+            eks,
+            &self.u2_hat,
+            &self.F[0],
+            &self.A,
+            &self.Y_hat,
+            &r,
+        )
+    }
+
+    /// Verifies many transcripts dealt for the same `sc`, `pp` and `eks` (e.g., all the transcripts
+    /// received in a DKG round) at once. Returns the indices in `trxs` of the invalid transcripts, if
+    /// any.
+    ///
+    /// This is equivalent to calling `traits::Transcript::verify` on each transcript, but is much
+    /// faster when all of them are valid: the PoKs, signatures and SoKs of all contributors are
+    /// verified together, the Lagrange-based low-degree tests of all transcripts are combined into a
+    /// single multiexp and their encryption checks are combined into a single size-$(n+2)$
+    /// multipairing. When this batched check fails, we fall back to verifying each transcript
+    /// individually, to find out which ones are invalid.
+    pub fn verify_batch(
+        trxs: &[Transcript],
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        spks: &Vec<bls12381::PublicKey>,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> Result<(), Vec<usize>> {
+        if trxs.is_empty() {
+            return Ok(());
+        }
+
+        if Self::batch_verifies(trxs, sc, pp, spks, eks, dst) {
+            return Ok(());
+        }
+
+        let culprits = (0..trxs.len())
+            .filter(|&k| {
+                !trxs[k].verify_with_low_degree_test(
+                    sc,
+                    pp,
+                    spks,
+                    eks,
+                    dst,
+                    LowDegreeTest::Lagrange,
+                )
+            })
+            .collect::<Vec<usize>>();
+
+        if culprits.is_empty() {
+            Ok(())
+        } else {
+            Err(culprits)
+        }
+    }
+
+    /// Returns true if all transcripts in `trxs` are valid, except with negligible probability. Does
+    /// not say which transcripts are invalid; see `verify_batch`.
+    fn batch_verifies(
+        trxs: &[Transcript],
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        spks: &Vec<bls12381::PublicKey>,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> bool {
+        if eks.len() != sc.n {
+            return false;
+        }
+
+        for trx in trxs {
+            if !traits::Transcript::has_valid_sizes(trx, sc)
+                || !contributors_add_up(&trx.F[0], &trx.contributions, &trx.soks, spks)
+            {
+                return false;
+            }
+        }
+
+        let contribs = trxs
+            .iter()
+            .flat_map(|trx| trx.contributions.iter().cloned())
+            .collect::<Vec<Contribution>>();
+        let soks = trxs
+            .iter()
+            .flat_map(|trx| trx.soks.iter().cloned())
+            .collect::<Vec<SoK>>();
+
+        if !verify_contributor_proofs(pp.get_commitment_base(), &contribs, &soks, spks, dst) {
+            return false;
+        }
+
+        let rho = Self::batch_fiat_shamir(trxs, sc, pp, eks, dst);
+
+        //
+        // The checks of the kth transcript are combined using coefficients \rho^k. So, for the low-degree
+        // tests, we check:
+        //
+        //      \prod_k (\prod_{i \in [n]} A_{k,i}^{lagr_k[i]} \prod_{j\in [0,t)} F_{k,j}^{-\alpha_k^j})^{\rho^k} = 1
+        //
+        // For the encryptions checks, we let c_{k,i} = \rho^k r_k^i and check (see `encryptions_check`):
+        //
+        //      e(g_1^{-1}, \prod_k \hat{u}_{k,2}^{c_{k,n}} \prod_{i \in [0,n)} \hat{Y}_{k,i}^{c_{k,i}})
+        //      \prod_{i \in [0,n)} e(\prod_k A_{k,i}^{c_{k,i}}, ek_i)
+        //      e(\prod_k F_{k,0}^{c_{k,n}}, \hat{u}_1) = 1
+        //
+        let m = trxs.len();
+        let mut ldt_bases = Vec::with_capacity(m * (sc.n + sc.t));
+        let mut ldt_scalars = Vec::with_capacity(m * (sc.n + sc.t));
+        let mut ek_bases = vec![Vec::with_capacity(m); sc.n];
+        let mut ek_scalars = vec![Vec::with_capacity(m); sc.n];
+        let mut u1_bases = Vec::with_capacity(m);
+        let mut u1_scalars = Vec::with_capacity(m);
+        let mut g1_bases = Vec::with_capacity(m * (sc.n + 1));
+        let mut g1_scalars = Vec::with_capacity(m * (sc.n + 1));
+
+        let mut rho_k = Scalar::one();
+        for trx in trxs {
+            let (alpha, _, r) = trx.fiat_shamir(sc, pp, eks, dst);
+
+            let (bases, scalars) = trx.low_degree_test_terms(sc, &alpha);
+            ldt_bases.extend(bases);
+            ldt_scalars.extend(scalars.iter().map(|s| s.mul(rho_k)));
+
+            let mut c_i = rho_k;
+            for i in 0..sc.n {
+                ek_bases[i].push(trx.A[i]);
+                ek_scalars[i].push(c_i);
+                g1_bases.push(trx.Y_hat[i]);
+                g1_scalars.push(c_i);
+                c_i *= r;
+            }
+
+            u1_bases.push(trx.F[0]);
+            u1_scalars.push(c_i);
+            g1_bases.push(trx.u2_hat);
+            g1_scalars.push(c_i);
+
+            rho_k *= rho;
+        }
+
+        if G1Projective::multi_exp(&ldt_bases, &ldt_scalars) != G1Projective::identity() {
+            return false;
+        }
+
+        let lhs = [pp.get_commitment_base().neg().to_affine()]
+            .into_iter()
+            .chain(
+                (0..sc.n)
+                    .map(|i| G1Projective::multi_exp(&ek_bases[i], &ek_scalars[i]).to_affine()),
+            )
+            .chain([G1Projective::multi_exp(&u1_bases, &u1_scalars).to_affine()].into_iter());
+        let rhs = [G2Prepared::from(
+            G2Projective::multi_exp(&g1_bases, &g1_scalars).to_affine(),
+        )]
+        .into_iter()
+        .chain(
+            eks.iter()
+                .map(|ek| G2Prepared::from(Into::<G2Projective>::into(ek).to_affine())),
+        )
+        .chain([G2Prepared::from(pp.get_public_key_base().to_affine())].into_iter());
+
+        let pairs = lhs.zip(rhs).collect::<Vec<(G1Affine, G2Prepared)>>();
+
+        let res = <Bls12 as MultiMillerLoop>::multi_miller_loop(
+            pairs
+                .iter()
+                .map(|(g1, g2)| (g1, g2))
+                .collect::<Vec<(&G1Affine, &G2Prepared)>>()
+                .as_slice(),
+        );
+
+        res.final_exponentiation() == Gt::identity()
+    }
+
+    /// Returns true if the transcript has at least one contribution or SoK, if no dealer contributed
+    /// twice, if all the contributors' PoKs, signatures and SoKs verify and if their commitments add
+    /// up to $F_0$.
+    pub(crate) fn verify_contributions(
+        &self,
+        pp: &scrape::PublicParameters,
+        spks: &Vec<bls12381::PublicKey>,
+        dst: &'static [u8],
+    ) -> bool {
+        verify_contributors(
+            pp.get_commitment_base(),
+            &self.F[0],
+            &self.contributions,
+            &self.soks,
+            spks,
+            dst,
+        )
+    }
+
+    /// Verifies the consistency of the coefficient commitments `F` with the evaluation commitments
+    /// `A`, by interpolating $f(\alpha)$ "in the exponent" from the $A_i$'s and comparing it with
+    /// $f(\alpha)$ computed "in the exponent" from the $F_j$'s.
+    pub(crate) fn low_degree_test(&self, sc: &ThresholdConfig, alpha: &Scalar) -> bool {
+        let (bases, scalars) = self.low_degree_test_terms(sc, alpha);
+
+        debug_assert_eq!(bases.len(), scalars.len());
+
+        let res = G1Projective::multi_exp(&bases, &scalars);
+
+        res == G1Projective::identity()
+    }
+
+    /// Returns the bases and scalars of the multiexp that must equal the identity for the Lagrange-based
+    /// low-degree test to pass. See `low_degree_test`.
+    fn low_degree_test_terms(
+        &self,
+        sc: &ThresholdConfig,
+        alpha: &Scalar,
+    ) -> (Vec<G1Projective>, Vec<Scalar>) {
+        let lagr = if is_power_of_two(sc.n) {
+            // NOTE: There's barely any wasted computation here: we have \alpha^{t-1} and
+            // `all_n_lagrange_coefficients` will recompute it as part of computing \alpha^n
+            // but it will do it very fast via doublings since n = 2^k.
+            all_n_lagrange_coefficients(sc.get_batch_evaluation_domain(), alpha)
+        } else {
+            let all_points = (0..sc.n).collect::<Vec<usize>>();
+            lagrange_coefficients(
+                sc.get_batch_evaluation_domain(),
+                all_points.as_slice(),
+                alpha,
+            )
+        };
+
+        // \alpha^0, \alpha^1, \ldots, \alpha^{t-1}
+        let mut alphas = Vec::with_capacity(sc.t);
+        alphas.push(Scalar::one());
+        for _ in 1..sc.t {
+            alphas.push(alphas.last().unwrap() * alpha);
+        }
+        debug_assert_eq!(alphas.len(), sc.t);
+
+        //
+        // Need to do a multiexp to verify consistency of coefficient commitments with evaluation
+        // commitments:
+        //
+        //      \prod_{i \in [n]} A_i^{lagr[i]} = \prod_{j\in [0,t)} F_j^{\alpha^j}
+        //
+        // We reorganize it as:
+        //
+        //      \prod_{i \in [n]} A_i^{lagr[i]} \prod_{j\in [0,t)} F_j^{-\alpha^j}
+        //
+        let bases = self
+            .A
+            .iter()
+            .map(|p| p.clone())
+            .chain(self.F.iter().map(|p| p.clone()))
+            .collect::<Vec<G1Projective>>();
+        let scalars = lagr
+            .into_iter()
+            .chain(alphas.iter().map(|a| a.neg()))
+            .collect::<Vec<Scalar>>();
+
+        (bases, scalars)
+    }
+
+    /// Securely derives the Fiat-Shamir challenge $\rho$ for batch-verifying `trxs`, via Merlin.
+    fn batch_fiat_shamir(
+        trxs: &[Transcript],
+        sc: &ThresholdConfig,
+        pp: &scrape::PublicParameters,
+        eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> Scalar {
+        // TODO(Security): Audit this
+        let mut fs_t = merlin::Transcript::new(dst);
+        fs_t.pvss_domain_sep(sc);
+        fs_t.append_public_parameters(pp);
+        fs_t.append_encryption_keys(eks);
+
+        fs_t.append_transcripts(trxs);
+        fs_t.challenge_batch_scalar()
+    }
+
     /// Securely derives a Fiat-Shamir challenge via Merlin.
     fn fiat_shamir(
         &self,
@@ -368,7 +883,7 @@ impl Transcript {
         pp: &scrape::PublicParameters,
         eks: &Vec<encryption_dlog::g2::EncryptPubKey>,
         dst: &'static [u8],
-    ) -> (Scalar, Scalar) {
+    ) -> (Scalar, Scalar, Scalar) {
         // TODO(Security): Audit this
         let mut fs_t = merlin::Transcript::new(dst);
         fs_t.pvss_domain_sep(sc);
@@ -376,10 +891,11 @@ impl Transcript {
         fs_t.append_encryption_keys(eks);
 
         fs_t.append_transcript(&self);
-        (
-            fs_t.challenge_lagrange_scalar(),
-            fs_t.challenge_multipairing_scalar(),
-        )
+        let alpha = fs_t.challenge_lagrange_scalar();
+        let r = fs_t.challenge_multipairing_scalar();
+        let beta = fs_t.challenge_dual_code_scalar();
+
+        (alpha, beta, r)
     }
 }
 
@@ -387,15 +903,21 @@ impl Transcript {
 mod test {
     use crate::algebra::evaluation_domain::BatchEvaluationDomain;
     use crate::algebra::fft::fft_assign;
-    use crate::pvss::scrape::transcript::get_dual_code_word;
-    use crate::pvss::scrape::Transcript;
+    use crate::constants::DST_PVSS_TESTING_APP;
+    use crate::pvss::scrape::transcript::{
+        dual_code_low_degree_test, get_dual_code_word, get_extended_dual_code_word,
+    };
+    use crate::pvss::scrape::{CompactTranscript, LowDegreeTest, Transcript};
+    use crate::pvss::test_utils;
     use crate::pvss::test_utils::get_threshold_config_and_rng;
     use crate::pvss::threshold_config::ThresholdConfig;
     use crate::pvss::traits::transcript::Transcript as UniformTranscript;
+    use crate::pvss::traits::{AggregationError, SecretSharingConfig};
     use crate::utils::random::random_scalars;
-    use aptos_crypto::ValidCryptoMaterial;
-    use blstrs::Scalar;
+    use aptos_crypto::{bls12381, Uniform, ValidCryptoMaterial};
+    use blstrs::{G1Projective, G2Projective, Scalar};
     use ff::Field;
+    use group::Group;
     use rand::thread_rng;
     use std::ops::Mul;
 
@@ -438,4 +960,339 @@ mod test {
 
         assert_eq!(zero, Scalar::zero());
     }
+
+    #[test]
+    fn extended_dual_code_word_test() {
+        // Both when n is a power of two and when it is not
+        for (t, n) in [(3, 8), (10, 20), (1, 1), (7, 7)] {
+            let (sc, mut rng) = get_threshold_config_and_rng(t, n);
+
+            // The degree t-1 polynomial that shares our secret, evaluated at 0 and at all \omega^i's
+            let p = random_scalars(sc.t, &mut rng);
+            let mut p_evals = p.clone();
+            fft_assign(&mut p_evals, sc.get_evaluation_domain());
+            p_evals.truncate(sc.n);
+            p_evals.insert(0, p[0]);
+
+            // The degree n - t dual polynomial
+            let f = random_scalars(sc.n - sc.t + 1, &mut rng);
+            let vf = get_extended_dual_code_word(&f, sc.get_batch_evaluation_domain(), sc.n);
+
+            // Compute p(0) v_0 f(0) + \sum_i p(\omega^i) v_{i+1} f(\omega^i), which should be zero
+            let zero: Scalar = p_evals.iter().zip(vf.iter()).map(|(p, vf)| p.mul(vf)).sum();
+            assert_eq!(zero, Scalar::zero());
+
+            // A polynomial of degree t should (w.h.p.) fail the check, if there is room for one
+            if sc.t < sc.n {
+                let p = random_scalars(sc.t + 1, &mut rng);
+                let mut p_evals = p.clone();
+                fft_assign(&mut p_evals, sc.get_evaluation_domain());
+                p_evals.truncate(sc.n);
+                p_evals.insert(0, p[0]);
+
+                let nonzero: Scalar = p_evals.iter().zip(vf.iter()).map(|(p, vf)| p.mul(vf)).sum();
+                assert_ne!(nonzero, Scalar::zero());
+            }
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn dual_code_low_degree_test_rejects_bad_evaluations() {
+        for (t, n) in [(3, 8), (10, 20)] {
+            let (sc, mut rng) = get_threshold_config_and_rng(t, n);
+            let (pp, _, eks, _, _) = test_utils::setup_dealing::<Transcript>(&sc);
+            let (ssks, spks) = test_utils::setup_signing::<Transcript>(&sc);
+
+            let s = <Transcript as UniformTranscript>::InputSecret::generate(&mut rng);
+            let trx = Transcript::deal(
+                &sc,
+                &pp,
+                &ssks[0],
+                &eks,
+                s,
+                &sc.get_player(0),
+                &DST_PVSS_TESTING_APP[..],
+                &mut rng,
+            );
+
+            for ldt in [LowDegreeTest::Lagrange, LowDegreeTest::DualCode] {
+                assert!(trx.verify_with_low_degree_test(
+                    &sc,
+                    &pp,
+                    &spks,
+                    &eks,
+                    &DST_PVSS_TESTING_APP[..],
+                    ldt
+                ));
+            }
+
+            // Moving one A_i off the polynomial should make both low-degree tests fail
+            let mut bad_trx = trx.clone();
+            bad_trx.A[0] += G1Projective::generator();
+
+            let challenge = random_scalars(1, &mut rng)[0];
+            assert!(trx.low_degree_test(&sc, &challenge));
+            assert!(dual_code_low_degree_test(
+                &sc, &trx.F[0], &trx.A, &challenge
+            ));
+            assert!(!bad_trx.low_degree_test(&sc, &challenge));
+            assert!(!dual_code_low_degree_test(
+                &sc,
+                &bad_trx.F[0],
+                &bad_trx.A,
+                &challenge
+            ));
+        }
+    }
+
+    #[test]
+    fn verify_rejects_missing_or_invalid_pok() {
+        let (sc, mut rng) = get_threshold_config_and_rng(3, 7);
+        let (pp, _, eks, _, _) = test_utils::setup_dealing::<Transcript>(&sc);
+        let (ssks, spks) = test_utils::setup_signing::<Transcript>(&sc);
+
+        let deal = |dealer: usize, rng: &mut rand::rngs::ThreadRng| {
+            let s = <Transcript as UniformTranscript>::InputSecret::generate(rng);
+            Transcript::deal(
+                &sc,
+                &pp,
+                &ssks[dealer],
+                &eks,
+                s,
+                &sc.get_player(dealer),
+                &DST_PVSS_TESTING_APP[..],
+                rng,
+            )
+        };
+
+        let trx = deal(0, &mut rng);
+        assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // Missing PoK
+        let mut bad_trx = trx.clone();
+        bad_trx.contributions.clear();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // PoK bound to a different dealer
+        let mut bad_trx = trx.clone();
+        bad_trx.contributions[0].dealer = sc.get_player(1);
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // Invalid PoK
+        let mut bad_trx = trx.clone();
+        bad_trx.contributions[0].pok.1 += Scalar::one();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // An aggregated transcript keeps the PoKs of its contributors and still verifies
+        let mut agg = trx.clone();
+        agg.aggregate_with(&sc, &deal(1, &mut rng)).unwrap();
+        agg.aggregate_with(&sc, &deal(2, &mut rng)).unwrap();
+        assert_eq!(agg.contributions.len(), 3);
+        assert!(agg.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // ...but not if we drop one of them
+        agg.contributions.pop();
+        assert!(!agg.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+    }
+
+    #[test]
+    fn verify_rejects_bad_signatures_and_aggregation_rejects_duplicate_dealers() {
+        let (sc, mut rng) = get_threshold_config_and_rng(3, 7);
+        let (pp, _, eks, _, _) = test_utils::setup_dealing::<Transcript>(&sc);
+        let (ssks, spks) = test_utils::setup_signing::<Transcript>(&sc);
+
+        let deal = |dealer: usize, ssk: &bls12381::PrivateKey, rng: &mut rand::rngs::ThreadRng| {
+            let s = <Transcript as UniformTranscript>::InputSecret::generate(rng);
+            Transcript::deal(
+                &sc,
+                &pp,
+                ssk,
+                &eks,
+                s,
+                &sc.get_player(dealer),
+                &DST_PVSS_TESTING_APP[..],
+                rng,
+            )
+        };
+
+        // Signed with someone else's key
+        let bad_trx = deal(0, &ssks[1], &mut rng);
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // Signature swapped in from another transcript
+        let mut trx = deal(0, &ssks[0], &mut rng);
+        let other = deal(1, &ssks[1], &mut rng);
+        let mut bad_trx = trx.clone();
+        bad_trx.contributions[0].sig = other.contributions[0].sig.clone();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // Dealer without a signing public key
+        assert!(!other.verify(
+            &sc,
+            &pp,
+            &spks[..1].to_vec(),
+            &eks,
+            &DST_PVSS_TESTING_APP[..]
+        ));
+
+        // Aggregating a dealer's contribution twice fails and leaves the transcript unchanged
+        trx.aggregate_with(&sc, &other).unwrap();
+        let before = trx.clone();
+        assert_eq!(
+            trx.aggregate_with(&sc, &deal(1, &ssks[1], &mut rng)),
+            Err(AggregationError::DuplicateDealer(sc.get_player(1)))
+        );
+        assert_eq!(trx, before);
+        assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+        assert_eq!(
+            trx.contributions
+                .iter()
+                .map(|c| c.get_dealer().get_id())
+                .collect::<Vec<usize>>(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn sok_transcripts_verify_and_aggregate_with_signed_ones() {
+        let (sc, mut rng) = get_threshold_config_and_rng(3, 7);
+        let (pp, _, eks, _, _) = test_utils::setup_dealing::<Transcript>(&sc);
+        let (ssks, spks) = test_utils::setup_signing::<Transcript>(&sc);
+
+        let deal_with_sok =
+            |dealer: usize, ssk: &bls12381::PrivateKey, rng: &mut rand::rngs::ThreadRng| {
+                let s = <Transcript as UniformTranscript>::InputSecret::generate(rng);
+                Transcript::deal_with_sok(
+                    &sc,
+                    &pp,
+                    ssk,
+                    &eks,
+                    s,
+                    &sc.get_player(dealer),
+                    &DST_PVSS_TESTING_APP[..],
+                    rng,
+                )
+                .unwrap()
+            };
+
+        let trx = deal_with_sok(0, &ssks[0], &mut rng);
+        assert!(trx.contributions.is_empty());
+        for ldt in [LowDegreeTest::Lagrange, LowDegreeTest::DualCode] {
+            assert!(trx.verify_with_low_degree_test(
+                &sc,
+                &pp,
+                &spks,
+                &eks,
+                &DST_PVSS_TESTING_APP[..],
+                ldt
+            ));
+        }
+
+        // An SoK made with someone else's signing key does not verify
+        let bad_trx = deal_with_sok(0, &ssks[1], &mut rng);
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // Transcripts dealt with SoKs and with signed contributions aggregate into one that verifies
+        let mut agg = trx.clone();
+        let s = <Transcript as UniformTranscript>::InputSecret::generate(&mut rng);
+        let signed_trx = Transcript::deal(
+            &sc,
+            &pp,
+            &ssks[1],
+            &eks,
+            s,
+            &sc.get_player(1),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+        agg.aggregate_with(&sc, &signed_trx).unwrap();
+        agg.aggregate_with(&sc, &deal_with_sok(2, &ssks[2], &mut rng))
+            .unwrap();
+        assert_eq!(agg.contributions.len(), 1);
+        assert_eq!(agg.soks.len(), 2);
+        assert!(agg.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+        assert!(CompactTranscript::from(agg.clone()).verify(
+            &sc,
+            &pp,
+            &spks,
+            &eks,
+            &DST_PVSS_TESTING_APP[..]
+        ));
+
+        // A dealer cannot contribute twice, even if once via an SoK and once via a signature
+        let s = <Transcript as UniformTranscript>::InputSecret::generate(&mut rng);
+        let dup_trx = Transcript::deal(
+            &sc,
+            &pp,
+            &ssks[2],
+            &eks,
+            s,
+            &sc.get_player(2),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+        assert_eq!(
+            agg.clone().aggregate_with(&sc, &dup_trx),
+            Err(AggregationError::DuplicateDealer(sc.get_player(2)))
+        );
+
+        // Dropping an SoK makes verification fail
+        agg.soks.pop();
+        assert!(!agg.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+    }
+
+    #[test]
+    fn verify_batch_blames_exactly_the_invalid_transcripts() {
+        let (sc, mut rng) = get_threshold_config_and_rng(3, 7);
+        let (pp, _, eks, _, _) = test_utils::setup_dealing::<Transcript>(&sc);
+        let (ssks, spks) = test_utils::setup_signing::<Transcript>(&sc);
+
+        let mut trxs = (0..5)
+            .map(|i| {
+                let s = <Transcript as UniformTranscript>::InputSecret::generate(&mut rng);
+                if i % 2 == 0 {
+                    Transcript::deal(
+                        &sc,
+                        &pp,
+                        &ssks[i],
+                        &eks,
+                        s,
+                        &sc.get_player(i),
+                        &DST_PVSS_TESTING_APP[..],
+                        &mut rng,
+                    )
+                } else {
+                    Transcript::deal_with_sok(
+                        &sc,
+                        &pp,
+                        &ssks[i],
+                        &eks,
+                        s,
+                        &sc.get_player(i),
+                        &DST_PVSS_TESTING_APP[..],
+                        &mut rng,
+                    )
+                    .unwrap()
+                }
+            })
+            .collect::<Vec<Transcript>>();
+
+        let verify_batch = |trxs: &[Transcript]| {
+            Transcript::verify_batch(trxs, &sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..])
+        };
+
+        assert_eq!(verify_batch(&[]), Ok(()));
+        assert_eq!(verify_batch(&trxs), Ok(()));
+
+        // A bad encryption, a bad evaluation commitment and a bad coefficient commitment
+        trxs[1].Y_hat[0] += G2Projective::generator();
+        trxs[3].A[4] += G1Projective::generator();
+        trxs[4].F[2] += G1Projective::generator();
+        assert_eq!(verify_batch(&trxs), Err(vec![1, 3, 4]));
+
+        // A missing contribution
+        trxs[0].contributions.clear();
+        assert_eq!(verify_batch(&trxs[..3]), Err(vec![0, 1]));
+    }
 }
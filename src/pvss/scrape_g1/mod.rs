@@ -0,0 +1,9 @@
+pub(crate) mod transcript;
+
+pub(crate) use crate::pvss::dealt_pub_key::g2::DealtPubKey;
+pub(crate) use crate::pvss::dealt_pub_key_share::g2::DealtPubKeyShare;
+pub(crate) use crate::pvss::dealt_secret_key::g1::DealtSecretKey;
+pub(crate) use crate::pvss::dealt_secret_key_share::g1::DealtSecretKeyShare;
+pub(crate) use crate::pvss::sk_in_g1::scrape_g1::PublicParameters;
+pub(crate) use crate::pvss::sk_in_g1::InputSecret;
+pub use transcript::Transcript;
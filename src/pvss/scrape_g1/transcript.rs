@@ -0,0 +1,381 @@
+use crate::algebra::fft::fft;
+use crate::pvss::contribution::{
+    merge_contributors, random_contribution, verify_contributors, Contribution,
+};
+use crate::pvss::encryption_dlog;
+use crate::pvss::player::Player;
+use crate::pvss::scrape::transcript::dual_code_low_degree_test;
+use crate::pvss::scrape_g1;
+use crate::pvss::sk_in_g1::scrape_g1::FiatShamirProtocol;
+use crate::pvss::threshold_config::ThresholdConfig;
+use crate::pvss::traits;
+use crate::pvss::traits::{AggregationError, SecretSharingConfig};
+use crate::utils::random::{random_g1_point, random_g2_point, random_scalars};
+use aptos_crypto::{bls12381, CryptoMaterialError, ValidCryptoMaterial};
+use blstrs::{Bls12, G1Affine, G1Projective, G2Prepared, G2Projective, Gt, Scalar};
+use ff::Field;
+use group::{Curve, Group};
+use pairing::{MillerLoopResult, MultiMillerLoop};
+use serde::{Deserialize, Serialize};
+use std::ops::{Mul, Neg};
+
+/// A SCRAPE PVSS *transcript* whose dealt secret key $h_1^a$ and encryption keys live in
+/// $\mathbb{G}_1$, rather than in $\mathbb{G}_2$ as in `scrape::Transcript`. As a result, the
+/// encrypted shares are $\mathbb{G}_1$ elements, which are half the size and faster to decrypt,
+/// while the commitments to the polynomial move to $\mathbb{G}_2$.
+///
+/// Like `scrape::CompactTranscript`, it only commits to $f(0)$ rather than to all coefficients of
+/// $f(X)$, since it is verified via the SCRAPE dual-code low-degree test.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[allow(non_snake_case)]
+pub struct Transcript {
+    /// Commitment to $f(0)$: $u_2 = u_1^{a_0}$
+    u2: G1Projective,
+    /// Commitment to $f(0)$, which is the dealt public key: $F_0 = g_2^{a_0}$
+    F_0: G2Projective,
+    /// Commitments to the $n$ evaluations of $f(X)$: $g_2^{f(\omega^i)}$
+    A: Vec<G2Projective>,
+    /// $n$ encryptions, one for each player's share of $f(X)$: $ek_i^{f(\omega^i)}, \forall i\in[0,n)$
+    pub(crate) Y: Vec<G1Projective>,
+    /// The contributions of all dealers who were aggregated into this transcript, with their PoKs
+    /// and signatures. Their commitments are w.r.t. the base $u_1$ and add up to $u_2$.
+    contributions: Vec<Contribution>,
+}
+
+impl ValidCryptoMaterial for Transcript {
+    fn to_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&self)
+            .expect("unexpected error during SCRAPE (G1) PVSS transcript serialization")
+    }
+}
+
+impl TryFrom<&[u8]> for Transcript {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bcs::from_bytes::<Transcript>(bytes).map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl traits::Transcript for Transcript {
+    type SecretSharingConfig = ThresholdConfig;
+    type PvssPublicParameters = scrape_g1::PublicParameters;
+    type SigningSecretKey = bls12381::PrivateKey;
+    type SigningPubKey = bls12381::PublicKey;
+    type DealtSecretKeyShare = scrape_g1::DealtSecretKeyShare;
+    type DealtPubKeyShare = scrape_g1::DealtPubKeyShare;
+    type DealtSecretKey = scrape_g1::DealtSecretKey;
+    type DealtPubKey = scrape_g1::DealtPubKey;
+    type InputSecret = scrape_g1::InputSecret;
+    type EncryptPubKey = encryption_dlog::g1::EncryptPubKey;
+    type DecryptPrivKey = encryption_dlog::g1::DecryptPrivKey;
+
+    fn scheme_name() -> String {
+        "scrape_sk_in_g1".to_string()
+    }
+
+    fn deal<R: rand_core::RngCore + rand_core::CryptoRng>(
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
+        eks: &Vec<Self::EncryptPubKey>,
+        s: Self::InputSecret,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> Self {
+        assert_eq!(eks.len(), sc.n);
+
+        // A random, degree t-1 polynomial $f(X) = [a_0, \dots, a_{t-1}]$, with $a_0$ set to `s.a`
+        let mut f = random_scalars(sc.t, rng);
+        f[0] = *s.get_secret_a();
+
+        // Evaluate $f$ at all the $N$th roots of unity.
+        let mut f_evals = fft(&f, sc.get_evaluation_domain());
+        f_evals.truncate(sc.n);
+
+        let u1 = pp.get_secret_commitment_base();
+        let g2 = pp.get_commitment_base();
+
+        Transcript {
+            u2: u1.mul(f[0]),
+            F_0: g2.mul(f[0]),
+            A: (0..sc.n).map(|i| g2.mul(f_evals[i])).collect(),
+            Y: (0..sc.n)
+                .map(|i| Into::<G1Projective>::into(&eks[i]).mul(f_evals[i]))
+                .collect(),
+            contributions: vec![Contribution::new(u1, &f[0], ssk, dealer, dst, rng)],
+        }
+    }
+
+    fn verify(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &Self::PvssPublicParameters,
+        spks: &Vec<Self::SigningPubKey>,
+        eks: &Vec<Self::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> bool {
+        if eks.len() != sc.n {
+            return false;
+        }
+
+        if !traits::Transcript::has_valid_sizes(self, sc) {
+            return false;
+        }
+
+        if !verify_contributors(
+            pp.get_secret_commitment_base(),
+            &self.u2,
+            &self.contributions,
+            &[],
+            spks,
+            dst,
+        ) {
+            return false;
+        }
+
+        // Derive challenges deterministically via Fiat-Shamir; it's easier to debug for distributed systems
+        let (beta, r) = self.fiat_shamir(sc, pp, eks, dst);
+
+        if !dual_code_low_degree_test(sc, &self.F_0, &self.A, &beta) {
+            return false;
+        }
+
+        //
+        // We need to check the following equations hold:
+        //
+        //     e(Y_i, g_2) = e(ek_i, A_i), \forall i \in [0,n)
+        //     e(u_2, g_2) = e(u_1, F_0)
+        //
+        // We combine them using r_i = r^i, for a random r, which gives a size-(n+2) multipairing:
+        //
+        //     e((\sum_{i\in[0,n)} r^i Y_i + r^n u_2)^{-1}, g_2)
+        //     \prod_{i\in[0,n)} e(ek_i^{r^i}, A_i) e(u_1^{r^n}, F_0) = 1
+        //
+        let mut r_i = Vec::with_capacity(sc.n + 1);
+        r_i.push(Scalar::one());
+        for _ in 0..sc.n {
+            r_i.push(r_i.last().unwrap().mul(r));
+        }
+
+        let lhs_g2 = G1Projective::multi_exp(
+            &self
+                .Y
+                .iter()
+                .chain([self.u2].iter())
+                .copied()
+                .collect::<Vec<G1Projective>>(),
+            &r_i,
+        );
+
+        let lhs = [lhs_g2.neg().to_affine()]
+            .into_iter()
+            .chain((0..sc.n).map(|i| Into::<G1Projective>::into(&eks[i]).mul(r_i[i]).to_affine()))
+            .chain([pp.get_secret_commitment_base().mul(r_i[sc.n]).to_affine()].into_iter());
+        let rhs = [G2Prepared::from(pp.get_commitment_base().to_affine())]
+            .into_iter()
+            .chain(self.A.iter().map(|a_i| G2Prepared::from(a_i.to_affine())))
+            .chain([G2Prepared::from(self.F_0.to_affine())].into_iter());
+
+        let pairs = lhs.zip(rhs).collect::<Vec<(G1Affine, G2Prepared)>>();
+
+        let res = <Bls12 as MultiMillerLoop>::multi_miller_loop(
+            pairs
+                .iter()
+                .map(|(g1, g2)| (g1, g2))
+                .collect::<Vec<(&G1Affine, &G2Prepared)>>()
+                .as_slice(),
+        );
+
+        res.final_exponentiation() == Gt::identity()
+    }
+
+    fn has_valid_sizes(&self, sc: &ThresholdConfig) -> bool {
+        self.A.len() == sc.n && self.Y.len() == sc.n
+    }
+
+    fn aggregate_with(
+        &mut self,
+        sc: &ThresholdConfig,
+        other: &Transcript,
+    ) -> Result<(), AggregationError> {
+        if !traits::Transcript::has_valid_sizes(self, sc)
+            || !traits::Transcript::has_valid_sizes(other, sc)
+        {
+            return Err(AggregationError::InvalidSizes);
+        }
+
+        merge_contributors(
+            &mut self.contributions,
+            &mut vec![],
+            &other.contributions,
+            &[],
+        )
+        .map_err(AggregationError::DuplicateDealer)?;
+
+        self.u2 += other.u2;
+        self.F_0 += other.F_0;
+
+        for i in 0..sc.n {
+            self.A[i] += other.A[i];
+            self.Y[i] += other.Y[i];
+        }
+
+        Ok(())
+    }
+
+    fn get_dealers(&self) -> Vec<Player> {
+        self.contributions
+            .iter()
+            .map(|c| c.get_dealer().clone())
+            .collect()
+    }
+
+    fn get_dealt_public_key(&self) -> scrape_g1::DealtPubKey {
+        scrape_g1::DealtPubKey::new(self.F_0)
+    }
+
+    fn get_public_key_share(
+        &self,
+        _sc: &ThresholdConfig,
+        player_id: &Player,
+    ) -> Self::DealtPubKeyShare {
+        scrape_g1::DealtPubKeyShare(Self::DealtPubKey::new(self.A[player_id.id]))
+    }
+
+    fn decrypt_own_share(
+        &self,
+        _sc: &ThresholdConfig,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+    ) -> (Self::DealtSecretKeyShare, Self::DealtPubKeyShare) {
+        let ctxt = self.Y[player_id.id]; // Y_i = \ek_i^{f(\omega^i)}
+        let secret_key_share = ctxt.mul(dk.dk); // Y_i^{\dk_i} = h_1^{f(\omega^i)} (because \ek_i = h_1^{\dk_i^{-1}})
+        let verification_key_share = self.A[player_id.id]; // g_2^{f(\omega^i})
+
+        (
+            scrape_g1::DealtSecretKeyShare(Self::DealtSecretKey::new(secret_key_share)),
+            scrape_g1::DealtPubKeyShare(Self::DealtPubKey::new(verification_key_share)),
+        )
+    }
+
+    fn generate<R>(sc: &ThresholdConfig, rng: &mut R) -> Self
+    where
+        R: rand_core::RngCore + rand_core::CryptoRng,
+    {
+        // TODO(rand_core_hell): See `scrape::Transcript::generate` for why we use doublings here.
+        let mut acc_g2 = random_g2_point(rng);
+        let g2_vec = (0..sc.n)
+            .map(|_| {
+                acc_g2 = acc_g2.double();
+                acc_g2
+            })
+            .collect::<Vec<G2Projective>>();
+
+        let mut acc_g1 = random_g1_point(rng);
+        let g1_vec = (0..sc.n)
+            .map(|_| {
+                acc_g1 = acc_g1.double();
+                acc_g1
+            })
+            .collect::<Vec<G1Projective>>();
+
+        let u2 = random_g1_point(rng);
+
+        Transcript {
+            u2,
+            F_0: random_g2_point(rng),
+            A: g2_vec,
+            Y: g1_vec,
+            contributions: vec![random_contribution(&sc.get_player(0), &u2, rng)],
+        }
+    }
+}
+
+impl Transcript {
+    /// Securely derives the Fiat-Shamir challenges for the dual-code low-degree test and for the
+    /// multipairing, via Merlin.
+    fn fiat_shamir(
+        &self,
+        sc: &ThresholdConfig,
+        pp: &scrape_g1::PublicParameters,
+        eks: &Vec<encryption_dlog::g1::EncryptPubKey>,
+        dst: &'static [u8],
+    ) -> (Scalar, Scalar) {
+        // TODO(Security): Audit this
+        let mut fs_t = merlin::Transcript::new(dst);
+        fs_t.pvss_domain_sep(sc);
+        fs_t.append_public_parameters(pp);
+        fs_t.append_encryption_keys(eks);
+
+        fs_t.append_transcript(self);
+        (
+            fs_t.challenge_dual_code_scalar(),
+            fs_t.challenge_multipairing_scalar(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::constants::DST_PVSS_TESTING_APP;
+    use crate::pvss::scrape_g1::Transcript;
+    use crate::pvss::test_utils;
+    use crate::pvss::test_utils::get_threshold_config_and_rng;
+    use crate::pvss::threshold_config::ThresholdConfig;
+    use crate::pvss::traits::transcript::Transcript as UniformTranscript;
+    use crate::pvss::traits::SecretSharingConfig;
+    use aptos_crypto::{Uniform, ValidCryptoMaterial};
+    use blstrs::{G1Projective, G2Projective};
+    use group::Group;
+    use rand::thread_rng;
+
+    #[test]
+    fn transcript_serialization() {
+        let sc = ThresholdConfig::new(10, 20);
+        let mut rng = thread_rng();
+
+        let trx = Transcript::generate(&sc, &mut rng);
+
+        let serialized = trx.to_bytes();
+        let deserialized = Transcript::try_from(serialized.as_slice())
+            .expect("serialized SCRAPE (G1) transcript should deserialize correctly");
+
+        assert_eq!(trx, deserialized);
+    }
+
+    #[test]
+    fn tampered_transcript_does_not_verify() {
+        let (sc, mut rng) = get_threshold_config_and_rng(3, 7);
+        let (pp, _, eks, _, _) = test_utils::setup_dealing::<Transcript>(&sc);
+        let (ssks, spks) = test_utils::setup_signing::<Transcript>(&sc);
+
+        let trx = Transcript::deal(
+            &sc,
+            &pp,
+            &ssks[0],
+            &eks,
+            <Transcript as UniformTranscript>::InputSecret::generate(&mut rng),
+            &sc.get_player(0),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+        assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // A bad encryption
+        let mut bad_trx = trx.clone();
+        bad_trx.Y[2] += G1Projective::generator();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // An evaluation commitment off the polynomial
+        let mut bad_trx = trx.clone();
+        bad_trx.A[2] += G2Projective::generator();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        // A dealt public key that does not match the dealer's commitment
+        let mut bad_trx = trx.clone();
+        bad_trx.F_0 += G2Projective::generator();
+        assert!(!bad_trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+    }
+}
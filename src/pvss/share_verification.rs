@@ -0,0 +1,237 @@
+//! Verification of decrypted dealt secret key shares $h^{a_i}$ against the dealt public key shares
+//! $g^{a_i}$ exposed by a PVSS transcript, via the pairing equation $e(h^{a_i}, g) = e(h, g^{a_i})$,
+//! and robust reconstruction from the shares that pass it.
+
+use crate::pvss::threshold_vrf::{DealtKeyGroup, HasDealtKeyBases};
+use crate::pvss::traits::{Reconstructable, Transcript, WeightedSharingConfig};
+use crate::pvss::{das, dleq_scrape, scrape, scrape_g1, Player, ThresholdConfig, Weighted};
+use crate::utils::{hash_to_scalar, HasMultiExp};
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::Field;
+use std::ops::Mul;
+
+pub const SHARE_VERIFICATION_HASH_TO_SCALAR_DST: &[u8; 48] =
+    b"APTOS_PVSS_SHARE_VERIFICATION_HASH_TO_SCALAR_DST";
+
+/// A PVSS transcript whose decrypted dealt secret key shares can be checked against its dealt public
+/// key shares.
+pub trait VerifiableShare: Transcript {
+    /// Returns true if `sk_share` (e.g., as returned by `Transcript::decrypt_own_share`) matches
+    /// `pk_share`.
+    fn verify_share(
+        pp: &Self::PvssPublicParameters,
+        pk_share: &Self::DealtPubKeyShare,
+        sk_share: &Self::DealtSecretKeyShare,
+    ) -> bool;
+
+    /// Verifies many shares at once, where `sk_shares[i]` should match `pk_shares[i]`. Returns the
+    /// indices of the shares that do not match, if any.
+    fn batch_verify_shares(
+        pp: &Self::PvssPublicParameters,
+        pk_shares: &[Self::DealtPubKeyShare],
+        sk_shares: &[Self::DealtSecretKeyShare],
+    ) -> Result<(), Vec<usize>>;
+
+    /// Reconstructs the dealt secret key from the `shares` of distinct players, leaving out the ones
+    /// that do not match their dealt public key shares, where `pk_shares[i]` is the dealt public key
+    /// share of the player in `shares[i]` (e.g., as given by `Transcript::get_public_key_share`).
+    ///
+    /// Returns the dealt secret key together with the players whose shares do not match, or `None`
+    /// if the remaining shares cannot reconstruct it.
+    fn reconstruct_verified(
+        sc: &Self::SecretSharingConfig,
+        pp: &Self::PvssPublicParameters,
+        shares: &Vec<(Player, Self::DealtSecretKeyShare)>,
+        pk_shares: &[Self::DealtPubKeyShare],
+    ) -> Option<(Self::DealtSecretKey, Vec<Player>)> {
+        assert_eq!(shares.len(), pk_shares.len());
+
+        let sk_shares = shares
+            .iter()
+            .map(|(_, sk_share)| sk_share.clone())
+            .collect::<Vec<Self::DealtSecretKeyShare>>();
+        let culprits = Self::batch_verify_shares(pp, pk_shares, &sk_shares)
+            .err()
+            .unwrap_or_default();
+
+        let valid_shares = shares
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !culprits.contains(i))
+            .map(|(_, share)| share.clone())
+            .collect::<Vec<(Player, Self::DealtSecretKeyShare)>>();
+
+        // The remaining shares are all correct, so this only checks that there are enough of them.
+        let sk = Self::DealtSecretKey::try_reconstruct(sc, &valid_shares).ok()?;
+
+        Some((
+            sk,
+            culprits.into_iter().map(|i| shares[i].0.clone()).collect(),
+        ))
+    }
+}
+
+/// Implements `VerifiableShare` for an unweighted PVSS transcript type whose public parameters
+/// implement `HasDealtKeyBases`.
+macro_rules! verifiable_share_impl {
+    ($Transcript:ty, $Gr:ty) => {
+        impl VerifiableShare for $Transcript {
+            fn verify_share(
+                pp: &Self::PvssPublicParameters,
+                pk_share: &Self::DealtPubKeyShare,
+                sk_share: &Self::DealtSecretKeyShare,
+            ) -> bool {
+                verify_share(
+                    pp,
+                    pk_share.0.as_group_element(),
+                    sk_share.0.as_group_element(),
+                )
+            }
+
+            fn batch_verify_shares(
+                pp: &Self::PvssPublicParameters,
+                pk_shares: &[Self::DealtPubKeyShare],
+                sk_shares: &[Self::DealtSecretKeyShare],
+            ) -> Result<(), Vec<usize>> {
+                assert_eq!(pk_shares.len(), sk_shares.len());
+
+                let pk_shares = pk_shares
+                    .iter()
+                    .map(|pk| *pk.0.as_group_element())
+                    .collect::<Vec<_>>();
+                let sk_shares = sk_shares
+                    .iter()
+                    .map(|sk| *sk.0.as_group_element())
+                    .collect::<Vec<$Gr>>();
+
+                batch_verify_shares(pp, &pk_shares, &sk_shares)
+            }
+        }
+    };
+}
+
+verifiable_share_impl!(scrape::Transcript, G2Projective);
+verifiable_share_impl!(scrape::CompactTranscript, G2Projective);
+verifiable_share_impl!(dleq_scrape::Transcript, G2Projective);
+verifiable_share_impl!(scrape_g1::Transcript, G1Projective);
+verifiable_share_impl!(das::Transcript, G1Projective);
+
+/// A weighted player's share is valid if all of its sub-shares are.
+impl<T, C> VerifiableShare for Weighted<T, C>
+where
+    T: VerifiableShare + Transcript<SecretSharingConfig = ThresholdConfig>,
+    T::DealtPubKeyShare: Clone,
+    C: WeightedSharingConfig,
+{
+    fn verify_share(
+        pp: &Self::PvssPublicParameters,
+        pk_share: &Self::DealtPubKeyShare,
+        sk_share: &Self::DealtSecretKeyShare,
+    ) -> bool {
+        pk_share.len() == sk_share.len()
+            && pk_share
+                .iter()
+                .zip(sk_share.iter())
+                .all(|(pk, sk)| T::verify_share(pp, pk, sk))
+    }
+
+    /// Batch-verifies all sub-shares at once and maps any invalid sub-shares back to the index of
+    /// the share they are part of.
+    fn batch_verify_shares(
+        pp: &Self::PvssPublicParameters,
+        pk_shares: &[Self::DealtPubKeyShare],
+        sk_shares: &[Self::DealtSecretKeyShare],
+    ) -> Result<(), Vec<usize>> {
+        assert_eq!(pk_shares.len(), sk_shares.len());
+
+        // Shares with a mismatched number of sub-shares are invalid, and are left out of the batch.
+        let mut culprits = vec![];
+        let mut owners = vec![];
+        let mut sub_pks = vec![];
+        let mut sub_sks = vec![];
+        for (i, (pk, sk)) in pk_shares.iter().zip(sk_shares.iter()).enumerate() {
+            if pk.len() != sk.len() {
+                culprits.push(i);
+                continue;
+            }
+
+            owners.extend(std::iter::repeat(i).take(pk.len()));
+            sub_pks.extend(pk.iter().cloned());
+            sub_sks.extend(sk.iter().cloned());
+        }
+
+        if let Err(sub_culprits) = T::batch_verify_shares(pp, &sub_pks, &sub_sks) {
+            culprits.extend(sub_culprits.into_iter().map(|j| owners[j]));
+        }
+
+        culprits.sort();
+        culprits.dedup();
+
+        if culprits.is_empty() {
+            Ok(())
+        } else {
+            Err(culprits)
+        }
+    }
+}
+
+/// Checks $e(h^{a_i}, g) = e(h, g^{a_i})$.
+fn verify_share<PP: HasDealtKeyBases>(
+    pp: &PP,
+    pk_share: &<PP::SecretKeyGroup as DealtKeyGroup>::Other,
+    sk_share: &PP::SecretKeyGroup,
+) -> bool {
+    let h = pp.get_dealt_secret_key_base();
+    let g = pp.get_dealt_public_key_base();
+
+    sk_share.pair(g) == h.pair(pk_share)
+}
+
+/// Checks all $e(h^{a_i}, g) = e(h, g^{a_i})$ at once by combining them using powers $\rho^i$ of a
+/// Fiat-Shamir challenge $\rho$ into $e(\sum_i \rho^i h^{a_i}, g) = e(h, \sum_i \rho^i g^{a_i})$,
+/// which only needs two pairings and two multiexps. If the combined check fails, falls back to
+/// checking every share individually, to find the invalid ones.
+fn batch_verify_shares<PP: HasDealtKeyBases>(
+    pp: &PP,
+    pk_shares: &[<PP::SecretKeyGroup as DealtKeyGroup>::Other],
+    sk_shares: &[PP::SecretKeyGroup],
+) -> Result<(), Vec<usize>> {
+    debug_assert_eq!(pk_shares.len(), sk_shares.len());
+    if sk_shares.is_empty() {
+        return Ok(());
+    }
+
+    let h = pp.get_dealt_secret_key_base();
+    let g = pp.get_dealt_public_key_base();
+
+    let rho = batch_challenge(pk_shares, sk_shares);
+    let mut rho_i = Vec::with_capacity(sk_shares.len());
+    rho_i.push(Scalar::one());
+    for _ in 1..sk_shares.len() {
+        rho_i.push(rho_i.last().unwrap().mul(rho));
+    }
+
+    let sk = PP::SecretKeyGroup::multi_exp(sk_shares, &rho_i);
+    let pk = <PP::SecretKeyGroup as DealtKeyGroup>::Other::multi_exp(pk_shares, &rho_i);
+    if sk.pair(g) == h.pair(&pk) {
+        return Ok(());
+    }
+
+    Err((0..sk_shares.len())
+        .filter(|&i| !verify_share(pp, &pk_shares[i], &sk_shares[i]))
+        .collect())
+}
+
+/// Derives the challenge $\rho$ for batch verification by hashing all the shares.
+fn batch_challenge<Gr: HasMultiExp, OtherGr: HasMultiExp>(
+    pk_shares: &[OtherGr],
+    sk_shares: &[Gr],
+) -> Scalar {
+    let mut buf = vec![];
+    for (pk, sk) in pk_shares.iter().zip(sk_shares.iter()) {
+        buf.extend(pk.to_compressed_bytes());
+        buf.extend(sk.to_compressed_bytes());
+    }
+
+    hash_to_scalar(buf.as_slice(), SHARE_VERIFICATION_HASH_TO_SCALAR_DST)
+}
@@ -0,0 +1,329 @@
+//! The input secret, public parameters and Fiat-Shamir protocol shared by the PVSS schemes whose
+//! dealt secret keys and encryption keys are in $G_1$ and whose dealt public keys are in $G_2$: the
+//! SCRAPE variant in `scrape_g1` and DAS in `das`.
+//!
+//! Both schemes' public parameters consist of the encryption key base, one more base in $G_1$ and a
+//! base $g_2$ for the commitments to the polynomial. Since the schemes use the second $G_1$ base
+//! differently, `sk_in_g1_impl!` gives each one its own `PublicParameters` type.
+
+use crate::utils::random::random_scalar;
+use aptos_crypto::traits::Uniform;
+use aptos_crypto_derive::{SilentDebug, SilentDisplay};
+use blstrs::Scalar;
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+
+/// The *input secret* that will be given as input to the PVSS dealing algorithm. See
+/// `scrape::InputSecret`.
+#[derive(SilentDebug, SilentDisplay, PartialEq)]
+pub struct InputSecret {
+    /// The actual secret being dealt; a scalar $a \in F$.
+    a: Scalar,
+}
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(InputSecret: Clone);
+
+//
+// InputSecret implementation
+//
+
+impl InputSecret {
+    /// The zero secret, which is dealt to refresh the shares of a previously-dealt secret (see
+    /// `refresh::Refreshable`).
+    pub(crate) fn zero() -> Self {
+        InputSecret { a: Scalar::zero() }
+    }
+
+    pub fn get_secret_a(&self) -> &Scalar {
+        &self.a
+    }
+}
+
+impl Uniform for InputSecret {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        let a = random_scalar(rng);
+
+        InputSecret { a }
+    }
+}
+
+macro_rules! sk_in_g1_impl {
+    (
+        $dom_sep:literal,
+        $hash_to_scalar_dst:literal,
+        $enc_label:literal,
+        $base:ident,
+        $base_label:literal,
+        $base_doc:literal,
+        $get_base:ident,
+        $get_secret_key_base:ident
+    ) => {
+        use crate::constants::{
+            DST_PVSS_PUBLIC_PARAMS_GENERATION, G1_PROJ_NUM_BYTES, G2_PROJ_NUM_BYTES,
+            SEED_PVSS_PUBLIC_PARAMS_GENERATION,
+        };
+        use crate::pvss::dealt_pub_key::g2::DealtPubKey;
+        use crate::pvss::dealt_secret_key::g1::DealtSecretKey;
+        use crate::pvss::encryption_dlog;
+        use crate::pvss::encryption_dlog::g1::EncryptPubKey;
+        use crate::pvss::sk_in_g1::InputSecret;
+        use crate::pvss::threshold_config::ThresholdConfig;
+        use crate::pvss::traits;
+        use crate::utils::{fiat_shamir, hash_to_scalar};
+        use aptos_crypto::{
+            CryptoMaterialError, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
+        };
+        use aptos_crypto_derive::{DeserializeKey, SerializeKey};
+        use blstrs::{G1Projective, G2Projective, Scalar};
+        use std::ops::Mul;
+
+        //
+        // Constants
+        //
+
+        pub const PVSS_DOM_SEP: &[u8] = $dom_sep;
+        pub const PVSS_HASH_TO_SCALAR_DST: &[u8] = $hash_to_scalar_dst;
+
+        /// The size, in number of bytes, of a serialized `PublicParameters` struct.
+        const NUM_BYTES: usize = 2 * G1_PROJ_NUM_BYTES + G2_PROJ_NUM_BYTES;
+
+        //
+        // Public parameters
+        //
+
+        /// The cryptographic *public parameters* needed to run the PVSS protocol.
+        #[derive(DeserializeKey, Clone, SerializeKey)]
+        pub struct PublicParameters {
+            /// Base for the encryption keys $h^{dk^{-1}} \in G_1$
+            enc: encryption_dlog::g1::PublicParameters,
+            #[doc = $base_doc]
+            $base: G1Projective,
+            /// Base for the commitments to the polynomial (and for the dealt public key
+            /// $g_2^a \in G_2$)
+            g2: G2Projective,
+        }
+
+        impl PublicParameters {
+            /// Verifiably creates public parameters from a public sequence of bytes `seed`.
+            pub fn new_from_seed(seed: &[u8]) -> Self {
+                PublicParameters {
+                    enc: encryption_dlog::g1::PublicParameters::new(G1Projective::hash_to_curve(
+                        seed,
+                        DST_PVSS_PUBLIC_PARAMS_GENERATION.as_slice(),
+                        $enc_label,
+                    )),
+                    $base: G1Projective::hash_to_curve(
+                        seed,
+                        DST_PVSS_PUBLIC_PARAMS_GENERATION.as_slice(),
+                        $base_label,
+                    ),
+                    g2: G2Projective::hash_to_curve(
+                        seed,
+                        DST_PVSS_PUBLIC_PARAMS_GENERATION.as_slice(),
+                        b"g2",
+                    ),
+                }
+            }
+
+            /// Returns the base $h$ used for computing an encryption key $h^{dk^{-1}}$.
+            pub fn get_encryption_key_base(&self) -> &G1Projective {
+                &self.enc.as_group_element()
+            }
+
+            #[doc = $base_doc]
+            pub fn $get_base(&self) -> &G1Projective {
+                &self.$base
+            }
+
+            /// Returns the base $g_2$ used for committing to the polynomial (i.e., the dealt public
+            /// key $g_2^a$ and shares of it).
+            pub fn get_commitment_base(&self) -> &G2Projective {
+                &self.g2
+            }
+
+            /// Serializes the public parameters.
+            pub fn to_bytes(&self) -> Vec<u8> {
+                let mut bytes = self.enc.as_group_element().to_compressed().to_vec();
+
+                bytes.append(&mut self.$base.to_compressed().to_vec());
+                bytes.append(&mut self.g2.to_compressed().to_vec());
+
+                bytes
+            }
+        }
+
+        impl ValidCryptoMaterial for PublicParameters {
+            fn to_bytes(&self) -> Vec<u8> {
+                self.to_bytes()
+            }
+        }
+
+        impl Default for PublicParameters {
+            /// Verifiably creates Aptos-specific public parameters.
+            fn default() -> Self {
+                Self::new_from_seed(SEED_PVSS_PUBLIC_PARAMS_GENERATION)
+            }
+        }
+
+        impl traits::HasEncryptionPublicParams for PublicParameters {
+            type EncryptionPublicParameters = encryption_dlog::g1::PublicParameters;
+
+            fn get_encryption_public_params(&self) -> &Self::EncryptionPublicParameters {
+                &self.enc
+            }
+        }
+
+        impl TryFrom<&[u8]> for PublicParameters {
+            type Error = CryptoMaterialError;
+
+            /// Deserialize a `PublicParameters` struct.
+            fn try_from(bytes: &[u8]) -> std::result::Result<PublicParameters, Self::Error> {
+                let slice: &[u8; NUM_BYTES] = match <&[u8; NUM_BYTES]>::try_from(bytes) {
+                    Ok(slice) => slice,
+                    Err(_) => return Err(CryptoMaterialError::WrongLengthError),
+                };
+
+                let enc_bytes = slice[0..G1_PROJ_NUM_BYTES].try_into().unwrap();
+                let base_bytes = slice[G1_PROJ_NUM_BYTES..2 * G1_PROJ_NUM_BYTES]
+                    .try_into()
+                    .unwrap();
+                let g2_bytes = slice[2 * G1_PROJ_NUM_BYTES..NUM_BYTES].try_into().unwrap();
+
+                let enc_opt = G1Projective::from_compressed(enc_bytes);
+                let base_opt = G1Projective::from_compressed(base_bytes);
+                let g2_opt = G2Projective::from_compressed(g2_bytes);
+
+                if enc_opt.is_some().unwrap_u8() == 1u8
+                    && base_opt.is_some().unwrap_u8() == 1u8
+                    && g2_opt.is_some().unwrap_u8() == 1u8
+                {
+                    Ok(PublicParameters {
+                        enc: encryption_dlog::g1::PublicParameters::new(enc_opt.unwrap()),
+                        $base: base_opt.unwrap(),
+                        g2: g2_opt.unwrap(),
+                    })
+                } else {
+                    Err(CryptoMaterialError::DeserializationError)
+                }
+            }
+        }
+
+        //
+        // Input secret conversions
+        //
+
+        impl traits::Convert<DealtSecretKey, PublicParameters> for InputSecret {
+            fn to(&self, pp: &PublicParameters) -> DealtSecretKey {
+                DealtSecretKey::new(pp.$get_secret_key_base().mul(self.get_secret_a()))
+            }
+        }
+
+        impl traits::Convert<DealtPubKey, PublicParameters> for InputSecret {
+            /// Computes the public key associated with the given input secret.
+            fn to(&self, pp: &PublicParameters) -> DealtPubKey {
+                DealtPubKey::new(pp.get_commitment_base().mul(self.get_secret_a()))
+            }
+        }
+
+        //
+        // Fiat-Shamir
+        //
+
+        pub trait FiatShamirProtocol {
+            /// Append a domain separator for the PVSS protocol, consisting of a sharing
+            /// configuration `sc`, which locks in the $t$ out of $n$ threshold.
+            fn pvss_domain_sep(&mut self, sc: &ThresholdConfig);
+
+            /// Append the public parameters `pp`.
+            fn append_public_parameters(&mut self, pp: &PublicParameters);
+
+            /// Append the encryption keys `eks`.
+            fn append_encryption_keys(&mut self, eks: &Vec<EncryptPubKey>);
+
+            /// Appends the transcript
+            fn append_transcript<T: ValidCryptoMaterial>(&mut self, trx: &T);
+
+            /// Compute the Fiat-Shamir challenge `\beta` for the SCRAPE dual-code low-degree test,
+            /// whose dual polynomial has coefficients $1, \beta, \beta^2, \ldots, \beta^{n-t}$
+            fn challenge_dual_code_scalar(&mut self) -> Scalar;
+
+            /// Compute the Fiat-Shamir challenge `r` for combining pairings in the multipairing
+            /// using coefficients $1, r, r^2, r^3, \ldots$
+            fn challenge_multipairing_scalar(&mut self) -> Scalar;
+        }
+
+        // TODO(Security): Audit this
+        impl FiatShamirProtocol for merlin::Transcript {
+            fn pvss_domain_sep(&mut self, sc: &ThresholdConfig) {
+                self.append_message(b"dom-sep", PVSS_DOM_SEP);
+                self.append_u64(b"t", sc.t as u64);
+                self.append_u64(b"n", sc.n as u64);
+            }
+
+            fn append_public_parameters(&mut self, pp: &PublicParameters) {
+                self.append_message(b"pp", pp.to_bytes().as_slice());
+            }
+
+            fn append_encryption_keys(&mut self, eks: &Vec<EncryptPubKey>) {
+                fiat_shamir::append_g1_vector(
+                    self,
+                    b"encryption-keys",
+                    &eks.iter()
+                        .map(|ek| Into::<G1Projective>::into(ek))
+                        .collect::<Vec<G1Projective>>(),
+                )
+            }
+
+            fn append_transcript<T: ValidCryptoMaterial>(&mut self, trx: &T) {
+                self.append_message(b"transcript", trx.to_bytes().as_slice());
+            }
+
+            fn challenge_dual_code_scalar(&mut self) -> Scalar {
+                let mut buf = [0u8; 64];
+                self.challenge_bytes(b"challenge_dual_code", &mut buf);
+
+                hash_to_scalar(buf.as_slice(), PVSS_HASH_TO_SCALAR_DST)
+            }
+
+            fn challenge_multipairing_scalar(&mut self) -> Scalar {
+                let mut buf = [0u8; 64];
+                self.challenge_bytes(b"challenge_multipairing", &mut buf);
+
+                hash_to_scalar(buf.as_slice(), PVSS_HASH_TO_SCALAR_DST)
+            }
+        }
+    };
+}
+
+/// For `scrape_g1`, whose encryption key base $h_1$ is also the dealt secret key base.
+pub mod scrape_g1 {
+    sk_in_g1_impl!(
+        b"APTOS_SCRAPE_G1_PVSS_DST",
+        b"APTOS_SCRAPE_G1_PVSS_HASH_TO_SCALAR_DST",
+        b"h1",
+        u1,
+        b"u1",
+        "Base $u_1$ for the commitment $u_1^a$ to the dealt secret $a$, which the PoKs are for.",
+        get_secret_commitment_base,
+        get_encryption_key_base
+    );
+}
+
+/// For `das`, whose encryption key base $g_1$ is also the base of the ElGamal randomness $g_1^r$
+/// and of the dealers' commitments $g_1^a$.
+pub mod das {
+    sk_in_g1_impl!(
+        b"APTOS_DAS_PVSS_DST",
+        b"APTOS_DAS_PVSS_HASH_TO_SCALAR_DST",
+        b"g1",
+        h1,
+        b"h1",
+        "Base $h_1$ for the dealt secret key $h_1^a \\in G_1$ and for shares of it.",
+        get_secret_key_base,
+        get_secret_key_base
+    );
+}
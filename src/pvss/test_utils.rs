@@ -16,22 +16,64 @@ pub fn setup_dealing<T: Transcript>(
     T::InputSecret,
     T::DealtSecretKey,
 ) {
-    let mut rng = thread_rng();
+    setup_dealing_with_rng::<T, _>(sc, &mut thread_rng())
+}
 
+/// Like `setup_dealing`, but derives all the keys and the input secret from `rng`, so that they can be
+/// reproduced from a seed.
+pub fn setup_dealing_with_rng<T: Transcript, R>(
+    sc: &T::SecretSharingConfig,
+    rng: &mut R,
+) -> (
+    T::PvssPublicParameters,
+    Vec<T::DecryptPrivKey>,
+    Vec<T::EncryptPubKey>,
+    T::InputSecret,
+    T::DealtSecretKey,
+)
+where
+    R: rand_core::RngCore + rand_core::CryptoRng,
+{
     let pp = T::PvssPublicParameters::default();
     let dks = (0..sc.get_total_num_players())
-        .map(|_| T::DecryptPrivKey::generate(&mut rng))
+        .map(|_| T::DecryptPrivKey::generate(rng))
         .collect::<Vec<T::DecryptPrivKey>>();
     let eks = dks
         .iter()
         .map(|dk| dk.to(&pp.get_encryption_public_params()))
         .collect();
-    let s = T::InputSecret::generate(&mut rng);
+    let s = T::InputSecret::generate(rng);
     let sk: <T as Transcript>::DealtSecretKey = s.to(&pp);
 
     (pp, dks, eks, s, sk)
 }
 
+/// Helper function that returns the signing key-pairs of all the dealers, where player $i$ is dealer $i$.
+pub fn setup_signing<T: Transcript>(
+    sc: &T::SecretSharingConfig,
+) -> (Vec<T::SigningSecretKey>, Vec<T::SigningPubKey>) {
+    setup_signing_with_rng::<T, _>(sc, &mut thread_rng())
+}
+
+/// Like `setup_signing`, but derives all the signing keys from `rng`.
+pub fn setup_signing_with_rng<T: Transcript, R>(
+    sc: &T::SecretSharingConfig,
+    rng: &mut R,
+) -> (Vec<T::SigningSecretKey>, Vec<T::SigningPubKey>)
+where
+    R: rand_core::RngCore + rand_core::CryptoRng,
+{
+    let ssks = (0..sc.get_total_num_players())
+        .map(|_| T::SigningSecretKey::generate(rng))
+        .collect::<Vec<T::SigningSecretKey>>();
+    let spks = ssks
+        .iter()
+        .map(|ssk| T::SigningPubKey::from(ssk))
+        .collect::<Vec<T::SigningPubKey>>();
+
+    (ssks, spks)
+}
+
 pub fn get_threshold_config_and_rng(t: usize, n: usize) -> (ThresholdConfig, ThreadRng) {
     let sc = ThresholdConfig::new(t, n);
 
@@ -74,4 +74,8 @@ impl traits::SecretSharingConfig for ThresholdConfig {
     fn get_total_num_shares(&self) -> usize {
         self.n
     }
+
+    fn get_player_weight(&self, _player: &Player) -> usize {
+        1
+    }
 }
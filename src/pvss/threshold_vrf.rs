@@ -0,0 +1,523 @@
+//! # Threshold VRF on the dealt secret key
+//!
+//! A PVSS transcript deals a secret key $h^a$ (in $\mathbb{G}_1$ or $\mathbb{G}_2$) that is never
+//! reconstructed. Instead, it is used via a threshold verifiable random function (VRF) whose output
+//! on a message $m$ is:
+//!
+//! $$y = e(h^a, H(m)) \in \mathbb{G}_T$$
+//!
+//! where $H$ hashes into the group of the dealt public key $g^a$ (and $e$ pairs its arguments in
+//! the right order). Each player $i$ computes a *partial evaluation* $y_i = e(h^{a_i}, H(m))$ using
+//! its dealt secret key share $h^{a_i}$, and any $t$ partial evaluations are combined into $y$ via
+//! Lagrange interpolation in the exponent.
+//!
+//! Since the shares are group elements, rather than scalars, a partial evaluation cannot be checked
+//! against the dealt public key share $g^{a_i}$ with a pairing. Instead, it comes with a sigma
+//! protocol proof of knowledge of an $X$ such that $e(X, g) = e(h, g^{a_i})$ and $e(X, H(m)) = y_i$.
+//! Since $e(\cdot, g)$ is injective, $X = h^{a_i}$ and $y_i$ is uniquely determined. Similarly, the
+//! output $y$ is checked against the dealt public key $g^a$ by checking the partial evaluations it
+//! was combined from, together with the dealt public key shares they were checked against.
+
+use crate::algebra::lagrange::lagrange_coefficients_at_zero;
+use crate::pvss::traits::{SecretSharingConfig, Transcript, WeightedSharingConfig};
+use crate::pvss::{das, dleq_scrape, scrape, scrape_g1, Player, ThresholdConfig, Weighted};
+use crate::utils::{hash_to_scalar, random::random_scalar, HasMultiExp};
+use blstrs::{pairing, G1Projective, G2Projective, Gt, Scalar};
+use group::{Curve, Group};
+use more_asserts::assert_ge;
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ops::Mul;
+
+pub const THRESHOLD_VRF_HASH_TO_CURVE_DST: &[u8; 37] = b"APTOS_THRESHOLD_VRF_HASH_TO_CURVE_DST";
+pub const THRESHOLD_VRF_PROOF_DST: &[u8; 29] = b"APTOS_THRESHOLD_VRF_PROOF_DST";
+
+/// The group of a dealt secret key $h^a$, which is paired with elements of the group of the dealt
+/// public key $g^a$ (i.e., the other group). Lets us implement the VRF generically over dealt secret
+/// keys in $\mathbb{G}_1$ and in $\mathbb{G}_2$.
+pub trait DealtKeyGroup: HasMultiExp + Serialize {
+    type Other: HasMultiExp;
+
+    /// Pairs an element of this group with an element of the other group.
+    fn pair(&self, other: &Self::Other) -> Gt;
+
+    /// Hashes the VRF message into the other group.
+    fn hash_to_other(msg: &[u8]) -> Self::Other;
+}
+
+impl DealtKeyGroup for G1Projective {
+    type Other = G2Projective;
+
+    fn pair(&self, other: &G2Projective) -> Gt {
+        pairing(&self.to_affine(), &other.to_affine())
+    }
+
+    fn hash_to_other(msg: &[u8]) -> G2Projective {
+        G2Projective::hash_to_curve(msg, THRESHOLD_VRF_HASH_TO_CURVE_DST.as_slice(), b"")
+    }
+}
+
+impl DealtKeyGroup for G2Projective {
+    type Other = G1Projective;
+
+    fn pair(&self, other: &G1Projective) -> Gt {
+        pairing(&other.to_affine(), &self.to_affine())
+    }
+
+    fn hash_to_other(msg: &[u8]) -> G1Projective {
+        G1Projective::hash_to_curve(msg, THRESHOLD_VRF_HASH_TO_CURVE_DST.as_slice(), b"")
+    }
+}
+
+/// PVSS public parameters that give access to the base $h$ of the dealt secret key $h^a$ and to the
+/// base $g$ of the dealt public key $g^a$.
+pub trait HasDealtKeyBases {
+    type SecretKeyGroup: DealtKeyGroup;
+
+    fn get_dealt_secret_key_base(&self) -> &Self::SecretKeyGroup;
+
+    fn get_dealt_public_key_base(&self) -> &<Self::SecretKeyGroup as DealtKeyGroup>::Other;
+}
+
+impl HasDealtKeyBases for scrape::PublicParameters {
+    type SecretKeyGroup = G2Projective;
+
+    fn get_dealt_secret_key_base(&self) -> &G2Projective {
+        self.get_encryption_key_base()
+    }
+
+    fn get_dealt_public_key_base(&self) -> &G1Projective {
+        self.get_commitment_base()
+    }
+}
+
+impl HasDealtKeyBases for scrape_g1::PublicParameters {
+    type SecretKeyGroup = G1Projective;
+
+    fn get_dealt_secret_key_base(&self) -> &G1Projective {
+        self.get_encryption_key_base()
+    }
+
+    fn get_dealt_public_key_base(&self) -> &G2Projective {
+        self.get_commitment_base()
+    }
+}
+
+impl HasDealtKeyBases for das::PublicParameters {
+    type SecretKeyGroup = G1Projective;
+
+    fn get_dealt_secret_key_base(&self) -> &G1Projective {
+        self.get_secret_key_base()
+    }
+
+    fn get_dealt_public_key_base(&self) -> &G2Projective {
+        self.get_commitment_base()
+    }
+}
+
+/// A player's partial evaluation $y_i = e(h^{a_i}, H(m))$ of the VRF, together with a proof $(c, Z)$
+/// that it was computed using the dealt secret key share $h^{a_i}$. Here, $Z = R + c \cdot h^{a_i}$
+/// for a random $R$ and a Fiat-Shamir challenge $c$ over $e(R, g)$ and $e(R, H(m))$.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PartialEval<Gr> {
+    y: Gt,
+    c: Scalar,
+    z: Gr,
+}
+
+/// The output $y = e(h^a, H(m))$ of the VRF on a message $m$.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Eval(Gt);
+
+impl Eval {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&self.0).expect("unexpected error during VRF output serialization")
+    }
+}
+
+/// A threshold VRF on the dealt secret key of a PVSS transcript. See the module-level docs.
+pub trait ThresholdVrf: Transcript {
+    type PartialEval: Clone;
+
+    /// Evaluates the VRF on `msg` using a player's dealt secret key share.
+    fn eval_share<R: RngCore + CryptoRng>(
+        pp: &Self::PvssPublicParameters,
+        sk_share: &Self::DealtSecretKeyShare,
+        msg: &[u8],
+        rng: &mut R,
+    ) -> Self::PartialEval;
+
+    /// Verifies a player's partial evaluation on `msg` against its dealt public key share.
+    fn verify_eval_share(
+        pp: &Self::PvssPublicParameters,
+        pk_share: &Self::DealtPubKeyShare,
+        msg: &[u8],
+        partial: &Self::PartialEval,
+    ) -> bool;
+
+    /// Combines the (already verified) partial evaluations of a subset of players capable of
+    /// reconstructing into the VRF output.
+    fn combine(sc: &Self::SecretSharingConfig, partials: &Vec<(Player, Self::PartialEval)>)
+        -> Eval;
+
+    /// Verifies the VRF output `eval` on `msg` against the dealt public key, given the partial
+    /// evaluations it was combined from and the dealt public key shares of the players who computed
+    /// them.
+    fn verify_eval(
+        sc: &Self::SecretSharingConfig,
+        pp: &Self::PvssPublicParameters,
+        pk: &Self::DealtPubKey,
+        msg: &[u8],
+        partials: &Vec<(Player, Self::DealtPubKeyShare, Self::PartialEval)>,
+        eval: &Eval,
+    ) -> bool;
+}
+
+/// Implements `ThresholdVrf` for an unweighted PVSS transcript type whose public parameters
+/// implement `HasDealtKeyBases`.
+macro_rules! threshold_vrf_impl {
+    ($Transcript:ty, $Gr:ty) => {
+        impl ThresholdVrf for $Transcript {
+            type PartialEval = PartialEval<$Gr>;
+
+            fn eval_share<R: RngCore + CryptoRng>(
+                pp: &Self::PvssPublicParameters,
+                sk_share: &Self::DealtSecretKeyShare,
+                msg: &[u8],
+                rng: &mut R,
+            ) -> Self::PartialEval {
+                eval_share(pp, sk_share.0.as_group_element(), msg, rng)
+            }
+
+            fn verify_eval_share(
+                pp: &Self::PvssPublicParameters,
+                pk_share: &Self::DealtPubKeyShare,
+                msg: &[u8],
+                partial: &Self::PartialEval,
+            ) -> bool {
+                verify_eval_share(pp, pk_share.0.as_group_element(), msg, partial)
+            }
+
+            fn combine(sc: &ThresholdConfig, partials: &Vec<(Player, Self::PartialEval)>) -> Eval {
+                combine(sc, partials)
+            }
+
+            fn verify_eval(
+                sc: &ThresholdConfig,
+                pp: &Self::PvssPublicParameters,
+                pk: &Self::DealtPubKey,
+                msg: &[u8],
+                partials: &Vec<(Player, Self::DealtPubKeyShare, Self::PartialEval)>,
+                eval: &Eval,
+            ) -> bool {
+                let partials = partials
+                    .iter()
+                    .map(|(p, pk_share, partial)| {
+                        (p.clone(), *pk_share.0.as_group_element(), partial)
+                    })
+                    .collect::<Vec<_>>();
+
+                verify_eval(sc, pp, pk.as_group_element(), msg, &partials, eval)
+            }
+        }
+    };
+}
+
+threshold_vrf_impl!(scrape::Transcript, G2Projective);
+threshold_vrf_impl!(scrape::CompactTranscript, G2Projective);
+threshold_vrf_impl!(dleq_scrape::Transcript, G2Projective);
+threshold_vrf_impl!(scrape_g1::Transcript, G1Projective);
+threshold_vrf_impl!(das::Transcript, G1Projective);
+
+/// A weighted player evaluates the VRF once per sub-share (i.e., once per unit of weight).
+impl<T, C> ThresholdVrf for Weighted<T, C>
+where
+    T: ThresholdVrf + Transcript<SecretSharingConfig = ThresholdConfig>,
+    T::DealtPubKeyShare: Clone,
+    C: WeightedSharingConfig,
+{
+    type PartialEval = Vec<T::PartialEval>;
+
+    fn eval_share<R: RngCore + CryptoRng>(
+        pp: &Self::PvssPublicParameters,
+        sk_share: &Self::DealtSecretKeyShare,
+        msg: &[u8],
+        rng: &mut R,
+    ) -> Self::PartialEval {
+        sk_share
+            .iter()
+            .map(|sub_share| T::eval_share(pp, sub_share, msg, rng))
+            .collect()
+    }
+
+    fn verify_eval_share(
+        pp: &Self::PvssPublicParameters,
+        pk_share: &Self::DealtPubKeyShare,
+        msg: &[u8],
+        partial: &Self::PartialEval,
+    ) -> bool {
+        pk_share.len() == partial.len()
+            && pk_share
+                .iter()
+                .zip(partial.iter())
+                .all(|(pk, partial)| T::verify_eval_share(pp, pk, msg, partial))
+    }
+
+    /// Only combines the $w$ sub-evaluations picked by `WeightedConfig::get_min_reconstructing_subset`.
+    fn combine(sc: &C, partials: &Vec<(Player, Self::PartialEval)>) -> Eval {
+        let sc = sc.get_weighted_config();
+        let players = partials
+            .iter()
+            .map(|(p, _)| p.clone())
+            .collect::<Vec<Player>>();
+        let subset = sc
+            .get_min_reconstructing_subset(&players)
+            .expect("players should have enough weight to combine partial evaluations");
+
+        let partials = partials
+            .iter()
+            .map(|(p, partial)| (p.get_id(), partial))
+            .collect::<HashMap<usize, &Vec<T::PartialEval>>>();
+
+        let mut flattened = vec![];
+        for (player, num_shares) in subset {
+            let sub_partials = partials[&player.get_id()];
+            assert_eq!(sub_partials.len(), sc.get_player_weight(&player));
+
+            for (pos, partial) in sub_partials.iter().take(num_shares).enumerate() {
+                flattened.push((sc.get_virtual_player(&player, pos), partial.clone()));
+            }
+        }
+
+        T::combine(sc.get_threshold_config(), &flattened)
+    }
+
+    fn verify_eval(
+        sc: &C,
+        pp: &Self::PvssPublicParameters,
+        pk: &Self::DealtPubKey,
+        msg: &[u8],
+        partials: &Vec<(Player, Self::DealtPubKeyShare, Self::PartialEval)>,
+        eval: &Eval,
+    ) -> bool {
+        let sc = sc.get_weighted_config();
+
+        let mut flattened = vec![];
+        for (player, pk_shares, sub_partials) in partials {
+            if player.get_id() >= sc.get_total_num_players()
+                || pk_shares.len() != sc.get_player_weight(player)
+                || sub_partials.len() != pk_shares.len()
+            {
+                return false;
+            }
+
+            for (pos, (pk_share, partial)) in pk_shares.iter().zip(sub_partials.iter()).enumerate()
+            {
+                flattened.push((
+                    sc.get_virtual_player(player, pos),
+                    pk_share.clone(),
+                    partial.clone(),
+                ));
+            }
+        }
+
+        T::verify_eval(
+            sc.get_threshold_config(),
+            pp,
+            pk.get_key(),
+            msg,
+            &flattened,
+            eval,
+        )
+    }
+}
+
+/// Computes $y_i = e(h^{a_i}, H(m))$ and a proof $(c, Z)$ of knowledge of $X = h^{a_i}$ such that
+/// $e(X, g) = e(h, g^{a_i})$ and $e(X, H(m)) = y_i$.
+fn eval_share<PP, R>(
+    pp: &PP,
+    sk_share: &PP::SecretKeyGroup,
+    msg: &[u8],
+    rng: &mut R,
+) -> PartialEval<PP::SecretKeyGroup>
+where
+    PP: HasDealtKeyBases,
+    R: RngCore + CryptoRng,
+{
+    let g = pp.get_dealt_public_key_base();
+    let h_m = PP::SecretKeyGroup::hash_to_other(msg);
+
+    let y = sk_share.pair(&h_m);
+    let statement = sk_share.pair(g); // = e(h, g^{a_i})
+
+    let r = PP::SecretKeyGroup::generator().mul(random_scalar(rng));
+    let c = proof_challenge(msg, &statement, &y, &r.pair(g), &r.pair(&h_m));
+    let z = r + sk_share.mul(c);
+
+    PartialEval { y, c, z }
+}
+
+/// Verifies a partial evaluation against the dealt public key share $g^{a_i}$ by recomputing
+/// $e(R, g) = e(Z, g) - c \cdot e(h, g^{a_i})$ and $e(R, H(m)) = e(Z, H(m)) - c \cdot y_i$ (in
+/// additive notation for $\mathbb{G}_T$) and checking the Fiat-Shamir challenge $c$.
+fn verify_eval_share<PP: HasDealtKeyBases>(
+    pp: &PP,
+    pk_share: &<PP::SecretKeyGroup as DealtKeyGroup>::Other,
+    msg: &[u8],
+    partial: &PartialEval<PP::SecretKeyGroup>,
+) -> bool {
+    let h = pp.get_dealt_secret_key_base();
+    let g = pp.get_dealt_public_key_base();
+    let h_m = PP::SecretKeyGroup::hash_to_other(msg);
+
+    let statement = h.pair(pk_share);
+    let r_g = partial.z.pair(g) - statement * partial.c;
+    let r_h_m = partial.z.pair(&h_m) - partial.y * partial.c;
+
+    partial.c == proof_challenge(msg, &statement, &partial.y, &r_g, &r_h_m)
+}
+
+/// Computes $y = \sum_i \lambda_i y_i$ (in additive notation for $\mathbb{G}_T$), where the
+/// $\lambda_i$'s are the Lagrange coefficients for the players in `partials`.
+fn combine<Gr>(sc: &ThresholdConfig, partials: &Vec<(Player, PartialEval<Gr>)>) -> Eval {
+    assert_ge!(partials.len(), sc.get_threshold());
+
+    let ids = partials.iter().map(|(p, _)| p.id).collect::<Vec<usize>>();
+    let lagr = lagrange_coefficients_at_zero(sc.get_batch_evaluation_domain(), ids.as_slice());
+
+    Eval(
+        partials
+            .iter()
+            .zip(lagr.iter())
+            .map(|((_, partial), lambda)| partial.y * lambda)
+            .sum(),
+    )
+}
+
+/// Checks that the dealt public key shares interpolate to the dealt public key `pk`, that every
+/// partial evaluation verifies against its dealt public key share and that they combine into `eval`.
+fn verify_eval<PP: HasDealtKeyBases>(
+    sc: &ThresholdConfig,
+    pp: &PP,
+    pk: &<PP::SecretKeyGroup as DealtKeyGroup>::Other,
+    msg: &[u8],
+    partials: &Vec<(
+        Player,
+        <PP::SecretKeyGroup as DealtKeyGroup>::Other,
+        &PartialEval<PP::SecretKeyGroup>,
+    )>,
+    eval: &Eval,
+) -> bool {
+    let ids = partials
+        .iter()
+        .map(|(p, _, _)| p.id)
+        .collect::<Vec<usize>>();
+    if ids.is_empty()
+        || ids.iter().any(|&id| id >= sc.get_total_num_players())
+        || ids.iter().collect::<HashSet<&usize>>().len() != ids.len()
+    {
+        return false;
+    }
+
+    let lagr = lagrange_coefficients_at_zero(sc.get_batch_evaluation_domain(), ids.as_slice());
+    let pk_shares = partials
+        .iter()
+        .map(|(_, pk_share, _)| *pk_share)
+        .collect::<Vec<_>>();
+    if <PP::SecretKeyGroup as DealtKeyGroup>::Other::multi_exp(&pk_shares, &lagr) != *pk {
+        return false;
+    }
+
+    if !partials
+        .iter()
+        .all(|(_, pk_share, partial)| verify_eval_share(pp, pk_share, msg, partial))
+    {
+        return false;
+    }
+
+    let y: Gt = partials
+        .iter()
+        .zip(lagr.iter())
+        .map(|((_, _, partial), lambda)| partial.y * lambda)
+        .sum();
+
+    y == eval.0
+}
+
+fn proof_challenge(msg: &[u8], statement: &Gt, y: &Gt, r_g: &Gt, r_h_m: &Gt) -> Scalar {
+    let buf = bcs::to_bytes(&(msg, statement, y, r_g, r_h_m))
+        .expect("unexpected error during VRF proof serialization");
+
+    hash_to_scalar(buf.as_slice(), THRESHOLD_VRF_PROOF_DST)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::constants::DST_PVSS_TESTING_APP;
+    use crate::pvss::test_utils;
+    use crate::pvss::threshold_vrf::ThresholdVrf;
+    use crate::pvss::traits::{SecretSharingConfig, Transcript};
+    use crate::pvss::{scrape_g1, Player};
+    use blstrs::Gt;
+    use group::Group;
+    use rand::thread_rng;
+
+    #[test]
+    fn tampered_partial_evals_do_not_verify() {
+        type T = scrape_g1::Transcript;
+
+        let (sc, mut rng) = test_utils::get_threshold_config_and_rng(3, 7);
+        let (pp, dks, eks, s, _) = test_utils::setup_dealing::<T>(&sc);
+        let (ssks, _) = test_utils::setup_signing::<T>(&sc);
+
+        let trx = T::deal(
+            &sc,
+            &pp,
+            &ssks[0],
+            &eks,
+            s,
+            &sc.get_player(0),
+            &DST_PVSS_TESTING_APP[..],
+            &mut rng,
+        );
+
+        let mut partials = (0..sc.get_threshold())
+            .map(|i| {
+                let p = sc.get_player(i);
+                let (sk_share, pk_share) = trx.decrypt_own_share(&sc, &p, &dks[i]);
+                let partial = T::eval_share(&pp, &sk_share, b"msg", &mut thread_rng());
+
+                assert!(T::verify_eval_share(&pp, &pk_share, b"msg", &partial));
+                assert!(!T::verify_eval_share(
+                    &pp,
+                    &pk_share,
+                    b"other msg",
+                    &partial
+                ));
+
+                (p, pk_share, partial)
+            })
+            .collect::<Vec<_>>();
+
+        let eval = T::combine(
+            &sc,
+            &partials
+                .iter()
+                .map(|(p, _, partial)| (p.clone(), partial.clone()))
+                .collect::<Vec<(Player, _)>>(),
+        );
+        let pk = trx.get_dealt_public_key();
+        assert!(T::verify_eval(&sc, &pp, &pk, b"msg", &partials, &eval));
+
+        // A partial evaluation that does not match its proof
+        partials[1].2.y += Gt::generator();
+        assert!(!T::verify_eval_share(
+            &pp,
+            &partials[1].1,
+            b"msg",
+            &partials[1].2
+        ));
+        assert!(!T::verify_eval(&sc, &pp, &pk, b"msg", &partials, &eval));
+    }
+}
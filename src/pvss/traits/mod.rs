@@ -1,11 +1,96 @@
 pub mod transcript;
 
 use crate::pvss::player::Player;
+use crate::pvss::WeightedConfig;
 use more_asserts::assert_lt;
-use std::fmt::Display;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
 
 pub use transcript::Transcript;
 
+/// The reasons why `Transcript::aggregate_with` can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AggregationError {
+    /// Both transcripts include a contribution from this dealer.
+    DuplicateDealer(Player),
+    /// One of the transcripts does not have the sizes required by the secret sharing config (see
+    /// `Transcript::has_valid_sizes`).
+    InvalidSizes,
+}
+
+impl Display for AggregationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregationError::DuplicateDealer(p) => {
+                write!(f, "dealer {} contributed to both transcripts", p.get_id())
+            }
+            AggregationError::InvalidSizes => {
+                write!(f, "transcript sizes do not match the secret sharing config")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AggregationError {}
+
+/// The reasons why `Reconstructable::try_reconstruct` can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReconstructionError {
+    /// The shares only add up to `got` out of the `needed` shares (or weight).
+    TooFewShares { got: usize, needed: usize },
+    /// This player contributed more than one share.
+    DuplicatePlayer(Player),
+    /// This player's ID is not smaller than the number of players in the secret sharing config.
+    PlayerOutOfRange(Player),
+    /// This player's share does not match the secret sharing config (e.g., a weighted player's share
+    /// does not have as many sub-shares as the player's weight).
+    MismatchedConfig(Player),
+}
+
+impl Display for ReconstructionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReconstructionError::TooFewShares { got, needed } => {
+                write!(f, "got {} shares, but need {} to reconstruct", got, needed)
+            }
+            ReconstructionError::DuplicatePlayer(p) => {
+                write!(f, "player {} contributed more than one share", p.get_id())
+            }
+            ReconstructionError::PlayerOutOfRange(p) => {
+                write!(f, "player {} is out of range", p.get_id())
+            }
+            ReconstructionError::MismatchedConfig(p) => {
+                write!(
+                    f,
+                    "player {}'s share does not match the secret sharing config",
+                    p.get_id()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReconstructionError {}
+
+/// Checks that the `players` are distinct and that their IDs are smaller than `n`.
+pub(crate) fn check_players<'a>(
+    n: usize,
+    players: impl IntoIterator<Item = &'a Player>,
+) -> Result<(), ReconstructionError> {
+    let mut seen = HashSet::new();
+    for p in players {
+        if p.id >= n {
+            return Err(ReconstructionError::PlayerOutOfRange(p.clone()));
+        }
+
+        if !seen.insert(p.id) {
+            return Err(ReconstructionError::DuplicatePlayer(p.clone()));
+        }
+    }
+
+    Ok(())
+}
+
 /// Converts a type `Self` to `D` using auxiliary data from type `W`.
 pub trait Convert<D, W> {
     fn to(&self, with: &W) -> D;
@@ -41,6 +126,18 @@ pub trait SecretSharingConfig: Display {
     fn get_total_num_players(&self) -> usize;
 
     fn get_total_num_shares(&self) -> usize;
+
+    /// Returns the number of shares that `player` gets: i.e., its weight, which is always 1 in an
+    /// unweighted config.
+    fn get_player_weight(&self, player: &Player) -> usize;
+}
+
+/// A secret sharing config where each player gets as many shares as its weight in an underlying
+/// `WeightedConfig`. Lets the same weighted PVSS (i.e., `Weighted`) be used with different ways of
+/// picking the weights and thresholds.
+pub trait WeightedSharingConfig: SecretSharingConfig {
+    /// Returns the weighted config used to deal and reconstruct.
+    fn get_weighted_config(&self) -> &WeightedConfig;
 }
 
 /// All dealt secret keys should be reconstructable from a subset of \[dealt secret key\] shares.
@@ -49,4 +146,57 @@ pub trait Reconstructable: IsSecretShareable {
 
     /// TODO: needs to be parameterized by the sharingconfig
     fn reconstruct(sc: &Self::SecretSharingConfig, shares: &Vec<(Player, Self::Share)>) -> Self;
+
+    /// Like `reconstruct`, but returns an error, rather than panicking, if the `shares` cannot be
+    /// reconstructed from (e.g., because they came over the network from misbehaving players).
+    fn try_reconstruct(
+        sc: &Self::SecretSharingConfig,
+        shares: &Vec<(Player, Self::Share)>,
+    ) -> Result<Self, ReconstructionError>
+    where
+        Self: Sized;
+}
+
+/// Dealt secret keys that can be reconstructed even when some of the shares are wrong.
+pub trait RobustlyReconstructable: Reconstructable {
+    /// Reconstructs the secret from the `shares` of distinct players, at most `max_errors` of which
+    /// may be wrong, by decoding them as a Reed-Solomon codeword (see
+    /// `reed_solomon::decode_in_exponent`). Returns the secret together with the players whose shares
+    /// are wrong, or `None` if there are too few shares, or too many wrong ones, to decode (or if
+    /// `try_reconstruct` would reject them).
+    ///
+    /// NOTE: Decoding tries every set of up to `max_errors` wrong shares, so, for $m$ shares, it
+    /// costs up to $\sum_{k \le max\_errors} \binom{m}{k}$ size-$m$ multiexps. Callers that have the
+    /// dealt public key shares should rather filter the shares via
+    /// `VerifiableShare::reconstruct_verified`.
+    fn reconstruct_robustly(
+        sc: &Self::SecretSharingConfig,
+        shares: &Vec<(Player, Self::Share)>,
+        max_errors: usize,
+    ) -> Option<(Self, Vec<Player>)>
+    where
+        Self: Sized,
+    {
+        let blocks = shares
+            .iter()
+            .map(|share| vec![share.clone()])
+            .collect::<Vec<Vec<(Player, Self::Share)>>>();
+        let (sk, errors) = Self::reconstruct_robustly_in_blocks(sc, &blocks, max_errors)?;
+
+        Some((
+            sk,
+            errors.into_iter().map(|i| shares[i].0.clone()).collect(),
+        ))
+    }
+
+    /// Like `reconstruct_robustly`, but for the shares of distinct players grouped into `blocks`,
+    /// where at most `max_errors` blocks may contain wrong shares. Returns the indices in `blocks` of
+    /// these blocks, each of which counts as a single error no matter how many wrong shares it has.
+    fn reconstruct_robustly_in_blocks(
+        sc: &Self::SecretSharingConfig,
+        blocks: &Vec<Vec<(Player, Self::Share)>>,
+        max_errors: usize,
+    ) -> Option<(Self, Vec<usize>)>
+    where
+        Self: Sized;
 }
@@ -36,7 +36,8 @@
 //! does not hold.
 
 use crate::pvss::traits::{
-    Convert, HasEncryptionPublicParams, IsSecretShareable, Reconstructable, SecretSharingConfig,
+    AggregationError, Convert, HasEncryptionPublicParams, IsSecretShareable, Reconstructable,
+    SecretSharingConfig,
 };
 use crate::pvss::Player;
 use aptos_crypto::{Uniform, ValidCryptoMaterial};
@@ -54,6 +55,10 @@ pub trait Transcript: ValidCryptoMaterial + Clone {
 
     type PvssPublicParameters: HasEncryptionPublicParams + Default;
 
+    /// The key a dealer signs its contribution to a transcript with.
+    type SigningSecretKey: Uniform;
+    type SigningPubKey: for<'a> From<&'a Self::SigningSecretKey>;
+
     type DealtSecretKeyShare: PartialEq + Clone;
     type DealtPubKeyShare;
     type DealtSecretKey: PartialEq
@@ -78,35 +83,65 @@ pub trait Transcript: ValidCryptoMaterial + Clone {
     fn scheme_name() -> String;
 
     /// Deals the *input secret* $s$ by creating a PVSS transcript which encrypts shares of $s$ for
-    /// all PVSS players.
+    /// all PVSS players. The transcript also includes a proof of knowledge (PoK) of $s$ that is bound
+    /// to the `dealer`'s identity and to `dst`, signed with the dealer's signing key `ssk`.
+    #[allow(clippy::too_many_arguments)]
     fn deal<R: rand_core::RngCore + rand_core::CryptoRng>(
         sc: &Self::SecretSharingConfig,
         pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
         eks: &Vec<Self::EncryptPubKey>,
         s: Self::InputSecret,
+        dealer: &Player,
         dst: &'static [u8],
         rng: &mut R,
     ) -> Self;
 
     /// Verifies the validity of the PVSS transcript: i.e., the transcripts correctly encrypts shares
     /// of an `InputSecret` $s$ which has been $(t, n)$ secret-shared such that only $\ge t$ players
-    /// can reconstruct it as a `DealtSecret`.
+    /// can reconstruct it as a `DealtSecret`. Also verifies the PoKs and signatures of all the
+    /// dealers who contributed to the transcript, where `spks[i]` is the signing public key of
+    /// dealer $i$.
     /// TODO: update comments
     fn verify(
         &self,
         sc: &Self::SecretSharingConfig,
         pp: &Self::PvssPublicParameters,
+        spks: &Vec<Self::SigningPubKey>,
         eks: &Vec<Self::EncryptPubKey>,
         dst: &'static [u8],
     ) -> bool;
 
-    /// Aggregates two transcripts.
-    fn aggregate_with(&mut self, sc: &Self::SecretSharingConfig, other: &Self);
+    /// Returns true if this transcript has as many commitments, encryptions, etc. as `sc` requires.
+    /// Unlike `verify`, this is cheap enough to check on unverified transcripts (e.g., before
+    /// aggregating them).
+    fn has_valid_sizes(&self, sc: &Self::SecretSharingConfig) -> bool;
+
+    /// Aggregates two transcripts. The aggregated transcript keeps the contributions (i.e., PoKs and
+    /// signatures) of the dealers of both. Fails, leaving `self` unchanged, if a dealer contributed
+    /// to both transcripts or if one of them does not have valid sizes.
+    fn aggregate_with(
+        &mut self,
+        sc: &Self::SecretSharingConfig,
+        other: &Self,
+    ) -> Result<(), AggregationError>;
+
+    /// Returns the dealers who contributed to this transcript (i.e., more than one, if it is an
+    /// aggregate of several transcripts).
+    fn get_dealers(&self) -> Vec<Player>;
 
     /// Given a valid transcript, returns the `DealtPublicKey` of that transcript: i.e., the public
     /// key associated with the secret key dealt in the transcript.
     fn get_dealt_public_key(&self) -> Self::DealtPubKey;
 
+    /// Given a valid transcript, returns the `DealtPubKeyShare` of the player with ID `player_id`,
+    /// which does not require its decryption key (unlike `decrypt_own_share`).
+    fn get_public_key_share(
+        &self,
+        sc: &Self::SecretSharingConfig,
+        player_id: &Player,
+    ) -> Self::DealtPubKeyShare;
+
     /// Given a valid transcript, returns the decrypted `DealtSecretShare` for the player with ID
     /// `player_id`.
     fn decrypt_own_share(
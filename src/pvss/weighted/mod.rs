@@ -1,4 +1,9 @@
+mod ramp_config;
+mod stake;
 mod weighted_config;
 mod weighting;
 
+pub use ramp_config::RampConfig;
+pub use stake::{StakeRoundingReport, WeightTarget};
 pub use weighted_config::WeightedConfig;
+pub use weighting::{Weighted, Wrapped};
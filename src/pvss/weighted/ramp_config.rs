@@ -0,0 +1,224 @@
+use crate::pvss::traits::{SecretSharingConfig, WeightedSharingConfig};
+use crate::pvss::weighted_analysis::analyze;
+use crate::pvss::{Player, StakeRoundingReport, WeightTarget, WeightedConfig};
+use more_asserts::assert_lt;
+use rand::seq::SliceRandom;
+use rand_core::{CryptoRng, RngCore};
+use std::fmt::{Display, Formatter};
+
+/// Encodes the configuration for a *ramp* (or dual-threshold) weighted PVSS: i.e., a secrecy
+/// threshold $a / b$ and a reconstruction threshold $c / d > a / b$, both as fractions of the total
+/// stake, such that:
+///
+///  - any subset of players with at most an $a / b$ fraction of the stake learns nothing about a
+///    dealt secret, and
+///  - any subset of players with at least a $c / d$ fraction of the stake can reconstruct it.
+///
+/// Subsets of players whose stake is in between may or may not be able to reconstruct, depending on
+/// how the stakes were rounded into the weights of the underlying `WeightedConfig`.
+pub struct RampConfig {
+    /// The weighted config that shares are actually dealt and reconstructed with.
+    wc: WeightedConfig,
+    /// Each player's stake
+    stakes: Vec<u64>,
+    /// The total stake $S$ of all players.
+    total_stake: u128,
+    /// The secrecy threshold $(a, b)$.
+    secrecy_threshold: (u64, u64),
+    /// The reconstruction threshold $(c, d)$.
+    reconstruction_threshold: (u64, u64),
+}
+
+impl RampConfig {
+    /// Creates a ramp config from a weighted config `wc`, checking (exactly, via
+    /// `weighted_analysis::analyze`) that no subset of players with at most a `secrecy_threshold`
+    /// fraction of the `stakes` can reconstruct, and that every subset with at least a
+    /// `reconstruction_threshold` fraction can.
+    ///
+    /// Panics if either threshold does not hold for `wc`, or if the secrecy threshold is not strictly
+    /// smaller than the reconstruction threshold.
+    pub fn new(
+        wc: WeightedConfig,
+        stakes: Vec<u64>,
+        secrecy_threshold: (u64, u64),
+        reconstruction_threshold: (u64, u64),
+    ) -> Self {
+        let (a, b) = secrecy_threshold;
+        let (c, d) = reconstruction_threshold;
+        // i.e., a / b < c / d
+        assert_lt!(
+            a as u128 * d as u128,
+            c as u128 * b as u128,
+            "secrecy threshold must be smaller than the reconstruction threshold"
+        );
+
+        let report = analyze(&stakes, &wc, secrecy_threshold, reconstruction_threshold);
+        assert!(
+            !report.breaks_secrecy,
+            "players with a secrecy-threshold fraction of the stake can reconstruct"
+        );
+        assert!(
+            !report.breaks_liveness,
+            "players with a reconstruction-threshold fraction of the stake cannot reconstruct"
+        );
+
+        RampConfig {
+            wc,
+            stakes,
+            total_stake: report.total_stake,
+            secrecy_threshold,
+            reconstruction_threshold,
+        }
+    }
+
+    /// Converts the players' `stakes` into weights via `WeightedConfig::from_stakes`, which picks the
+    /// threshold weight based on the `secrecy_threshold`, and checks that the rounding still lets any
+    /// subset with a `reconstruction_threshold` fraction of the stake reconstruct.
+    ///
+    /// Panics if it does not, in which case a larger `target` is needed.
+    pub fn from_stakes(
+        stakes: &[u64],
+        target: WeightTarget,
+        secrecy_threshold: (u64, u64),
+        reconstruction_threshold: (u64, u64),
+    ) -> (Self, StakeRoundingReport) {
+        let (wc, report) = WeightedConfig::from_stakes(stakes, target, secrecy_threshold);
+
+        (
+            RampConfig::new(
+                wc,
+                stakes.to_vec(),
+                secrecy_threshold,
+                reconstruction_threshold,
+            ),
+            report,
+        )
+    }
+
+    pub fn get_secrecy_threshold(&self) -> (u64, u64) {
+        self.secrecy_threshold
+    }
+
+    pub fn get_reconstruction_threshold(&self) -> (u64, u64) {
+        self.reconstruction_threshold
+    }
+
+    pub fn get_player_stake(&self, player: &Player) -> u64 {
+        self.stakes[player.id]
+    }
+
+    pub fn get_total_stake(&self) -> u128 {
+        self.total_stake
+    }
+
+    /// Returns true if the `players` have at least a reconstruction-threshold fraction of the stake
+    /// and are thus guaranteed to be able to reconstruct.
+    pub fn meets_reconstruction_threshold(&self, players: &[Player]) -> bool {
+        let stake = players
+            .iter()
+            .map(|p| self.stakes[p.id] as u128)
+            .sum::<u128>();
+        let (c, d) = self.reconstruction_threshold;
+
+        // i.e., stake / S >= c / d
+        stake * d as u128 >= self.total_stake * c as u128
+    }
+}
+
+impl Display for RampConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}-to-{}/{}-stake/{}/ramp",
+            self.secrecy_threshold.0,
+            self.secrecy_threshold.1,
+            self.reconstruction_threshold.0,
+            self.reconstruction_threshold.1,
+            self.wc
+        )
+    }
+}
+
+impl SecretSharingConfig for RampConfig {
+    /// Picks players in a random order until their combined stake reaches the reconstruction
+    /// threshold (rather than until their combined weight reaches the threshold weight, which could
+    /// happen earlier).
+    fn get_random_subset_of_capable_players<R>(&self, mut rng: &mut R) -> Vec<Player>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut player_ids = (0..self.stakes.len())
+            .filter(|&i| self.stakes[i] > 0)
+            .collect::<Vec<usize>>();
+        player_ids.shuffle(&mut rng);
+
+        let mut subset = vec![];
+        for i in player_ids {
+            if self.meets_reconstruction_threshold(&subset) {
+                break;
+            }
+
+            subset.push(self.get_player(i));
+        }
+
+        assert!(self.meets_reconstruction_threshold(&subset));
+        subset
+    }
+
+    fn get_total_num_players(&self) -> usize {
+        self.wc.get_total_num_players()
+    }
+
+    fn get_total_num_shares(&self) -> usize {
+        self.wc.get_total_num_shares()
+    }
+
+    fn get_player_weight(&self, player: &Player) -> usize {
+        self.wc.get_player_weight(player)
+    }
+}
+
+impl WeightedSharingConfig for RampConfig {
+    fn get_weighted_config(&self) -> &WeightedConfig {
+        &self.wc
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pvss::traits::{SecretSharingConfig, WeightedSharingConfig};
+    use crate::pvss::{RampConfig, WeightTarget, WeightedConfig};
+    use more_asserts::assert_ge;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn capable_subsets_meet_both_thresholds() {
+        let mut rng = thread_rng();
+
+        for _ in 0..10 {
+            let stakes = (0..20)
+                .map(|_| rng.gen_range(1_000_000u64, 100_000_000u64))
+                .collect::<Vec<u64>>();
+            let (rc, _) =
+                RampConfig::from_stakes(&stakes, WeightTarget::TotalWeight(200), (1, 3), (2, 3));
+
+            let subset = rc.get_random_subset_of_capable_players(&mut rng);
+            assert!(rc.meets_reconstruction_threshold(&subset));
+
+            let wc = rc.get_weighted_config();
+            let weight = subset
+                .iter()
+                .map(|p| wc.get_player_weight(p))
+                .sum::<usize>();
+            assert_ge!(weight, wc.get_threshold_weight());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_rejects_configs_that_break_reconstruction_threshold() {
+        // Players 1, 2 and 3 have 30% of the stake but only weight 1 < 2.
+        let wc = WeightedConfig::new(2, 4, vec![7, 0, 0, 1]);
+        RampConfig::new(wc, vec![70, 10, 10, 10], (1, 10), (1, 4));
+    }
+}
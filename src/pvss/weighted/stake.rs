@@ -0,0 +1,186 @@
+use crate::pvss::WeightedConfig;
+use more_asserts::{assert_gt, assert_le, assert_lt};
+
+/// How to pick the stake that one unit of weight stands for, when converting stakes into weights via
+/// `WeightedConfig::from_stakes`. Larger weights approximate stakes more closely, but make dealing,
+/// verifying and reconstructing more expensive, since the PVSS deals one share per unit of weight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightTarget {
+    /// The total weight $W$ of all players should be at most this.
+    TotalWeight(usize),
+    /// The weight of every player should be at most this.
+    MaxPlayerWeight(usize),
+}
+
+/// A report on the error introduced by rounding stakes down to weights in `WeightedConfig::from_stakes`.
+///
+/// All errors are expressed in units of weight: a player with stake $s_i$ "should" have weight
+/// $s_i / d$ (where $d$ is `stake_per_weight`) but gets $\lfloor s_i / d \rfloor$, so it loses
+/// $s_i / d - \lfloor s_i / d \rfloor < 1$ to rounding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StakeRoundingReport {
+    /// The stake $d$ that one unit of weight stands for.
+    pub stake_per_weight: u128,
+    /// The largest weight that a single player loses to rounding; always less than 1.
+    pub max_rounding_error: f64,
+    /// The total weight that all players lose to rounding; always less than $n$.
+    pub total_rounding_error: f64,
+    /// The number of players whose stake is smaller than $d$ and who thus get a weight of 0.
+    pub num_zero_weight_players: usize,
+    /// Any set of players with at least this fraction of the total stake is guaranteed to have
+    /// enough weight to reconstruct. This is always larger than the secrecy threshold, and the gap
+    /// between the two is the price of rounding. (If it exceeds 1, then some sets of players with
+    /// more stake than the secrecy threshold may never be able to reconstruct.)
+    pub liveness_stake_fraction: f64,
+}
+
+impl WeightedConfig {
+    /// Converts the players' `stakes` into weights, aiming for the given `target`, and picks the
+    /// threshold weight such that any set of players with at most a `secrecy_threshold` $=
+    /// (a, b)$ fraction $a / b$ of the total stake **cannot** reconstruct. Returns the resulting
+    /// config together with a report on how much rounding stakes into weights cost.
+    ///
+    /// Player $i$ with stake $s_i$ gets weight $\lfloor s_i / d \rfloor$, where the stake $d$ per
+    /// unit of weight is picked based on `target`. Since rounding down never increases weights, a
+    /// set $A$ with stake $\le (a / b) S$ has weight $\le \lfloor (a / b) S / d \rfloor$, where $S$
+    /// is the total stake. So, we set the threshold weight to $w = \lfloor (a / b) S / d \rfloor + 1$.
+    ///
+    /// Panics if the stakes are all zero, if the fraction is not in $(0, 1)$, or if the weights are
+    /// too coarse for any set of players to reach $w$ (in which case, a larger `target` is needed).
+    pub fn from_stakes(
+        stakes: &[u64],
+        target: WeightTarget,
+        secrecy_threshold: (u64, u64),
+    ) -> (WeightedConfig, StakeRoundingReport) {
+        let (a, b) = secrecy_threshold;
+        assert_gt!(a, 0);
+        assert_lt!(a, b);
+
+        let total_stake = stakes.iter().map(|&s| s as u128).sum::<u128>();
+        assert_gt!(total_stake, 0);
+
+        let d = match target {
+            WeightTarget::TotalWeight(total_weight) => {
+                assert_gt!(total_weight, 0);
+                div_ceil(total_stake, total_weight as u128)
+            }
+            WeightTarget::MaxPlayerWeight(max_weight) => {
+                assert_gt!(max_weight, 0);
+                div_ceil(*stakes.iter().max().unwrap() as u128, max_weight as u128)
+            }
+        }
+        .max(1);
+
+        let weights = stakes
+            .iter()
+            .map(|&s| (s as u128 / d) as usize)
+            .collect::<Vec<usize>>();
+        let total_weight = weights.iter().sum::<usize>();
+
+        // w = \lfloor (a S) / (b d) \rfloor + 1
+        let threshold_weight = (a as u128)
+            .checked_mul(total_stake)
+            .expect("total stake is too large")
+            .checked_div(b as u128 * d)
+            .unwrap() as usize
+            + 1;
+        assert_le!(
+            threshold_weight,
+            total_weight,
+            "weights are too coarse to reconstruct; use a larger weight target"
+        );
+
+        let errors = stakes
+            .iter()
+            .zip(weights.iter())
+            .map(|(&s, &w)| s as f64 / d as f64 - w as f64)
+            .collect::<Vec<f64>>();
+        let total_rounding_error = errors.iter().sum::<f64>();
+
+        // A set A has weight \ge stake(A) / d - (total rounding error), so it can reconstruct when
+        // stake(A) \ge d (w + total rounding error).
+        let liveness_stake_fraction =
+            d as f64 * (threshold_weight as f64 + total_rounding_error) / total_stake as f64;
+
+        let report = StakeRoundingReport {
+            stake_per_weight: d,
+            max_rounding_error: errors.iter().cloned().fold(0.0, f64::max),
+            total_rounding_error,
+            num_zero_weight_players: weights.iter().filter(|&&w| w == 0).count(),
+            liveness_stake_fraction,
+        };
+
+        (
+            WeightedConfig::new(threshold_weight, stakes.len(), weights),
+            report,
+        )
+    }
+}
+
+fn div_ceil(x: u128, y: u128) -> u128 {
+    (x + y - 1) / y
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pvss::weighted::stake::WeightTarget;
+    use crate::pvss::WeightedConfig;
+    use more_asserts::{assert_ge, assert_le, assert_lt};
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn from_stakes_preserves_secrecy_and_liveness_thresholds() {
+        let mut rng = thread_rng();
+        let n = 12;
+
+        for target in [
+            WeightTarget::TotalWeight(50),
+            WeightTarget::TotalWeight(200),
+            WeightTarget::MaxPlayerWeight(10),
+            WeightTarget::MaxPlayerWeight(40),
+        ] {
+            for (a, b) in [(1, 3), (1, 2), (2, 3)] {
+                let stakes = (0..n)
+                    .map(|_| rng.gen_range(1_000_000u64, 100_000_000u64))
+                    .collect::<Vec<u64>>();
+                let total_stake = stakes.iter().map(|&s| s as u128).sum::<u128>();
+
+                let (wc, report) = WeightedConfig::from_stakes(&stakes, target, (a, b));
+
+                match target {
+                    WeightTarget::TotalWeight(max) => assert_le!(wc.get_total_weight(), max),
+                    WeightTarget::MaxPlayerWeight(max) => {
+                        assert_le!(*wc.get_weights().iter().max().unwrap(), max)
+                    }
+                }
+                assert_lt!(report.max_rounding_error, 1.0);
+                assert_lt!(report.total_rounding_error, n as f64);
+
+                // Check all 2^n subsets of players
+                for set in 0..(1u32 << n) {
+                    let (stake, weight) = (0..n)
+                        .filter(|i| set & (1 << i) != 0)
+                        .fold((0u128, 0usize), |(s, w), i| {
+                            (s + stakes[i] as u128, w + wc.get_weights()[i])
+                        });
+
+                    if stake * b as u128 <= total_stake * a as u128 {
+                        assert_lt!(weight, wc.get_threshold_weight());
+                    }
+
+                    if stake as f64 >= report.liveness_stake_fraction * total_stake as f64 {
+                        assert_ge!(weight, wc.get_threshold_weight());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_stakes_rejects_too_coarse_weights() {
+        // The player with stake 10 gets weight 0 and the other one gets weight 1, so W = 1, but
+        // w = \lfloor (2 \cdot 29) / (3 \cdot 19) \rfloor + 1 = 2
+        WeightedConfig::from_stakes(&[10, 19], WeightTarget::MaxPlayerWeight(1), (2, 3));
+    }
+}
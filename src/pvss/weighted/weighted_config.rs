@@ -1,9 +1,11 @@
 use crate::algebra::evaluation_domain::{BatchEvaluationDomain, EvaluationDomain};
-use rand_core::{CryptoRng, RngCore};
-use std::fmt::{Display, Formatter};
-// use crate::algebra::evaluation_domain::{BatchEvaluationDomain, EvaluationDomain};
 use crate::pvss::traits::SecretSharingConfig;
 use crate::pvss::{traits, Player, ThresholdConfig};
+use more_asserts::{assert_ge, assert_lt};
+use rand::seq::SliceRandom;
+use rand_core::{CryptoRng, RngCore};
+use std::cmp::Reverse;
+use std::fmt::{Display, Formatter};
 
 /// Encodes the *threshold configuration* for a *weighted* PVSS: i.e., the minimum weight $w$ and
 /// the total weight $W$ such that any subset of players with weight $\ge w$ can reconstruct a
@@ -31,6 +33,7 @@ pub struct WeightedConfig {
 impl WeightedConfig {
     #[allow(non_snake_case)]
     pub fn new(w: usize, n: usize, weight: Vec<usize>) -> Self {
+        assert_eq!(weight.len(), n);
         let W = weight.iter().sum();
 
         // e.g., Suppose the weights for players 0, 1 and 2 are [2, 4, 3]
@@ -72,13 +75,56 @@ impl WeightedConfig {
         self.weight[player.id]
     }
 
+    /// Returns the weights of all players, where the $i$th entry is player $i$'s weight.
+    pub fn get_weights(&self) -> &Vec<usize> {
+        &self.weight
+    }
+
     /// In an unweighted secret sharing scheme, each player has one share. We can whey such a scheme
     /// by splitting a player into as many "virtual" players as that player's weight, assigning one
     /// share per "virtual player."
     ///
     /// This function returns the "virtual" player associated with the $i$th sub-share of this player.
     pub fn get_virtual_player(&self, player: &Player, i: usize) -> Player {
-        self.get_player(self.starting_index[player.id] + i)
+        assert_lt!(i, self.weight[player.id]);
+
+        // NOTE: Virtual players range over all $W$ shares, not over the $n$ players.
+        self.tc.get_player(self.starting_index[player.id] + i)
+    }
+
+    /// Picks a subset of the available `players` whose combined weight just meets the threshold
+    /// weight $w$, to reconstruct from as few Lagrange coefficients as possible.
+    ///
+    /// Picks the heaviest players first, which minimizes the number of players picked, and only uses
+    /// as many of the last picked player's virtual shares as needed to get exactly $w$ virtual shares.
+    /// Returns each picked player together with the number of its virtual shares to use, or `None` if
+    /// the combined weight of `players` is below $w$.
+    pub fn get_min_reconstructing_subset(
+        &self,
+        players: &[Player],
+    ) -> Option<Vec<(Player, usize)>> {
+        let mut by_weight = players.iter().collect::<Vec<&Player>>();
+        by_weight.sort_by_key(|p| Reverse(self.weight[p.id]));
+
+        let mut subset = vec![];
+        let mut remaining = self.tc.t;
+        for player in by_weight {
+            if remaining == 0 {
+                break;
+            }
+
+            let num_shares = self.weight[player.id].min(remaining);
+            if num_shares > 0 {
+                subset.push((player.clone(), num_shares));
+                remaining -= num_shares;
+            }
+        }
+
+        if remaining > 0 {
+            None
+        } else {
+            Some(subset)
+        }
     }
 
     pub fn get_batch_evaluation_domain(&self) -> &BatchEvaluationDomain {
@@ -101,18 +147,29 @@ impl Display for WeightedConfig {
 }
 
 impl traits::SecretSharingConfig for WeightedConfig {
-    fn get_random_subset_of_capable_players<R>(&self, mut _rng: &mut R) -> Vec<Player>
+    /// Picks players in a random order until their combined weight reaches the threshold weight $w$.
+    fn get_random_subset_of_capable_players<R>(&self, mut rng: &mut R) -> Vec<Player>
     where
         R: RngCore + CryptoRng,
     {
-        // (0..sc.get_total_num_shares())
-        //     .choose_multiple(&mut rng, self.t)
-        //     .into_iter()
-        //     .map(|i| {
-        //         sc.get_player(i)
-        //     })
-        //     .collect::<Vec<Player>>()
-        todo!()
+        let mut player_ids = (0..self.n)
+            .filter(|&i| self.weight[i] > 0)
+            .collect::<Vec<usize>>();
+        player_ids.shuffle(&mut rng);
+
+        let mut subset = vec![];
+        let mut subset_weight = 0;
+        for i in player_ids {
+            if subset_weight >= self.tc.t {
+                break;
+            }
+
+            subset_weight += self.weight[i];
+            subset.push(self.get_player(i));
+        }
+
+        assert_ge!(subset_weight, self.tc.t);
+        subset
     }
 
     fn get_total_num_players(&self) -> usize {
@@ -122,4 +179,51 @@ impl traits::SecretSharingConfig for WeightedConfig {
     fn get_total_num_shares(&self) -> usize {
         self.tc.n
     }
+
+    fn get_player_weight(&self, player: &Player) -> usize {
+        self.weight[player.id]
+    }
+}
+
+impl traits::WeightedSharingConfig for WeightedConfig {
+    fn get_weighted_config(&self) -> &WeightedConfig {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pvss::traits::SecretSharingConfig;
+    use crate::pvss::WeightedConfig;
+
+    #[test]
+    fn min_reconstructing_subset_uses_heaviest_players_and_exactly_w_shares() {
+        let wc = WeightedConfig::new(9, 5, vec![2, 5, 1, 4, 3]);
+        let players = (0..5).map(|i| wc.get_player(i)).collect::<Vec<_>>();
+
+        // Players 1 and 3 have weight 5 + 4 = 9
+        let subset = wc.get_min_reconstructing_subset(&players).unwrap();
+        assert_eq!(subset, vec![(wc.get_player(1), 5), (wc.get_player(3), 4)]);
+
+        // Without player 3, players 1 and 4 have weight 8, so we only need 1 of player 0's 2 shares
+        let available = vec![
+            wc.get_player(0),
+            wc.get_player(1),
+            wc.get_player(2),
+            wc.get_player(4),
+        ];
+        let subset = wc.get_min_reconstructing_subset(&available).unwrap();
+        assert_eq!(
+            subset,
+            vec![
+                (wc.get_player(1), 5),
+                (wc.get_player(4), 3),
+                (wc.get_player(0), 1)
+            ]
+        );
+
+        // Players 0, 2 and 4 only have weight 6
+        let available = vec![wc.get_player(0), wc.get_player(2), wc.get_player(4)];
+        assert_eq!(wc.get_min_reconstructing_subset(&available), None);
+    }
 }
@@ -1,52 +1,94 @@
+use crate::pvss::decryption_proof::VerifiableDecryption;
+use crate::pvss::refresh::Refreshable;
 use crate::pvss::traits::{
-    Convert, IsSecretShareable, Reconstructable, SecretSharingConfig, Transcript,
+    check_players, AggregationError, Convert, IsSecretShareable, Reconstructable,
+    ReconstructionError, RobustlyReconstructable, SecretSharingConfig, Transcript,
+    WeightedSharingConfig,
 };
 use crate::pvss::{Player, ThresholdConfig, WeightedConfig};
 use aptos_crypto::{CryptoMaterialError, Uniform, ValidCryptoMaterial};
 use aptos_crypto_derive::{SilentDebug, SilentDisplay};
 use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 /// A weighting wrapper around a `Transcript` type `T`. Given an implementation of an [unweighted
 /// PVSS] `Transcript` for `T`, this wrapper can be used to easily obtain a *weighted* PVSS abiding
 /// by the same `Transcript` trait.
-pub struct Weighted<T> {
+///
+/// The weighted secret sharing config type `C` defaults to a `WeightedConfig`, but can also be, e.g.,
+/// a `RampConfig`.
+pub struct Weighted<T, C = WeightedConfig> {
     trx: T,
+    #[serde(skip)]
+    _config: PhantomData<C>,
+}
+
+impl<T: Clone, C> Clone for Weighted<T, C> {
+    fn clone(&self) -> Self {
+        Weighted::new(self.trx.clone())
+    }
+}
+
+impl<T, C> Weighted<T, C> {
+    fn new(trx: T) -> Self {
+        Weighted {
+            trx,
+            _config: PhantomData,
+        }
+    }
 }
 
-#[derive(SilentDebug, SilentDisplay, PartialEq)]
+#[derive(SilentDebug, SilentDisplay)]
 /// Wrapper around a key, whether a `Transcript::DealtSecretKey`, a `Transcript::DealtSecretKeyShare`,
 /// or a `Transcript::InputSecret`. Helps us override the `Reconstructable` trait for a weighted
 /// dealt secret key, which is implemented as a `Wrapper<Transcript::DealtSecretKey>` an has a
 /// `Vec<Transcript::DealtSecretKeyShare>` as its associated `Share` type (via the `IsSecretShareable`
 /// trait).
-pub struct Wrapped<Key> {
+pub struct Wrapped<Key, C = WeightedConfig> {
     key: Key,
+    _config: PhantomData<C>,
 }
 
-impl<InputSecret: Uniform> Uniform for Wrapped<InputSecret> {
+impl<Key, C> Wrapped<Key, C> {
+    fn new(key: Key) -> Self {
+        Wrapped {
+            key,
+            _config: PhantomData,
+        }
+    }
+
+    pub(crate) fn get_key(&self) -> &Key {
+        &self.key
+    }
+}
+
+impl<Key: PartialEq, C> PartialEq for Wrapped<Key, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<InputSecret: Uniform, C> Uniform for Wrapped<InputSecret, C> {
     fn generate<R>(rng: &mut R) -> Self
     where
         R: RngCore + CryptoRng,
     {
-        Wrapped {
-            key: InputSecret::generate(rng),
-        }
+        Wrapped::new(InputSecret::generate(rng))
     }
 }
 
 /// Implements conversion from `Wrapped<T::InputSecret>` to `Wrapped<T::DealtSecretKey>` and
 /// `Wrapped<T::DealtPubKey>` where `T` is an unweighted `Transcript`.
-impl<InputSecret, Key, PublicParameters> Convert<Wrapped<Key>, PublicParameters>
-    for Wrapped<InputSecret>
+impl<InputSecret, Key, PublicParameters, C> Convert<Wrapped<Key, C>, PublicParameters>
+    for Wrapped<InputSecret, C>
 where
     InputSecret: Convert<Key, PublicParameters>,
 {
-    fn to(&self, with: &PublicParameters) -> Wrapped<Key> {
-        Wrapped {
-            key: self.key.to(with),
-        }
+    fn to(&self, with: &PublicParameters) -> Wrapped<Key, C> {
+        Wrapped::new(self.key.to(with))
     }
 }
 
@@ -56,50 +98,150 @@ where
 ///
 /// Associates `Vec<SK::Share>` as the dealt secret key share type of a `Wrapped<T::SK>`, where `T`
 /// is in an unweighted `Transcript`.
-impl<SK: IsSecretShareable> IsSecretShareable for Wrapped<SK> {
+impl<SK: IsSecretShareable, C> IsSecretShareable for Wrapped<SK, C> {
     type Share = Vec<SK::Share>;
 }
 
 /// Implements weighted reconstruction of a secret `Wrapped<SK>` through the existing unweighted
 /// reconstruction implementation of `SK`.
-impl<SK: IsSecretShareable + Reconstructable<SecretSharingConfig = ThresholdConfig>> Reconstructable
-    for Wrapped<SK>
+impl<SK, C> Reconstructable for Wrapped<SK, C>
+where
+    SK: IsSecretShareable + Reconstructable<SecretSharingConfig = ThresholdConfig>,
+    C: WeightedSharingConfig,
 {
-    type SecretSharingConfig = WeightedConfig;
+    type SecretSharingConfig = C;
+
+    fn reconstruct(sc: &C, shares: &Vec<(Player, Self::Share)>) -> Self {
+        Self::try_reconstruct(sc, shares).expect("could not reconstruct weighted secret")
+    }
+
+    /// Only reconstructs from the $w$ sub-shares picked by `WeightedConfig::get_min_reconstructing_subset`,
+    /// rather than from all the sub-shares in `shares`.
+    fn try_reconstruct(
+        sc: &C,
+        shares: &Vec<(Player, Self::Share)>,
+    ) -> Result<Self, ReconstructionError> {
+        let sc = sc.get_weighted_config();
+        check_players(
+            sc.get_total_num_players(),
+            shares.iter().map(|(player, _)| player),
+        )?;
+        if let Some((player, _)) = shares
+            .iter()
+            .find(|(player, sub_shares)| sub_shares.len() != sc.get_player_weight(player))
+        {
+            return Err(ReconstructionError::MismatchedConfig(player.clone()));
+        }
+
+        let players = shares
+            .iter()
+            .map(|(player, _)| player.clone())
+            .collect::<Vec<Player>>();
+        let subset = sc.get_min_reconstructing_subset(&players).ok_or_else(|| {
+            ReconstructionError::TooFewShares {
+                got: players.iter().map(|p| sc.get_player_weight(p)).sum(),
+                needed: sc.get_threshold_weight(),
+            }
+        })?;
+        let sub_shares_by_id = shares
+            .iter()
+            .map(|(player, sub_shares)| (player.get_id(), sub_shares))
+            .collect::<HashMap<usize, &Self::Share>>();
+
+        let mut flattened_shares = Vec::with_capacity(sc.get_threshold_weight());
 
-    fn reconstruct(sc: &Self::SecretSharingConfig, shares: &Vec<(Player, Self::Share)>) -> Self {
-        let mut flattened_shares = Vec::with_capacity(sc.get_total_weight());
+        for (player, num_shares) in subset {
+            let sub_shares = sub_shares_by_id[&player.get_id()];
 
-        for (player, sub_shares) in shares {
-            for (pos, share) in sub_shares.iter().enumerate() {
-                let virtual_player = sc.get_virtual_player(player, pos);
+            for (pos, share) in sub_shares.iter().take(num_shares).enumerate() {
+                let virtual_player = sc.get_virtual_player(&player, pos);
 
                 // TODO(Performance): Avoiding the cloning here might be nice
                 flattened_shares.push((virtual_player, (*share).clone()));
             }
         }
 
-        Wrapped {
-            key: SK::reconstruct(sc.get_threshold_config(), &flattened_shares),
+        SK::try_reconstruct(sc.get_threshold_config(), &flattened_shares).map(Wrapped::new)
+    }
+}
+
+/// Implements robust weighted reconstruction by decoding the sub-shares as the shares of the virtual
+/// players, where all the sub-shares of a block of players are decoded as one block (see
+/// `reed_solomon::decode_in_exponent`). A block is wrong if any of its sub-shares is wrong, or if one
+/// of its players does not have as many sub-shares as its weight. Via `reconstruct_robustly`, every
+/// player is a block of its own, so `max_errors` bounds the number of players with a wrong share,
+/// no matter how heavy they are.
+impl<SK, C> RobustlyReconstructable for Wrapped<SK, C>
+where
+    SK: IsSecretShareable + RobustlyReconstructable<SecretSharingConfig = ThresholdConfig>,
+    C: WeightedSharingConfig,
+{
+    fn reconstruct_robustly_in_blocks(
+        sc: &C,
+        blocks: &Vec<Vec<(Player, Self::Share)>>,
+        max_errors: usize,
+    ) -> Option<(Self, Vec<usize>)> {
+        let sc = sc.get_weighted_config();
+        check_players(
+            sc.get_total_num_players(),
+            blocks.iter().flatten().map(|(player, _)| player),
+        )
+        .ok()?;
+
+        let mut faulty = vec![];
+        // The index in `blocks` of every block of virtual players
+        let mut block_ids = vec![];
+        let mut virtual_blocks = vec![];
+        for (i, block) in blocks.iter().enumerate() {
+            if block
+                .iter()
+                .any(|(player, sub_shares)| sub_shares.len() != sc.get_player_weight(player))
+            {
+                faulty.push(i);
+                continue;
+            }
+
+            block_ids.push(i);
+            virtual_blocks.push(
+                block
+                    .iter()
+                    .flat_map(|(player, sub_shares)| {
+                        sub_shares.iter().enumerate().map(move |(pos, share)| {
+                            (sc.get_virtual_player(player, pos), share.clone())
+                        })
+                    })
+                    .collect::<Vec<(Player, SK::Share)>>(),
+            );
         }
+
+        let (sk, faulty_virtual_blocks) = SK::reconstruct_robustly_in_blocks(
+            sc.get_threshold_config(),
+            &virtual_blocks,
+            max_errors,
+        )?;
+
+        faulty.extend(faulty_virtual_blocks.into_iter().map(|k| block_ids[k]));
+        faulty.sort();
+
+        Some((Wrapped::new(sk), faulty))
     }
 }
 
-impl<T: Transcript> ValidCryptoMaterial for Weighted<T> {
+impl<T: Transcript, C> ValidCryptoMaterial for Weighted<T, C> {
     fn to_bytes(&self) -> Vec<u8> {
         self.trx.to_bytes()
     }
 }
 
-impl<T: Transcript> TryFrom<&[u8]> for Weighted<T> {
+impl<T: Transcript, C> TryFrom<&[u8]> for Weighted<T, C> {
     type Error = CryptoMaterialError;
 
     fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        T::try_from(bytes).map(|trx| Self { trx })
+        T::try_from(bytes).map(Weighted::new)
     }
 }
 
-impl<T: Transcript> Weighted<T> {
+impl<T: Transcript, C> Weighted<T, C> {
     fn to_weighted_encryption_keys(
         sc: &WeightedConfig,
         eks: &Vec<T::EncryptPubKey>,
@@ -120,17 +262,23 @@ impl<T: Transcript> Weighted<T> {
     }
 }
 
-impl<T: Transcript<SecretSharingConfig = ThresholdConfig>> Transcript for Weighted<T> {
-    type SecretSharingConfig = WeightedConfig;
+impl<T, C> Transcript for Weighted<T, C>
+where
+    T: Transcript<SecretSharingConfig = ThresholdConfig>,
+    C: WeightedSharingConfig,
+{
+    type SecretSharingConfig = C;
     type PvssPublicParameters = T::PvssPublicParameters;
+    type SigningSecretKey = T::SigningSecretKey;
+    type SigningPubKey = T::SigningPubKey;
 
     /// In a weighted PVSS, an SK share is represented as a vector of SK shares in the unweighted
     /// PVSS, whose size is proportional to the weight of the owning player.
     type DealtSecretKeyShare = Vec<T::DealtSecretKeyShare>;
     type DealtPubKeyShare = Vec<T::DealtPubKeyShare>;
-    type DealtSecretKey = Wrapped<T::DealtSecretKey>;
-    type DealtPubKey = Wrapped<T::DealtPubKey>;
-    type InputSecret = Wrapped<T::InputSecret>;
+    type DealtSecretKey = Wrapped<T::DealtSecretKey, C>;
+    type DealtPubKey = Wrapped<T::DealtPubKey, C>;
+    type InputSecret = Wrapped<T::InputSecret, C>;
     type EncryptPubKey = T::EncryptPubKey;
     type DecryptPrivKey = T::DecryptPrivKey;
 
@@ -141,51 +289,90 @@ impl<T: Transcript<SecretSharingConfig = ThresholdConfig>> Transcript for Weight
     fn deal<R: RngCore + CryptoRng>(
         sc: &Self::SecretSharingConfig,
         pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
         eks: &Vec<Self::EncryptPubKey>,
         s: Self::InputSecret,
+        dealer: &Player,
         dst: &'static [u8],
         rng: &mut R,
     ) -> Self {
-        let duplicated_eks = Weighted::<T>::to_weighted_encryption_keys(sc, eks);
+        let sc = sc.get_weighted_config();
+        let duplicated_eks = Self::to_weighted_encryption_keys(sc, eks);
 
-        Weighted {
-            trx: T::deal(
-                sc.get_threshold_config(),
-                pp,
-                &duplicated_eks,
-                s.key,
-                dst,
-                rng,
-            ),
-        }
+        Weighted::new(T::deal(
+            sc.get_threshold_config(),
+            pp,
+            ssk,
+            &duplicated_eks,
+            s.key,
+            dealer,
+            dst,
+            rng,
+        ))
     }
 
     fn verify(
         &self,
         sc: &Self::SecretSharingConfig,
         pp: &Self::PvssPublicParameters,
+        spks: &Vec<Self::SigningPubKey>,
         eks: &Vec<Self::EncryptPubKey>,
         dst: &'static [u8],
     ) -> bool {
-        let duplicated_eks = Weighted::<T>::to_weighted_encryption_keys(sc, eks);
+        let sc = sc.get_weighted_config();
+        let duplicated_eks = Self::to_weighted_encryption_keys(sc, eks);
 
         T::verify(
             &self.trx,
             sc.get_threshold_config(),
             pp,
+            spks,
             &duplicated_eks,
             dst,
         )
     }
 
-    fn aggregate_with(&mut self, sc: &Self::SecretSharingConfig, other: &Self) {
-        T::aggregate_with(&mut self.trx, sc.get_threshold_config(), &other.trx)
+    fn has_valid_sizes(&self, sc: &Self::SecretSharingConfig) -> bool {
+        T::has_valid_sizes(&self.trx, sc.get_weighted_config().get_threshold_config())
+    }
+
+    fn aggregate_with(
+        &mut self,
+        sc: &Self::SecretSharingConfig,
+        other: &Self,
+    ) -> Result<(), AggregationError> {
+        T::aggregate_with(
+            &mut self.trx,
+            sc.get_weighted_config().get_threshold_config(),
+            &other.trx,
+        )
+    }
+
+    fn get_dealers(&self) -> Vec<Player> {
+        T::get_dealers(&self.trx)
     }
 
     fn get_dealt_public_key(&self) -> Self::DealtPubKey {
-        Wrapped {
-            key: T::get_dealt_public_key(&self.trx),
-        }
+        Wrapped::new(T::get_dealt_public_key(&self.trx))
+    }
+
+    /// Returns the dealt public key shares of all of the player's virtual players.
+    fn get_public_key_share(
+        &self,
+        sc: &Self::SecretSharingConfig,
+        player_id: &Player,
+    ) -> Self::DealtPubKeyShare {
+        let sc = sc.get_weighted_config();
+
+        (0..sc.get_player_weight(player_id))
+            .map(|i| {
+                T::get_public_key_share(
+                    &self.trx,
+                    sc.get_threshold_config(),
+                    &sc.get_virtual_player(player_id, i),
+                )
+            })
+            .collect()
     }
 
     fn decrypt_own_share(
@@ -194,7 +381,8 @@ impl<T: Transcript<SecretSharingConfig = ThresholdConfig>> Transcript for Weight
         player_id: &Player, // TODO: could make Player keep track of its weight and avoid passing `Self::SecretSharingConfig`
         dk: &Self::DecryptPrivKey,
     ) -> (Self::DealtSecretKeyShare, Self::DealtPubKeyShare) {
-        let weight = sc.get_total_weight();
+        let sc = sc.get_weighted_config();
+        let weight = sc.get_player_weight(player_id);
 
         let mut weighted_dsk_share = Vec::with_capacity(weight);
         let mut weighted_dpk_share = Vec::with_capacity(weight);
@@ -214,8 +402,171 @@ impl<T: Transcript<SecretSharingConfig = ThresholdConfig>> Transcript for Weight
     where
         R: RngCore + CryptoRng,
     {
-        Weighted {
-            trx: T::generate(sc.get_threshold_config(), rng),
+        Weighted::new(T::generate(
+            sc.get_weighted_config().get_threshold_config(),
+            rng,
+        ))
+    }
+}
+
+impl<T, C> VerifiableDecryption for Weighted<T, C>
+where
+    T: VerifiableDecryption + Transcript<SecretSharingConfig = ThresholdConfig>,
+    C: WeightedSharingConfig,
+{
+    type DecryptionProof = Vec<T::DecryptionProof>;
+
+    /// Decrypts, and proves the decryption of, each of the player's sub-shares.
+    fn decrypt_own_share_with_proof<R>(
+        &self,
+        sc: &C,
+        pp: &Self::PvssPublicParameters,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+        rng: &mut R,
+    ) -> (
+        Self::DealtSecretKeyShare,
+        Self::DealtPubKeyShare,
+        Self::DecryptionProof,
+    )
+    where
+        R: RngCore + CryptoRng,
+    {
+        let sc = sc.get_weighted_config();
+        let weight = sc.get_player_weight(player_id);
+
+        let mut weighted_dsk_share = Vec::with_capacity(weight);
+        let mut weighted_dpk_share = Vec::with_capacity(weight);
+        let mut proofs = Vec::with_capacity(weight);
+
+        for i in 0..weight {
+            let virtual_player = sc.get_virtual_player(player_id, i);
+            let (dsk_share, dpk_share, proof) = self.trx.decrypt_own_share_with_proof(
+                sc.get_threshold_config(),
+                pp,
+                &virtual_player,
+                dk,
+                rng,
+            );
+            weighted_dsk_share.push(dsk_share);
+            weighted_dpk_share.push(dpk_share);
+            proofs.push(proof);
+        }
+
+        (weighted_dsk_share, weighted_dpk_share, proofs)
+    }
+
+    fn verify_decryption(
+        &self,
+        sc: &C,
+        pp: &Self::PvssPublicParameters,
+        player_id: &Player,
+        ek: &Self::EncryptPubKey,
+        sk_share: &Self::DealtSecretKeyShare,
+        proof: &Self::DecryptionProof,
+    ) -> bool {
+        let sc = sc.get_weighted_config();
+        if player_id.get_id() >= sc.get_total_num_players() {
+            return false;
         }
+
+        let weight = sc.get_player_weight(player_id);
+        sk_share.len() == weight
+            && proof.len() == weight
+            && (0..weight).all(|i| {
+                self.trx.verify_decryption(
+                    sc.get_threshold_config(),
+                    pp,
+                    &sc.get_virtual_player(player_id, i),
+                    ek,
+                    &sk_share[i],
+                    &proof[i],
+                )
+            })
+    }
+}
+
+impl<T, C> Refreshable for Weighted<T, C>
+where
+    T: Refreshable + Transcript<SecretSharingConfig = ThresholdConfig>,
+    C: WeightedSharingConfig,
+{
+    fn deal_refresh<R: RngCore + CryptoRng>(
+        sc: &C,
+        pp: &Self::PvssPublicParameters,
+        ssk: &Self::SigningSecretKey,
+        eks: &Vec<Self::EncryptPubKey>,
+        dealer: &Player,
+        dst: &'static [u8],
+        rng: &mut R,
+    ) -> Self {
+        let sc = sc.get_weighted_config();
+        let duplicated_eks = Self::to_weighted_encryption_keys(sc, eks);
+
+        Weighted::new(T::deal_refresh(
+            sc.get_threshold_config(),
+            pp,
+            ssk,
+            &duplicated_eks,
+            dealer,
+            dst,
+            rng,
+        ))
+    }
+
+    fn is_refresh(&self) -> bool {
+        self.trx.is_refresh()
+    }
+
+    /// Refreshes each of the player's sub-shares. Panics if `pk_share` does not have as many
+    /// sub-shares as the player's weight.
+    fn refresh_public_key_share(
+        &self,
+        sc: &C,
+        player_id: &Player,
+        pk_share: &Self::DealtPubKeyShare,
+    ) -> Self::DealtPubKeyShare {
+        let sc = sc.get_weighted_config();
+        assert_eq!(pk_share.len(), sc.get_player_weight(player_id));
+
+        pk_share
+            .iter()
+            .enumerate()
+            .map(|(i, pk)| {
+                self.trx.refresh_public_key_share(
+                    sc.get_threshold_config(),
+                    &sc.get_virtual_player(player_id, i),
+                    pk,
+                )
+            })
+            .collect()
+    }
+
+    /// Refreshes each of the player's sub-shares. Panics if `sk_share` or `pk_share` do not have as
+    /// many sub-shares as the player's weight.
+    fn apply_refresh(
+        &self,
+        sc: &C,
+        player_id: &Player,
+        dk: &Self::DecryptPrivKey,
+        sk_share: &Self::DealtSecretKeyShare,
+        pk_share: &Self::DealtPubKeyShare,
+    ) -> (Self::DealtSecretKeyShare, Self::DealtPubKeyShare) {
+        let sc = sc.get_weighted_config();
+        let weight = sc.get_player_weight(player_id);
+        assert_eq!(sk_share.len(), weight);
+        assert_eq!(pk_share.len(), weight);
+
+        (0..weight)
+            .map(|i| {
+                self.trx.apply_refresh(
+                    sc.get_threshold_config(),
+                    &sc.get_virtual_player(player_id, i),
+                    dk,
+                    &sk_share[i],
+                    &pk_share[i],
+                )
+            })
+            .unzip()
     }
 }
@@ -0,0 +1,228 @@
+//! # Security analysis of weighted threshold configurations
+//!
+//! A `WeightedConfig` is derived from the players' stakes by rounding (e.g., via
+//! `WeightedConfig::from_stakes`), so the sets of players who can reconstruct are not exactly the
+//! sets with enough stake. This module computes, exactly, how far apart the two are for a given stake
+//! distribution:
+//!
+//! 1. The largest stake held by a set of players who **cannot** reconstruct. If this is more than
+//!    the intended *liveness* threshold, some sets with enough stake are stuck.
+//! 2. The smallest stake held by a set of players who **can** reconstruct. If this is at most the
+//!    intended *secrecy* threshold, some sets with too little stake can learn the secret.
+//!
+//! Both are 0/1 knapsack problems over the players' weights, which we solve via dynamic programming
+//! in $O(n \cdot W)$ time, where $W$ is the total weight.
+
+use crate::pvss::traits::SecretSharingConfig;
+use crate::pvss::WeightedConfig;
+use more_asserts::{assert_ge, assert_le};
+
+/// The exact worst-case gap between stake and weight for a weighted config, together with whether it
+/// breaks the intended secrecy and liveness thresholds. See `analyze`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedSecurityReport {
+    /// The total stake $S$ of all players.
+    pub total_stake: u128,
+    /// The largest stake of any set of players whose combined weight is below the threshold weight
+    /// (i.e., who cannot reconstruct).
+    pub max_non_reconstructing_stake: u128,
+    /// The smallest stake of any set of players whose combined weight reaches the threshold weight
+    /// (i.e., who can reconstruct).
+    pub min_reconstructing_stake: u128,
+    /// True if some set of players with at most a secrecy-threshold fraction of the stake can
+    /// reconstruct.
+    pub breaks_secrecy: bool,
+    /// True if some set of players with at least a liveness-threshold fraction of the stake cannot
+    /// reconstruct.
+    pub breaks_liveness: bool,
+}
+
+impl WeightedSecurityReport {
+    /// The largest fraction of the total stake held by a set of players who cannot reconstruct.
+    pub fn max_non_reconstructing_stake_fraction(&self) -> f64 {
+        self.max_non_reconstructing_stake as f64 / self.total_stake as f64
+    }
+
+    /// The smallest fraction of the total stake held by a set of players who can reconstruct.
+    pub fn min_reconstructing_stake_fraction(&self) -> f64 {
+        self.min_reconstructing_stake as f64 / self.total_stake as f64
+    }
+
+    /// Returns true if the config breaks neither the secrecy threshold nor the liveness threshold.
+    pub fn is_secure(&self) -> bool {
+        !self.breaks_secrecy && !self.breaks_liveness
+    }
+}
+
+/// Analyzes the weighted config `wc` given the players' `stakes`, where `stakes[i]` is player $i$'s
+/// stake, against the intended thresholds:
+///
+///  - `secrecy_threshold` $= (a, b)$: no set of players with at most an $a / b$ fraction of the
+///    total stake should be able to reconstruct.
+///  - `liveness_threshold` $= (c, d)$: every set of players with at least a $c / d$ fraction of the
+///    total stake should be able to reconstruct.
+///
+/// Both thresholds must be fractions in $(0, 1]$.
+pub fn analyze(
+    stakes: &[u64],
+    wc: &WeightedConfig,
+    secrecy_threshold: (u64, u64),
+    liveness_threshold: (u64, u64),
+) -> WeightedSecurityReport {
+    assert_eq!(stakes.len(), wc.get_total_num_players());
+    for (num, den) in [secrecy_threshold, liveness_threshold] {
+        assert!(
+            0 < num && num <= den,
+            "thresholds must be fractions in (0, 1]"
+        );
+    }
+
+    let total_stake = stakes.iter().map(|&s| s as u128).sum::<u128>();
+    let w = wc.get_threshold_weight();
+    let total_weight = wc.get_total_weight();
+    assert_ge!(w, 1);
+    assert_le!(w, total_weight);
+
+    // A set can reconstruct if, and only if, its complement has weight at most W - w. So the smallest
+    // reconstructing stake is S minus the largest stake of a set with weight at most W - w.
+    let max_stake = max_stake_by_weight(stakes, wc.get_weights(), (w - 1).max(total_weight - w));
+
+    let max_non_reconstructing_stake = max_stake[w - 1];
+    let min_reconstructing_stake = total_stake - max_stake[total_weight - w];
+
+    // i.e., min_reconstructing_stake / S <= a / b
+    let (a, b) = secrecy_threshold;
+    let breaks_secrecy = min_reconstructing_stake * b as u128 <= total_stake * a as u128;
+
+    // i.e., max_non_reconstructing_stake / S >= c / d
+    let (c, d) = liveness_threshold;
+    let breaks_liveness = max_non_reconstructing_stake * d as u128 >= total_stake * c as u128;
+
+    WeightedSecurityReport {
+        total_stake,
+        max_non_reconstructing_stake,
+        min_reconstructing_stake,
+        breaks_secrecy,
+        breaks_liveness,
+    }
+}
+
+/// Returns a vector whose $c$th entry is the largest stake of any set of players whose combined
+/// weight is at most $c$, for all $c \in [0, \mathsf{cap}]$. This is the classic 0/1 knapsack DP.
+fn max_stake_by_weight(stakes: &[u64], weights: &Vec<usize>, cap: usize) -> Vec<u128> {
+    let mut max_stake = vec![0u128; cap + 1];
+
+    for (&s, &wt) in stakes.iter().zip(weights.iter()) {
+        if wt > cap {
+            continue;
+        }
+
+        // Iterate downwards so that each player is included at most once.
+        for c in (wt..=cap).rev() {
+            max_stake[c] = max_stake[c].max(max_stake[c - wt] + s as u128);
+        }
+    }
+
+    max_stake
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pvss::weighted_analysis::analyze;
+    use crate::pvss::{WeightTarget, WeightedConfig};
+    use more_asserts::assert_le;
+    use rand::{thread_rng, Rng};
+
+    /// Computes the report's stake bounds by going through all $2^n$ sets of players.
+    fn brute_force(stakes: &[u64], wc: &WeightedConfig) -> (u128, u128) {
+        let n = stakes.len();
+        let mut max_non_reconstructing = 0;
+        let mut min_reconstructing = u128::MAX;
+
+        for set in 0..(1u32 << n) {
+            let (stake, weight) = (0..n)
+                .filter(|i| set & (1 << i) != 0)
+                .fold((0u128, 0usize), |(s, w), i| {
+                    (s + stakes[i] as u128, w + wc.get_weights()[i])
+                });
+
+            if weight < wc.get_threshold_weight() {
+                max_non_reconstructing = max_non_reconstructing.max(stake);
+            } else {
+                min_reconstructing = min_reconstructing.min(stake);
+            }
+        }
+
+        (max_non_reconstructing, min_reconstructing)
+    }
+
+    #[test]
+    fn analyze_matches_brute_force() {
+        let mut rng = thread_rng();
+
+        for _ in 0..20 {
+            let n = rng.gen_range(1, 11);
+            let stakes = (0..n)
+                .map(|_| rng.gen_range(0u64, 1_000u64))
+                .collect::<Vec<u64>>();
+            let weights = (0..n)
+                .map(|_| rng.gen_range(0usize, 6usize))
+                .collect::<Vec<usize>>();
+            let total_weight = weights.iter().sum::<usize>();
+            if total_weight == 0 || stakes.iter().all(|&s| s == 0) {
+                continue;
+            }
+
+            let wc = WeightedConfig::new(rng.gen_range(1, total_weight + 1), n, weights);
+            let report = analyze(&stakes, &wc, (1, 3), (2, 3));
+
+            assert_eq!(
+                (
+                    report.max_non_reconstructing_stake,
+                    report.min_reconstructing_stake
+                ),
+                brute_force(&stakes, &wc)
+            );
+        }
+    }
+
+    #[test]
+    fn analyze_flags_broken_thresholds() {
+        // Equal weights for unequal stakes: the player with 70% of the stake cannot reconstruct
+        // alone, while the two players with 20% of the stake together can.
+        let stakes = [70, 10, 10, 10];
+        let wc = WeightedConfig::new(2, 4, vec![1, 1, 1, 1]);
+        let report = analyze(&stakes, &wc, (1, 3), (2, 3));
+
+        assert_eq!(report.max_non_reconstructing_stake, 70);
+        assert_eq!(report.min_reconstructing_stake, 20);
+        assert!(report.breaks_secrecy);
+        assert!(report.breaks_liveness);
+        assert!(!report.is_secure());
+
+        // Weights proportional to stakes
+        let wc = WeightedConfig::new(4, 4, vec![7, 1, 1, 1]);
+        let report = analyze(&stakes, &wc, (1, 3), (2, 3));
+        assert!(report.is_secure());
+    }
+
+    #[test]
+    fn from_stakes_never_breaks_secrecy() {
+        let mut rng = thread_rng();
+
+        for _ in 0..10 {
+            let stakes = (0..50)
+                .map(|_| rng.gen_range(1_000_000u64, 100_000_000u64))
+                .collect::<Vec<u64>>();
+            let (wc, rounding) =
+                WeightedConfig::from_stakes(&stakes, WeightTarget::TotalWeight(500), (1, 3));
+            let report = analyze(&stakes, &wc, (1, 3), (2, 3));
+
+            assert!(!report.breaks_secrecy);
+            assert_le!(
+                report.max_non_reconstructing_stake_fraction(),
+                rounding.liveness_stake_fraction
+            );
+        }
+    }
+}
@@ -10,7 +10,6 @@ pub(crate) fn append_g2_point(t: &mut merlin::Transcript, label: &'static [u8],
     t.append_message(label, p.to_compressed().as_slice())
 }
 
-#[allow(unused)]
 pub(crate) fn append_g1_vector(
     t: &mut merlin::Transcript,
     label: &'static [u8],
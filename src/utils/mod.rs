@@ -1,5 +1,6 @@
 use crate::SCALAR_FIELD_ORDER;
-use blstrs::Scalar;
+use blstrs::{G1Projective, G2Projective, Scalar};
+use group::Group;
 use num_bigint::BigUint;
 use num_integer::Integer;
 use sha3::Digest;
@@ -14,6 +15,34 @@ pub fn is_power_of_two(n: usize) -> bool {
     n != 0 && (n & (n - 1) == 0)
 }
 
+/// A group in which we can do multiexps and which we can serialize (e.g., into a Fiat-Shamir
+/// transcript). Lets us write code that is generic over $\mathbb{G}_1$ and $\mathbb{G}_2$.
+pub trait HasMultiExp: Group<Scalar = Scalar> {
+    fn multi_exp(bases: &[Self], scalars: &[Scalar]) -> Self;
+
+    fn to_compressed_bytes(&self) -> Vec<u8>;
+}
+
+impl HasMultiExp for G1Projective {
+    fn multi_exp(bases: &[Self], scalars: &[Scalar]) -> Self {
+        G1Projective::multi_exp(bases, scalars)
+    }
+
+    fn to_compressed_bytes(&self) -> Vec<u8> {
+        self.to_compressed().to_vec()
+    }
+}
+
+impl HasMultiExp for G2Projective {
+    fn multi_exp(bases: &[Self], scalars: &[Scalar]) -> Self {
+        G2Projective::multi_exp(bases, scalars)
+    }
+
+    fn to_compressed_bytes(&self) -> Vec<u8> {
+        self.to_compressed().to_vec()
+    }
+}
+
 /// Hashes the specified `msg` and domain separation tag `dst` into a `Scalar` by computing a 512-bit
 /// number as SHA3-512(SHA3-512(dst) || msg) and reducing it modulo the order of the field.
 /// (Same design as in `curve25519-dalek` explained here https://crypto.stackexchange.com/questions/88002/how-to-map-output-of-hash-algorithm-to-a-finite-field)
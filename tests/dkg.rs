@@ -0,0 +1,213 @@
+//! End-to-end testing of DKG sessions
+use aptos_crypto::{Uniform, ValidCryptoMaterial};
+use aptos_dkg::constants::DST_PVSS_TESTING_APP;
+use aptos_dkg::dkg::simulator::{ByzantineBehavior, Simulator};
+use aptos_dkg::dkg::{DkgConfig, DkgError, DkgSession};
+use aptos_dkg::pvss;
+use aptos_dkg::pvss::traits::transcript::Transcript;
+use aptos_dkg::pvss::traits::{Reconstructable, SecretSharingConfig, WeightedSharingConfig};
+use aptos_dkg::pvss::{
+    test_utils, RampConfig, ThresholdConfig, WeightTarget, Weighted, WeightedConfig,
+};
+use rand::thread_rng;
+use std::collections::BTreeSet;
+
+#[test]
+fn all_dkg_sessions() {
+    dkg_sessions::<pvss::scrape::Transcript>(ThresholdConfig::new(3, 5), 3);
+    dkg_sessions::<pvss::scrape_g1::Transcript>(ThresholdConfig::new(3, 5), 3);
+    dkg_sessions::<pvss::das::Transcript>(ThresholdConfig::new(3, 5), 3);
+
+    // The quorum is measured in weight for weighted configs
+    let wc = WeightedConfig::new(6, 5, vec![5, 1, 0, 2, 7]);
+    dkg_sessions::<Weighted<pvss::scrape::Transcript>>(wc, 6);
+
+    let (rc, _) = RampConfig::from_stakes(
+        &[50, 20, 15, 10, 5],
+        WeightTarget::TotalWeight(30),
+        (1, 3),
+        (2, 3),
+    );
+    let quorum = rc.get_weighted_config().get_threshold_weight();
+    dkg_sessions::<Weighted<pvss::scrape::Transcript, RampConfig>>(rc, quorum);
+}
+
+/// Runs a DKG where every player deals a transcript and every player receives all of them, in a
+/// different order. Checks that all players finalize to the same dealt public key and public key
+/// shares, and that their secret key shares reconstruct the same dealt secret key.
+fn dkg_sessions<T: Transcript>(sc: T::SecretSharingConfig, quorum: usize)
+where
+    T::DealtPubKey: PartialEq,
+    T::DealtPubKeyShare: PartialEq,
+{
+    let mut rng = thread_rng();
+    let n = sc.get_total_num_players();
+
+    let (pp, dks, eks, _, _) = test_utils::setup_dealing::<T>(&sc);
+    let (ssks, spks) = test_utils::setup_signing::<T>(&sc);
+    let config = DkgConfig::<T>::new(sc, pp, spks, eks, &DST_PVSS_TESTING_APP[..], quorum);
+    let sc = config.get_secret_sharing_config();
+
+    let mut sessions = dks
+        .into_iter()
+        .enumerate()
+        .map(|(i, dk)| DkgSession::new(&config, sc.get_player(i), dk))
+        .collect::<Vec<DkgSession<T>>>();
+    let trxs = (0..n)
+        .map(|i| sessions[i].deal(&ssks[i], T::InputSecret::generate(&mut rng), &mut rng))
+        .collect::<Vec<T>>();
+
+    // Invalid transcripts are rejected
+    let mut other = DkgSession::new(
+        &config,
+        sc.get_player(0),
+        T::DecryptPrivKey::generate(&mut rng),
+    );
+    assert_eq!(
+        other.receive_transcript(T::generate(sc, &mut rng)),
+        Err(DkgError::InvalidTranscript)
+    );
+    assert!(!other.has_quorum());
+    assert_eq!(
+        other.finalize().err(),
+        Some(DkgError::NoQuorum {
+            got: 0,
+            needed: quorum
+        })
+    );
+
+    for (i, session) in sessions.iter_mut().enumerate() {
+        let mut weight = 0;
+        for j in 0..n {
+            let k = (i + j) % n;
+            assert_eq!(session.receive_transcript(trxs[k].clone()), Ok(()));
+            weight += sc.get_player_weight(&sc.get_player(k));
+            assert_eq!(session.get_contributed_weight(), weight);
+            assert_eq!(session.has_quorum(), weight >= quorum);
+        }
+
+        // Duplicates are rejected
+        assert_eq!(
+            session.receive_transcript(trxs[i].clone()),
+            Err(DkgError::DuplicateDealer(sc.get_player(i)))
+        );
+        assert_eq!(session.get_contributors().len(), n);
+    }
+
+    let outputs = sessions
+        .into_iter()
+        .map(|session| session.finalize().unwrap())
+        .collect::<Vec<_>>();
+
+    for output in &outputs[1..] {
+        assert!(output.get_dealt_public_key() == outputs[0].get_dealt_public_key());
+        assert!(output.get_public_key_shares() == outputs[0].get_public_key_shares());
+        assert_eq!(output.get_dealers().len(), n);
+    }
+
+    let mut sks = vec![];
+    for _ in 0..2 {
+        let shares = sc
+            .get_random_subset_of_capable_players(&mut rng)
+            .into_iter()
+            .map(|p| {
+                let share = outputs[p.get_id()].get_secret_key_share().clone();
+                (p, share)
+            })
+            .collect::<Vec<_>>();
+
+        sks.push(T::DealtSecretKey::reconstruct(sc, &shares));
+    }
+    assert_eq!(sks[0], sks[1]);
+}
+
+#[test]
+fn simulated_dkgs() {
+    simulated_dkg::<pvss::scrape::Transcript>(|| ThresholdConfig::new(3, 7), 4);
+    simulated_dkg::<pvss::das::Transcript>(|| ThresholdConfig::new(3, 7), 4);
+    // Larger than any single player's weight, so that nobody finalizes on its own transcript
+    simulated_dkg::<Weighted<pvss::scrape::Transcript>>(
+        || WeightedConfig::new(6, 7, vec![5, 1, 0, 2, 7, 3, 1]),
+        10,
+    );
+}
+
+/// Simulates DKGs with 7 players over a faulty network, checking when the honest players agree.
+fn simulated_dkg<T: Transcript>(new_sc: impl Fn() -> T::SecretSharingConfig, quorum: usize)
+where
+    T::DealtPubKey: PartialEq,
+{
+    let sc = new_sc();
+    let dst = &DST_PVSS_TESTING_APP[..];
+    let new = |seed| Simulator::<T>::new(new_sc(), quorum, dst, seed);
+
+    // A delayed, but reliable, network with a crashed player and a malformed dealer
+    let mut sim = new(1);
+    sim.set_delays(1, 10);
+    sim.crash(&sc.get_player(2));
+    sim.make_byzantine(&sc.get_player(5), ByzantineBehavior::Malformed);
+    let honest = sim
+        .get_honest_players()
+        .iter()
+        .map(|p| p.get_id())
+        .collect::<BTreeSet<usize>>();
+    let report = sim.run();
+    assert!(report.honest_players_agree());
+    assert_eq!(report.get_num_dropped(), 0);
+    for &i in &honest {
+        let output = report.get_output(i).as_ref().unwrap();
+        let dealers = output
+            .get_dealers()
+            .iter()
+            .map(|p| p.get_id())
+            .collect::<BTreeSet<usize>>();
+        assert_eq!(honest, dealers);
+        assert!(report
+            .get_rejected(i)
+            .iter()
+            .all(|(from, _)| from.get_id() == 5));
+    }
+
+    // Replaying a seed gives the same outcome
+    let run = |seed| {
+        let mut sim = new(seed);
+        sim.set_delays(0, 20);
+        sim.set_drop_probability(0.2);
+        sim.set_deadline(15);
+        sim.run()
+    };
+    let (a, b) = (run(7), run(7));
+    assert_eq!(a.get_num_delivered(), b.get_num_delivered());
+    assert_eq!(a.get_num_dropped(), b.get_num_dropped());
+    for (i, output) in a.get_outputs() {
+        assert_eq!(output.is_ok(), b.get_output(*i).is_ok());
+        if let (Ok(x), Ok(y)) = (output, b.get_output(*i)) {
+            assert!(x.get_dealt_public_key() == y.get_dealt_public_key());
+            // ...down to the keys the transcripts were encrypted and signed under
+            assert_eq!(x.get_transcript().to_bytes(), y.get_transcript().to_bytes());
+        }
+    }
+
+    // An equivocating dealer splits the even- and odd-numbered players
+    let mut sim = new(2);
+    sim.make_byzantine(&sc.get_player(0), ByzantineBehavior::Equivocating);
+    let report = sim.run();
+    assert!(report.get_outputs().values().all(|output| output.is_ok()));
+    assert!(!report.honest_players_agree());
+
+    // Nobody gets a quorum if all messages are dropped
+    let mut sim = new(3);
+    sim.set_drop_probability(1.0);
+    let report = sim.run();
+    assert!(!report.honest_players_agree());
+    assert_eq!(report.get_num_delivered(), 7);
+    for (i, output) in report.get_outputs() {
+        assert_eq!(
+            output.as_ref().err(),
+            Some(&DkgError::NoQuorum {
+                got: sc.get_player_weight(&sc.get_player(*i)),
+                needed: quorum
+            })
+        );
+    }
+}
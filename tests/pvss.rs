@@ -1,13 +1,25 @@
 //! PVSS scheme-independent testing
+use aptos_crypto::Uniform;
 use aptos_dkg::constants::{
     BEST_CASE_N, BEST_CASE_THRESHOLD, DST_PVSS_TESTING_APP, G1_PROJ_NUM_BYTES, G2_PROJ_NUM_BYTES,
     SCALAR_NUM_BYTES, WORST_CASE_N, WORST_CASE_THRESHOLD,
 };
 use aptos_dkg::pvss;
+use aptos_dkg::pvss::decryption_proof::VerifiableDecryption;
+use aptos_dkg::pvss::encryption_dlog;
+use aptos_dkg::pvss::refresh::Refreshable;
+use aptos_dkg::pvss::share_verification::VerifiableShare;
+use aptos_dkg::pvss::threshold_vrf::ThresholdVrf;
 use aptos_dkg::pvss::traits::transcript::Transcript;
-use aptos_dkg::pvss::traits::{Reconstructable, SecretSharingConfig};
-use aptos_dkg::pvss::{scrape, test_utils};
-use aptos_dkg::pvss::{Player, ThresholdConfig};
+use aptos_dkg::pvss::traits::{
+    AggregationError, HasEncryptionPublicParams, Reconstructable, ReconstructionError,
+    RobustlyReconstructable, SecretSharingConfig,
+};
+use aptos_dkg::pvss::{das, dleq_scrape, scrape, scrape_g1, test_utils};
+use aptos_dkg::pvss::{
+    OptimisticAggregator, Player, RampConfig, ThresholdConfig, WeightTarget, Weighted,
+    WeightedConfig,
+};
 use rand::thread_rng;
 
 #[test]
@@ -16,6 +28,235 @@ fn all_pvss_bvt() {
     for sc in get_threshold_configs_for_testing() {
         pvss_bvt::<pvss::scrape::Transcript>(&sc);
     }
+
+    // DLEQ-SCRAPE unweighted
+    for sc in get_threshold_configs_for_testing() {
+        pvss_bvt::<pvss::dleq_scrape::Transcript>(&sc);
+    }
+
+    // Compact SCRAPE unweighted
+    for sc in get_threshold_configs_for_testing() {
+        pvss_bvt::<pvss::scrape::CompactTranscript>(&sc);
+    }
+
+    // SCRAPE with dealt secret keys in G1, unweighted
+    for sc in get_threshold_configs_for_testing() {
+        pvss_bvt::<pvss::scrape_g1::Transcript>(&sc);
+    }
+
+    // DAS unweighted
+    for sc in get_threshold_configs_for_testing() {
+        pvss_bvt::<pvss::das::Transcript>(&sc);
+    }
+
+    // SCRAPE weighted
+    for wc in get_weighted_configs_for_testing() {
+        pvss_bvt::<Weighted<pvss::scrape::Transcript>>(&wc);
+    }
+
+    // DLEQ-SCRAPE weighted
+    for wc in get_weighted_configs_for_testing() {
+        pvss_bvt::<Weighted<pvss::dleq_scrape::Transcript>>(&wc);
+    }
+
+    // SCRAPE with dealt secret keys in G1, weighted
+    for wc in get_weighted_configs_for_testing() {
+        pvss_bvt::<Weighted<pvss::scrape_g1::Transcript>>(&wc);
+    }
+
+    // SCRAPE with ramp sharing
+    for rc in get_ramp_configs_for_testing() {
+        pvss_bvt::<Weighted<pvss::scrape::Transcript, RampConfig>>(&rc);
+    }
+}
+
+/// Runs the generic test `$test` on every unweighted PVSS transcript type that the extension traits
+/// in `threshold_vrf`, `share_verification` and `refresh` are implemented for, and on its weighted
+/// version.
+macro_rules! for_all_extended_schemes {
+    ($test:ident) => {{
+        let sc = ThresholdConfig::new(3, 7);
+        $test::<pvss::scrape::Transcript>(&sc);
+        $test::<pvss::scrape::CompactTranscript>(&sc);
+        $test::<pvss::dleq_scrape::Transcript>(&sc);
+        $test::<pvss::scrape_g1::Transcript>(&sc);
+        $test::<pvss::das::Transcript>(&sc);
+
+        let wc = WeightedConfig::new(6, 5, vec![5, 1, 0, 2, 7]);
+        $test::<Weighted<pvss::scrape::Transcript>>(&wc);
+        $test::<Weighted<pvss::scrape::CompactTranscript>>(&wc);
+        $test::<Weighted<pvss::dleq_scrape::Transcript>>(&wc);
+        $test::<Weighted<pvss::scrape_g1::Transcript>>(&wc);
+        $test::<Weighted<pvss::das::Transcript>>(&wc);
+    }};
+}
+
+#[test]
+fn all_pvss_threshold_vrf() {
+    for_all_extended_schemes!(pvss_threshold_vrf);
+}
+
+#[test]
+fn all_pvss_share_verification() {
+    for_all_extended_schemes!(pvss_share_verification);
+}
+
+#[test]
+fn all_pvss_robust_reconstruction() {
+    for_all_extended_schemes!(pvss_robust_reconstruction);
+}
+
+#[test]
+fn all_pvss_refresh() {
+    for_all_extended_schemes!(pvss_refresh);
+}
+
+#[test]
+fn encryption_key_pops() {
+    use encryption_dlog::g2::EncryptPubKey;
+    use encryption_dlog::PopVerificationError;
+
+    let mut rng = thread_rng();
+    let sc = ThresholdConfig::new(3, 7);
+    let (pp, dks, mut eks, _, _) = test_utils::setup_dealing::<scrape::Transcript>(&sc);
+    let pp = pp.get_encryption_public_params();
+    let mut ctxs = (0..sc.get_total_num_players())
+        .map(|i| format!("validator {i}").into_bytes())
+        .collect::<Vec<Vec<u8>>>();
+
+    let mut pops = dks
+        .iter()
+        .zip(ctxs.iter())
+        .map(|(dk, ctx)| dk.prove_possession(pp, ctx, &mut rng))
+        .collect::<Vec<_>>();
+    for ((ek, ctx), pop) in eks.iter().zip(ctxs.iter()).zip(pops.iter()) {
+        assert!(ek.verify_possession(pp, ctx, pop));
+    }
+    assert_eq!(
+        EncryptPubKey::batch_verify_possession(pp, &eks, &ctxs, &pops),
+        Ok(())
+    );
+    assert_eq!(
+        EncryptPubKey::batch_verify_possession(pp, &[], &[], &[]),
+        Ok(())
+    );
+
+    // There must be one context and one PoP per encryption key
+    assert_eq!(
+        EncryptPubKey::batch_verify_possession(pp, &eks, &ctxs, &pops[1..]),
+        Err(PopVerificationError::MismatchedLengths {
+            eks: 7,
+            ctxs: 7,
+            pops: 6
+        })
+    );
+
+    // A PoP is only valid for the encryption key it was created for
+    pops.swap(1, 4);
+    assert!(!eks[1].verify_possession(pp, &ctxs[1], &pops[1]));
+    assert_eq!(
+        EncryptPubKey::batch_verify_possession(pp, &eks, &ctxs, &pops),
+        Err(PopVerificationError::InvalidPops(vec![1, 4]))
+    );
+    pops.swap(1, 4);
+
+    // ...and for the owner it was created for, so it cannot be replayed by someone else
+    assert!(!eks[1].verify_possession(pp, &ctxs[4], &pops[1]));
+    ctxs.swap(1, 4);
+    assert_eq!(
+        EncryptPubKey::batch_verify_possession(pp, &eks, &ctxs, &pops),
+        Err(PopVerificationError::InvalidPops(vec![1, 4]))
+    );
+    ctxs.swap(1, 4);
+
+    // A rogue key, picked as a function of another key, comes with no valid PoP
+    eks[2] = eks[3].clone();
+    assert_eq!(
+        EncryptPubKey::batch_verify_possession(pp, &eks, &ctxs, &pops),
+        Err(PopVerificationError::InvalidPops(vec![2]))
+    );
+
+    // Encryption keys in G1 get PoPs too
+    let (pp, dks, eks, _, _) = test_utils::setup_dealing::<scrape_g1::Transcript>(&sc);
+    let pp = pp.get_encryption_public_params();
+    let pop = dks[0].prove_possession(pp, &ctxs[0], &mut rng);
+    assert!(eks[0].verify_possession(pp, &ctxs[0], &pop));
+    assert!(!eks[1].verify_possession(pp, &ctxs[0], &pop));
+}
+
+#[test]
+fn try_reconstruct_rejects_bad_shares() {
+    type T = pvss::scrape::Transcript;
+    type W = Weighted<pvss::scrape::Transcript>;
+
+    let sc = ThresholdConfig::new(3, 7);
+    let (shares, sk) = deal_and_decrypt_all::<T>(&sc);
+    assert_eq!(
+        <T as Transcript>::DealtSecretKey::try_reconstruct(&sc, &shares),
+        Ok(sk)
+    );
+    assert_eq!(
+        <T as Transcript>::DealtSecretKey::try_reconstruct(&sc, &shares[..2].to_vec()),
+        Err(ReconstructionError::TooFewShares { got: 2, needed: 3 })
+    );
+
+    let mut duplicated = shares[..3].to_vec();
+    duplicated.push(shares[1].clone());
+    assert_eq!(
+        <T as Transcript>::DealtSecretKey::try_reconstruct(&sc, &duplicated),
+        Err(ReconstructionError::DuplicatePlayer(sc.get_player(1)))
+    );
+
+    // Shares for a config with more players
+    let small_sc = ThresholdConfig::new(3, 5);
+    assert_eq!(
+        <T as Transcript>::DealtSecretKey::try_reconstruct(&small_sc, &shares),
+        Err(ReconstructionError::PlayerOutOfRange(sc.get_player(5)))
+    );
+
+    let wc = WeightedConfig::new(6, 5, vec![5, 1, 0, 2, 7]);
+    let (mut shares, sk) = deal_and_decrypt_all::<W>(&wc);
+    assert_eq!(
+        <W as Transcript>::DealtSecretKey::try_reconstruct(&wc, &shares),
+        Ok(sk)
+    );
+    assert_eq!(
+        <W as Transcript>::DealtSecretKey::try_reconstruct(&wc, &shares[1..4].to_vec()),
+        Err(ReconstructionError::TooFewShares { got: 3, needed: 6 })
+    );
+
+    shares[3].1.pop();
+    assert_eq!(
+        <W as Transcript>::DealtSecretKey::try_reconstruct(&wc, &shares),
+        Err(ReconstructionError::MismatchedConfig(wc.get_player(3)))
+    );
+}
+
+#[test]
+fn all_pvss_decryption_proofs() {
+    let sc = ThresholdConfig::new(3, 7);
+    pvss_decryption_proofs::<pvss::scrape::Transcript>(&sc);
+    pvss_decryption_proofs::<pvss::dleq_scrape::Transcript>(&sc);
+    pvss_decryption_proofs::<pvss::scrape::CompactTranscript>(&sc);
+    pvss_decryption_proofs::<pvss::scrape_g1::Transcript>(&sc);
+    pvss_decryption_proofs::<pvss::das::Transcript>(&sc);
+
+    // A player with zero weight has no shares, so (trivially) any decryption proof of theirs verifies.
+    let wc = WeightedConfig::new(6, 4, vec![5, 1, 2, 7]);
+    pvss_decryption_proofs::<Weighted<pvss::scrape::Transcript>>(&wc);
+    pvss_decryption_proofs::<Weighted<pvss::das::Transcript>>(&wc);
+}
+
+#[test]
+fn all_pvss_optimistic_aggregation() {
+    let sc = ThresholdConfig::new(3, 7);
+    let short_sc = ThresholdConfig::new(3, 5);
+
+    pvss_optimistic_aggregation::<pvss::scrape::Transcript>(&sc, &short_sc);
+    pvss_optimistic_aggregation::<pvss::dleq_scrape::Transcript>(&sc, &short_sc);
+    pvss_optimistic_aggregation::<pvss::scrape::CompactTranscript>(&sc, &short_sc);
+    pvss_optimistic_aggregation::<pvss::scrape_g1::Transcript>(&sc, &short_sc);
+    pvss_optimistic_aggregation::<pvss::das::Transcript>(&sc, &short_sc);
 }
 
 #[test]
@@ -24,8 +265,77 @@ fn scrape_transcript_size() {
         (BEST_CASE_THRESHOLD, BEST_CASE_N),
         (WORST_CASE_THRESHOLD, WORST_CASE_N),
     ] {
-        transcript_size::<pvss::scrape::Transcript>(t, n);
-        expected_vanilla_scrape_transcript_size(t, n);
+        // F, A, \hat{Y}, the contributions and the (empty) SoKs
+        transcript_size::<pvss::scrape::Transcript>(
+            t,
+            n,
+            expected_vanilla_scrape_transcript_size(t, n),
+            &[t, n, n, 1, 0],
+        );
+    }
+}
+
+#[test]
+fn compact_scrape_transcript_size() {
+    for (t, n) in [
+        (BEST_CASE_THRESHOLD, BEST_CASE_N),
+        (WORST_CASE_THRESHOLD, WORST_CASE_N),
+    ] {
+        // A, \hat{Y}, the contributions and the (empty) SoKs
+        transcript_size::<pvss::scrape::CompactTranscript>(
+            t,
+            n,
+            expected_compact_scrape_transcript_size(t, n),
+            &[n, n, 1, 0],
+        );
+    }
+}
+
+#[test]
+fn dleq_scrape_transcript_size() {
+    for (t, n) in [
+        (BEST_CASE_THRESHOLD, BEST_CASE_N),
+        (WORST_CASE_THRESHOLD, WORST_CASE_N),
+    ] {
+        // The SCRAPE transcript's vectors, followed by the DLEQ proofs
+        transcript_size::<pvss::dleq_scrape::Transcript>(
+            t,
+            n,
+            expected_dleq_scrape_transcript_size(t, n),
+            &[t, n, n, 1, 0, n],
+        );
+    }
+}
+
+#[test]
+fn scrape_g1_transcript_size() {
+    for (t, n) in [
+        (BEST_CASE_THRESHOLD, BEST_CASE_N),
+        (WORST_CASE_THRESHOLD, WORST_CASE_N),
+    ] {
+        // A, Y and the contributions
+        transcript_size::<pvss::scrape_g1::Transcript>(
+            t,
+            n,
+            expected_scrape_g1_transcript_size(t, n),
+            &[n, n, 1],
+        );
+    }
+}
+
+#[test]
+fn das_transcript_size() {
+    for (t, n) in [
+        (BEST_CASE_THRESHOLD, BEST_CASE_N),
+        (WORST_CASE_THRESHOLD, WORST_CASE_N),
+    ] {
+        // V, C and the contributions
+        transcript_size::<pvss::das::Transcript>(
+            t,
+            n,
+            expected_das_transcript_size(t, n),
+            &[n + 1, n, 1],
+        );
     }
 }
 
@@ -45,19 +355,156 @@ fn get_threshold_configs_for_testing() -> Vec<ThresholdConfig> {
     scs
 }
 
+fn get_weighted_configs_for_testing() -> Vec<WeightedConfig> {
+    let mut wcs = vec![];
+
+    for weights in [
+        vec![3],
+        vec![1, 1, 1],
+        vec![2, 4, 3],
+        vec![5, 1, 0, 2, 7],
+        vec![1, 1, 1, 10, 2, 3],
+    ] {
+        let total_weight: usize = weights.iter().sum();
+
+        for w in 1..=total_weight {
+            wcs.push(WeightedConfig::new(w, weights.len(), weights.clone()));
+        }
+    }
+
+    wcs
+}
+
+fn get_ramp_configs_for_testing() -> Vec<RampConfig> {
+    let mut rcs = vec![];
+
+    for stakes in [
+        vec![10, 10, 10, 10],
+        vec![50, 20, 15, 10, 5],
+        vec![1_000, 3_000, 2_500, 700, 1_200, 4_100],
+    ] {
+        for (secrecy, reconstruction) in [((1, 3), (2, 3)), ((1, 2), (3, 4))] {
+            let (rc, _) = RampConfig::from_stakes(
+                &stakes,
+                WeightTarget::TotalWeight(30),
+                secrecy,
+                reconstruction,
+            );
+            rcs.push(rc);
+        }
+    }
+
+    rcs
+}
+
 fn pvss_bvt<T: Transcript>(sc: &T::SecretSharingConfig) {
     pvss_deal_verify_and_reconstruct::<T>(sc);
 }
 
+/// Has every player deal a transcript, with the ones in `bad` signing with the wrong key, and checks
+/// that optimistic aggregation returns a valid aggregate of the others and blames exactly `bad`.
+/// Transcripts dealt for `short_sc`, which has fewer players than `sc`, must be rejected.
+fn pvss_optimistic_aggregation<T: Transcript>(
+    sc: &T::SecretSharingConfig,
+    short_sc: &T::SecretSharingConfig,
+) {
+    let (pp, _, eks, _, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, spks) = test_utils::setup_signing::<T>(sc);
+    let n = sc.get_total_num_players();
+
+    let mut rng = thread_rng();
+    let trxs = (0..n)
+        .map(|i| {
+            T::deal(
+                &sc,
+                &pp,
+                &ssks[i],
+                &eks,
+                T::InputSecret::generate(&mut rng),
+                &sc.get_player(i),
+                &DST_PVSS_TESTING_APP[..],
+                &mut rng,
+            )
+        })
+        .collect::<Vec<T>>();
+
+    for bad in [
+        vec![],
+        vec![3],
+        vec![0, 4, 5],
+        (0..n).collect::<Vec<usize>>(),
+    ] {
+        let mut agg = OptimisticAggregator::<T>::new();
+
+        for i in 0..n {
+            let trx = if bad.contains(&i) {
+                T::deal(
+                    &sc,
+                    &pp,
+                    &ssks[(i + 1) % n],
+                    &eks,
+                    T::InputSecret::generate(&mut rng),
+                    &sc.get_player(i),
+                    &DST_PVSS_TESTING_APP[..],
+                    &mut rng,
+                )
+            } else {
+                trxs[i].clone()
+            };
+
+            agg.add(&sc, trx).unwrap();
+        }
+
+        // Dealer 0's own transcript is not kept out by a (forged) one claiming its ID that came
+        // first, while resending a valid transcript does not blame anyone
+        agg.add(&sc, trxs[0].clone()).unwrap();
+        assert_eq!(agg.get_inputs().len(), n + 1);
+
+        // A transcript with the wrong sizes is rejected, rather than making aggregation panic
+        assert_eq!(
+            agg.add(&sc, T::generate(short_sc, &mut rng)),
+            Err(AggregationError::InvalidSizes)
+        );
+        assert_eq!(agg.get_inputs().len(), n + 1);
+
+        let (valid, culprits) = agg.finish(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]);
+        assert_eq!(culprits, bad);
+
+        let valid = valid.unwrap();
+        assert!(valid.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
+
+        let mut dealers = valid
+            .get_dealers()
+            .iter()
+            .map(|p| p.get_id())
+            .collect::<Vec<usize>>();
+        dealers.sort_unstable();
+        let honest = (0..n)
+            .filter(|i| *i == 0 || !bad.contains(i))
+            .collect::<Vec<usize>>();
+        assert_eq!(dealers, honest);
+    }
+}
+
 /// 1. Deals a secret, creating a transcript
 /// 2. Verifies the transcript.
 /// 3. Ensures the a sufficiently-large random subset of the players can recover the dealt secret
 fn pvss_deal_verify_and_reconstruct<T: Transcript>(sc: &T::SecretSharingConfig) {
     let (pp, dks, eks, s, sk) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, spks) = test_utils::setup_signing::<T>(sc);
 
     let mut rng = thread_rng();
-    let trx = T::deal(&sc, &pp, &eks, s, &DST_PVSS_TESTING_APP[..], &mut rng);
-    assert!(trx.verify(&sc, &pp, &eks, &DST_PVSS_TESTING_APP[..]));
+    let trx = T::deal(
+        &sc,
+        &pp,
+        &ssks[0],
+        &eks,
+        s,
+        &sc.get_player(0),
+        &DST_PVSS_TESTING_APP[..],
+        &mut rng,
+    );
+    assert!(trx.verify(&sc, &pp, &spks, &eks, &DST_PVSS_TESTING_APP[..]));
 
     // Test reconstruction from t random shares
     let players_and_shares = sc
@@ -75,7 +522,308 @@ fn pvss_deal_verify_and_reconstruct<T: Transcript>(sc: &T::SecretSharingConfig)
     assert_eq!(sk, sk_reconstruct);
 }
 
-fn transcript_size<T: Transcript<SecretSharingConfig = ThresholdConfig>>(t: usize, n: usize) {
+/// Deals a secret and returns every player's decrypted share, together with the dealt secret key.
+fn deal_and_decrypt_all<T: Transcript>(
+    sc: &T::SecretSharingConfig,
+) -> (Vec<(Player, T::DealtSecretKeyShare)>, T::DealtSecretKey) {
+    let (pp, dks, eks, s, sk) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, _) = test_utils::setup_signing::<T>(sc);
+
+    let trx = T::deal(
+        &sc,
+        &pp,
+        &ssks[0],
+        &eks,
+        s,
+        &sc.get_player(0),
+        &DST_PVSS_TESTING_APP[..],
+        &mut thread_rng(),
+    );
+
+    let shares = (0..sc.get_total_num_players())
+        .map(|i| {
+            let p = sc.get_player(i);
+            let (sk_share, _) = trx.decrypt_own_share(&sc, &p, &dks[i]);
+
+            (p, sk_share)
+        })
+        .collect();
+
+    (shares, sk)
+}
+
+/// Deals a secret, swaps the shares of two players and checks that reconstructing, both by checking
+/// the shares against the dealt public key shares and by decoding them, still gives the dealt secret
+/// key and flags the two players.
+fn pvss_robust_reconstruction<T: VerifiableShare>(sc: &T::SecretSharingConfig)
+where
+    T::DealtSecretKey: RobustlyReconstructable,
+{
+    let (pp, dks, eks, s, sk) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, _) = test_utils::setup_signing::<T>(sc);
+
+    let mut rng = thread_rng();
+    let trx = T::deal(
+        &sc,
+        &pp,
+        &ssks[0],
+        &eks,
+        s,
+        &sc.get_player(0),
+        &DST_PVSS_TESTING_APP[..],
+        &mut rng,
+    );
+
+    let (mut shares, pk_shares): (
+        Vec<(Player, T::DealtSecretKeyShare)>,
+        Vec<T::DealtPubKeyShare>,
+    ) = (0..sc.get_total_num_players())
+        .map(|i| {
+            let p = sc.get_player(i);
+            let (sk_share, pk_share) = trx.decrypt_own_share(&sc, &p, &dks[i]);
+
+            ((p, sk_share), pk_share)
+        })
+        .unzip();
+
+    let (decoded, faulty) = T::DealtSecretKey::reconstruct_robustly(&sc, &shares, 2).unwrap();
+    assert_eq!(decoded, sk);
+    assert!(faulty.is_empty());
+
+    let tmp = shares[1].1.clone();
+    shares[1].1 = shares[3].1.clone();
+    shares[3].1 = tmp;
+    let culprits = vec![sc.get_player(1), sc.get_player(3)];
+
+    let (verified, faulty) = T::reconstruct_verified(&sc, &pp, &shares, &pk_shares).unwrap();
+    assert_eq!(verified, sk);
+    assert_eq!(faulty, culprits);
+
+    let (decoded, faulty) = T::DealtSecretKey::reconstruct_robustly(&sc, &shares, 2).unwrap();
+    assert_eq!(decoded, sk);
+    assert_eq!(faulty, culprits);
+}
+
+/// Deals a secret, has every player decrypt its share with a proof and checks that the proofs
+/// verify for the right shares only.
+fn pvss_decryption_proofs<T: VerifiableDecryption>(sc: &T::SecretSharingConfig) {
+    let (pp, dks, eks, s, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, _) = test_utils::setup_signing::<T>(sc);
+
+    let mut rng = thread_rng();
+    let trx = T::deal(
+        &sc,
+        &pp,
+        &ssks[0],
+        &eks,
+        s,
+        &sc.get_player(0),
+        &DST_PVSS_TESTING_APP[..],
+        &mut rng,
+    );
+
+    let n = sc.get_total_num_players();
+    let (sk_shares, proofs): (Vec<T::DealtSecretKeyShare>, Vec<T::DecryptionProof>) = (0..n)
+        .map(|i| {
+            let p = sc.get_player(i);
+            let (sk_share, _, proof) =
+                trx.decrypt_own_share_with_proof(&sc, &pp, &p, &dks[i], &mut rng);
+            assert!(sk_share == trx.decrypt_own_share(&sc, &p, &dks[i]).0);
+
+            (sk_share, proof)
+        })
+        .unzip();
+
+    for i in 0..n {
+        let p = sc.get_player(i);
+        assert!(trx.verify_decryption(&sc, &pp, &p, &eks[i], &sk_shares[i], &proofs[i]));
+
+        // The proof does not verify for another player's share, encryption key or identity
+        let j = (i + 1) % n;
+        assert!(!trx.verify_decryption(&sc, &pp, &p, &eks[i], &sk_shares[j], &proofs[i]));
+        assert!(!trx.verify_decryption(&sc, &pp, &p, &eks[j], &sk_shares[i], &proofs[i]));
+        assert!(!trx.verify_decryption(
+            &sc,
+            &pp,
+            &sc.get_player(j),
+            &eks[i],
+            &sk_shares[i],
+            &proofs[i]
+        ));
+    }
+}
+
+/// Deals a secret, has every player decrypt its share and checks that the shares verify against the
+/// dealt public key shares, both individually and in a batch, but not once two of them are swapped.
+fn pvss_share_verification<T: VerifiableShare>(sc: &T::SecretSharingConfig)
+where
+    T::DealtPubKeyShare: PartialEq,
+{
+    let (pp, dks, eks, s, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, _) = test_utils::setup_signing::<T>(sc);
+
+    let mut rng = thread_rng();
+    let trx = T::deal(
+        &sc,
+        &pp,
+        &ssks[0],
+        &eks,
+        s,
+        &sc.get_player(0),
+        &DST_PVSS_TESTING_APP[..],
+        &mut rng,
+    );
+
+    let (mut sk_shares, pk_shares): (Vec<T::DealtSecretKeyShare>, Vec<T::DealtPubKeyShare>) = (0
+        ..sc.get_total_num_players())
+        .map(|i| trx.decrypt_own_share(&sc, &sc.get_player(i), &dks[i]))
+        .unzip();
+
+    for (i, (pk, sk)) in pk_shares.iter().zip(sk_shares.iter()).enumerate() {
+        assert!(T::verify_share(&pp, pk, sk));
+        assert!(trx.get_public_key_share(&sc, &sc.get_player(i)) == *pk);
+    }
+    assert_eq!(T::batch_verify_shares(&pp, &pk_shares, &sk_shares), Ok(()));
+
+    sk_shares.swap(1, 3);
+    assert!(!T::verify_share(&pp, &pk_shares[1], &sk_shares[1]));
+    assert_eq!(
+        T::batch_verify_shares(&pp, &pk_shares, &sk_shares),
+        Err(vec![1, 3])
+    );
+}
+
+/// Deals a secret, refreshes every player's share with an aggregate of two refresh transcripts and
+/// checks that the refreshed shares (1) differ from the old ones, (2) verify against the refreshed
+/// dealt public key shares, (3) are the shares dealt by aggregating the original transcript with the
+/// refresh transcript and (4) still reconstruct the same dealt secret key.
+fn pvss_refresh<T: Refreshable + VerifiableShare>(sc: &T::SecretSharingConfig)
+where
+    T::DealtPubKey: PartialEq,
+    T::DealtPubKeyShare: PartialEq,
+{
+    let (pp, dks, eks, s, sk) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, spks) = test_utils::setup_signing::<T>(sc);
+    let dst = &DST_PVSS_TESTING_APP[..];
+
+    let mut rng = thread_rng();
+    let trx = T::deal(
+        &sc,
+        &pp,
+        &ssks[0],
+        &eks,
+        s,
+        &sc.get_player(0),
+        dst,
+        &mut rng,
+    );
+    assert!(!trx.is_refresh());
+    assert!(!trx.verify_refresh(&sc, &pp, &spks, &eks, dst));
+
+    let mut refresh = T::deal_refresh(&sc, &pp, &ssks[1], &eks, &sc.get_player(1), dst, &mut rng);
+    let other = T::deal_refresh(&sc, &pp, &ssks[2], &eks, &sc.get_player(2), dst, &mut rng);
+    refresh.aggregate_with(&sc, &other).unwrap();
+    assert!(refresh.verify_refresh(&sc, &pp, &spks, &eks, dst));
+
+    // A refresh transcript aggregated with a non-zero one no longer deals zero.
+    let mut refreshed = trx.clone();
+    refreshed.aggregate_with(&sc, &refresh).unwrap();
+    assert!(refreshed.verify(&sc, &pp, &spks, &eks, dst));
+    assert!(!refreshed.verify_refresh(&sc, &pp, &spks, &eks, dst));
+    assert!(refreshed.get_dealt_public_key() == trx.get_dealt_public_key());
+
+    let mut old_shares = vec![];
+    let mut new_shares = vec![];
+    for i in 0..sc.get_total_num_players() {
+        let p = sc.get_player(i);
+        let (sk_share, pk_share) = trx.decrypt_own_share(&sc, &p, &dks[i]);
+        let (new_sk_share, new_pk_share) =
+            refresh.apply_refresh(&sc, &p, &dks[i], &sk_share, &pk_share);
+
+        assert!(T::verify_share(&pp, &new_pk_share, &new_sk_share));
+        assert!(refresh.refresh_public_key_share(&sc, &p, &pk_share) == new_pk_share);
+        assert!(
+            refreshed.decrypt_own_share(&sc, &p, &dks[i]) == (new_sk_share.clone(), new_pk_share)
+        );
+
+        old_shares.push((p.clone(), sk_share));
+        new_shares.push((p, new_sk_share));
+    }
+    assert!(old_shares != new_shares);
+
+    let shares = sc
+        .get_random_subset_of_capable_players(&mut rng)
+        .into_iter()
+        .map(|p| new_shares[p.get_id()].clone())
+        .collect::<Vec<(Player, T::DealtSecretKeyShare)>>();
+    assert_eq!(T::DealtSecretKey::reconstruct(&sc, &shares), sk);
+}
+
+/// Deals a secret, has two random capable subsets of players evaluate the threshold VRF on it and
+/// checks that both combine into the same output, which verifies against the dealt public key.
+fn pvss_threshold_vrf<T: ThresholdVrf>(sc: &T::SecretSharingConfig) {
+    let (pp, dks, eks, s, _) = test_utils::setup_dealing::<T>(sc);
+    let (ssks, _) = test_utils::setup_signing::<T>(sc);
+
+    let mut rng = thread_rng();
+    let trx = T::deal(
+        &sc,
+        &pp,
+        &ssks[0],
+        &eks,
+        s,
+        &sc.get_player(0),
+        &DST_PVSS_TESTING_APP[..],
+        &mut rng,
+    );
+    let pk = trx.get_dealt_public_key();
+    let msg = b"some VRF input";
+
+    let mut evals = vec![];
+    for _ in 0..2 {
+        let partials = sc
+            .get_random_subset_of_capable_players(&mut rng)
+            .into_iter()
+            .map(|p| {
+                let (sk_share, pk_share) = trx.decrypt_own_share(&sc, &p, &dks[p.get_id()]);
+                let partial = T::eval_share(&pp, &sk_share, msg, &mut rng);
+                assert!(T::verify_eval_share(&pp, &pk_share, msg, &partial));
+
+                (p, pk_share, partial)
+            })
+            .collect::<Vec<(Player, T::DealtPubKeyShare, T::PartialEval)>>();
+
+        let eval = T::combine(
+            &sc,
+            &partials
+                .iter()
+                .map(|(p, _, partial)| (p.clone(), partial.clone()))
+                .collect::<Vec<(Player, T::PartialEval)>>(),
+        );
+        assert!(T::verify_eval(&sc, &pp, &pk, msg, &partials, &eval));
+        assert!(!T::verify_eval(
+            &sc,
+            &pp,
+            &pk,
+            b"other VRF input",
+            &partials,
+            &eval
+        ));
+
+        evals.push(eval);
+    }
+
+    assert_eq!(evals[0], evals[1]);
+}
+
+/// Checks that a `T` transcript for a `t`-out-of-`n` config serializes to `expected_size` bytes, plus
+/// the BCS length prefixes of its vectors, which have `vec_lens` elements each.
+fn transcript_size<T: Transcript<SecretSharingConfig = ThresholdConfig>>(
+    t: usize,
+    n: usize,
+    expected_size: usize,
+    vec_lens: &[usize],
+) {
     let (sc, mut rng) = test_utils::get_threshold_config_and_rng(t, n);
 
     let trx = T::generate(&sc, &mut rng);
@@ -86,24 +834,57 @@ fn transcript_size<T: Transcript<SecretSharingConfig = ThresholdConfig>>(t: usiz
     // output from `expected_*_transcript_size` calls, which print the same thing but start with
     // "expected."
     println!("Actual   transcript size for {t}-out-of-{n} {name}: {actual_size} bytes");
+
+    let prefixes_size = vec_lens
+        .iter()
+        .map(|len| bcs_length_prefix_size(*len))
+        .sum::<usize>();
+    assert_eq!(actual_size, expected_size + prefixes_size);
+}
+
+/// The number of bytes BCS uses to encode the length of a vector with `len` elements (i.e., the size
+/// of `len` as a ULEB128).
+fn bcs_length_prefix_size(len: usize) -> usize {
+    let mut size = 1;
+    let mut len = len >> 7;
+    while len > 0 {
+        size += 1;
+        len >>= 7;
+    }
+    size
 }
 
 fn expected_vanilla_scrape_transcript_size(t: usize, n: usize) -> usize {
     let name = scrape::Transcript::scheme_name();
 
-    let expected_size =
-        G2_PROJ_NUM_BYTES + n * (G2_PROJ_NUM_BYTES + G1_PROJ_NUM_BYTES) + t * G1_PROJ_NUM_BYTES;
+    let expected_size = G2_PROJ_NUM_BYTES
+        + n * (G2_PROJ_NUM_BYTES + G1_PROJ_NUM_BYTES)
+        + t * G1_PROJ_NUM_BYTES
+        + expected_contribution_size();
+
+    println!("Expected transcript size for {t}-out-of-{n} {name}: {expected_size} bytes");
+    expected_size
+}
+
+fn expected_compact_scrape_transcript_size(t: usize, n: usize) -> usize {
+    let name = scrape::CompactTranscript::scheme_name();
+
+    let expected_size = G2_PROJ_NUM_BYTES
+        + n * (G2_PROJ_NUM_BYTES + G1_PROJ_NUM_BYTES)
+        + G1_PROJ_NUM_BYTES
+        + expected_contribution_size();
 
     println!("Expected transcript size for {t}-out-of-{n} {name}: {expected_size} bytes");
     expected_size
 }
 
-#[allow(unused)]
 fn expected_dleq_scrape_transcript_size(t: usize, n: usize) -> usize {
-    let name = "DLEQ-SCRAPE"; // TODO: change to function call once updated
+    let name = dleq_scrape::Transcript::scheme_name();
 
-    let vanilla_expected_size =
-        G2_PROJ_NUM_BYTES + n * (G2_PROJ_NUM_BYTES + G1_PROJ_NUM_BYTES) + t * G1_PROJ_NUM_BYTES;
+    let vanilla_expected_size = G2_PROJ_NUM_BYTES
+        + n * (G2_PROJ_NUM_BYTES + G1_PROJ_NUM_BYTES)
+        + t * G1_PROJ_NUM_BYTES
+        + expected_contribution_size();
 
     let expected_size =
         vanilla_expected_size + n * (G2_PROJ_NUM_BYTES + G1_PROJ_NUM_BYTES + SCALAR_NUM_BYTES);
@@ -111,3 +892,34 @@ fn expected_dleq_scrape_transcript_size(t: usize, n: usize) -> usize {
     println!("Expected transcript size for {t}-out-of-{n} {name}: {expected_size} bytes");
     expected_size
 }
+
+fn expected_scrape_g1_transcript_size(t: usize, n: usize) -> usize {
+    let name = scrape_g1::Transcript::scheme_name();
+
+    let expected_size = G1_PROJ_NUM_BYTES
+        + G2_PROJ_NUM_BYTES
+        + n * (G2_PROJ_NUM_BYTES + G1_PROJ_NUM_BYTES)
+        + expected_contribution_size();
+
+    println!("Expected transcript size for {t}-out-of-{n} {name}: {expected_size} bytes");
+    expected_size
+}
+
+fn expected_das_transcript_size(t: usize, n: usize) -> usize {
+    let name = das::Transcript::scheme_name();
+
+    let expected_size = G2_PROJ_NUM_BYTES
+        + (n + 1) * G2_PROJ_NUM_BYTES
+        + n * G1_PROJ_NUM_BYTES
+        + G1_PROJ_NUM_BYTES
+        + expected_contribution_size();
+
+    println!("Expected transcript size for {t}-out-of-{n} {name}: {expected_size} bytes");
+    expected_size
+}
+
+/// The size of a single dealer's contribution: its player ID, its commitment, its Schnorr PoK and its
+/// BLS signature (serialized as a length-prefixed byte vector).
+fn expected_contribution_size() -> usize {
+    8 + 2 * G1_PROJ_NUM_BYTES + SCALAR_NUM_BYTES + (1 + G2_PROJ_NUM_BYTES)
+}